@@ -304,6 +304,13 @@ impl<ComponentKey: Key + Hash, PK: InternerKey<ImplKey = Range<usize>>>
         self.sequences.max_sequence_len()
     }
 
+    /// Estimate of the heap memory used by interned paths, in bytes
+    pub fn memory_footprint(&self) -> usize {
+        self.components.current_memory_usage()
+            + self.num_components() * std::mem::size_of::<ComponentKey>()
+            + self.len() * std::mem::size_of::<PK>()
+    }
+
     /// Finalize the collection of paths, keeping all keys valid
     pub fn finalize(self) -> InternedPaths<ComponentKey, PK> {
         InternedPaths {
@@ -0,0 +1,253 @@
+//! Binary on-disk cache of parsed time-traces
+//!
+//! Parsing the JSON output of -ftime-trace is slow for large traces, so a
+//! parsed trace can be saved next to its source file and reloaded on later
+//! runs. Cached data is keyed by the size and modification time of the source
+//! file, so that it is ignored once the source file changes.
+
+use crate::{tree::ActivityTree, ClangTrace, Duration, GlobalStat, Pid, ProcessMetadata, Tid};
+use cpparser::EntityParser;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::SystemTime,
+};
+use thiserror::Error;
+
+/// Extension that is appended to a trace's file name to get its cache
+const CACHE_EXTENSION: &str = "crofiler-cache";
+
+impl ClangTrace {
+    /// Location of the cache associated with a certain time-trace file
+    ///
+    /// This is a sibling of the trace file, with `.crofiler-cache` appended
+    /// to its name.
+    ///
+    pub fn cache_path(path: impl AsRef<Path>) -> PathBuf {
+        let mut cache_path = OsString::from(path.as_ref());
+        cache_path.push(".");
+        cache_path.push(CACHE_EXTENSION);
+        cache_path.into()
+    }
+
+    /// Save this trace into the cache of the time-trace file it was loaded from
+    ///
+    /// `path` is the path to the time-trace file, not to the cache.
+    ///
+    pub fn save_cache(&self, path: impl AsRef<Path>) -> Result<(), ClangTraceCacheError> {
+        let path = path.as_ref();
+        let key = CacheKey::new(path)?;
+        let mut output = BufWriter::new(File::create(Self::cache_path(path))?);
+        ciborium::into_writer(&key, &mut output)?;
+        ciborium::into_writer(
+            &(
+                &self.activities,
+                &self.global_stats,
+                &self.process_name,
+                &self.process_metadata,
+                &self.pid,
+                &self.thread_names,
+                &self.beginning_of_time,
+            ),
+            &mut output,
+        )?;
+        output.flush()?;
+        Ok(())
+    }
+
+    /// Load a trace from the cache of a time-trace file
+    ///
+    /// `path` is the path to the time-trace file, not to the cache. None is
+    /// returned if there is no cache, or if the trace file has changed since
+    /// the cache was saved.
+    ///
+    /// C++ entities are parsed lazily and are thus not part of the cache.
+    ///
+    pub fn load_cache(path: impl AsRef<Path>) -> Result<Option<Self>, ClangTraceCacheError> {
+        let path = path.as_ref();
+        let cache = match File::open(Self::cache_path(path)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut input = BufReader::new(cache);
+        if ciborium::from_reader::<CacheKey, _>(&mut input)? != CacheKey::new(path)? {
+            return Ok(None);
+        }
+        let (
+            activities,
+            global_stats,
+            process_name,
+            process_metadata,
+            pid,
+            thread_names,
+            beginning_of_time,
+        ): CacheContents = ciborium::from_reader(&mut input)?;
+        Ok(Some(Self {
+            activities,
+            entities: Rc::new(EntityParser::new()),
+            demangling_buf: String::new(),
+            parsed_arguments: 0,
+            global_stats,
+            process_name,
+            process_metadata,
+            pid,
+            thread_names,
+            beginning_of_time,
+        }))
+    }
+
+    /// Load a trace from its cache if it is fresh, warning about cache errors
+    pub(crate) fn try_load_cache(path: &Path) -> Option<Self> {
+        Self::load_cache(path).unwrap_or_else(|e| {
+            warn!("Ignoring time-trace cache of {} ({e})", path.display());
+            None
+        })
+    }
+}
+
+/// ClangTrace fields that are stored in the cache, in serialization order
+type CacheContents = (
+    ActivityTree,
+    HashMap<Box<str>, GlobalStat>,
+    Box<str>,
+    ProcessMetadata,
+    Option<Pid>,
+    BTreeMap<Tid, Box<str>>,
+    Option<Duration>,
+);
+
+/// Identifies which version of a time-trace file a cache was built from
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct CacheKey {
+    /// Version of this crate, since the cache format may change across versions
+    version: Box<str>,
+
+    /// Size of the time-trace file
+    len: u64,
+
+    /// Last modification time of the time-trace file
+    modified: SystemTime,
+}
+//
+impl CacheKey {
+    /// Compute the cache key of a time-trace file
+    fn new(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").into(),
+            len: metadata.len(),
+            modified: metadata.modified()?,
+        })
+    }
+}
+
+/// What can go wrong while saving or loading a time-trace cache
+#[derive(Error, Debug)]
+pub enum ClangTraceCacheError {
+    /// Failed to access the trace or cache file
+    #[error("failed to access time-trace cache ({0})")]
+    Io(#[from] io::Error),
+
+    /// Failed to serialize the trace
+    #[error("failed to serialize time-trace cache ({0})")]
+    Serialize(#[from] ciborium::ser::Error<io::Error>),
+
+    /// Failed to deserialize the trace
+    #[error("failed to deserialize time-trace cache ({0})")]
+    Deserialize(#[from] ciborium::de::Error<io::Error>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn save_and_load() {
+        // Write a trace into a scratch directory
+        let dir = std::env::temp_dir().join(format!("crofiler-cache-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let trace_path = dir.join("trace.json");
+        let trace_json = r#"{
+    "traceEvents": [
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 20,
+            "dur": 500,
+            "name": "Source",
+            "cat": "frontend",
+            "args": {
+                "detail": "/path/to/header.h"
+            }
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 10,
+            "dur": 1000,
+            "name": "ExecuteCompiler"
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 0,
+            "dur": 1000,
+            "name": "Total ExecuteCompiler",
+            "args": {
+                "count": 1,
+                "avg ms": 1
+            }
+        },
+        {
+            "ph": "M",
+            "pid": 1,
+            "tid": 0,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-14",
+                "detail": "clang version 14.0.0"
+            }
+        }
+    ]
+}"#;
+        fs::write(&trace_path, trace_json).unwrap();
+
+        // No cache exists initially
+        assert!(ClangTrace::load_cache(&trace_path).unwrap().is_none());
+
+        // Saved caches can be reloaded into an identical trace
+        let fresh = ClangTrace::from_str(trace_json).unwrap();
+        fresh.save_cache(&trace_path).unwrap();
+        let cached = ClangTrace::load_cache(&trace_path).unwrap().unwrap();
+        assert_eq!(cached.activities, fresh.activities);
+        assert_eq!(cached.global_stats, fresh.global_stats);
+        assert_eq!(cached.process_name, fresh.process_name);
+        assert_eq!(cached.process_metadata, fresh.process_metadata);
+        assert_eq!(cached.pid, fresh.pid);
+        assert_eq!(cached.thread_names, fresh.thread_names);
+        assert_eq!(cached.beginning_of_time, fresh.beginning_of_time);
+
+        // from_file uses the cache when it is fresh
+        let loaded = ClangTrace::from_file(&trace_path).unwrap();
+        assert_eq!(loaded.activities, fresh.activities);
+
+        // Caches are ignored once the trace file changes
+        fs::write(&trace_path, format!("{trace_json}\n")).unwrap();
+        assert!(ClangTrace::load_cache(&trace_path).unwrap().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -14,7 +14,7 @@ use self::{
     events::{duration::DurationEvent, metadata::MetadataEvent},
     stack::{EndStackTrace, StackFrame, StackFrameId},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json as json;
 use std::collections::HashMap;
 
@@ -190,8 +190,8 @@ pub type SampleWeight = i64;
 pub type Pid = i32;
 
 /// Event categories
-#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
-#[serde(from = "Box<str>")]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(from = "Box<str>", into = "Box<str>")]
 pub struct EventCategories(pub Box<[Box<str>]>);
 //
 impl From<Box<str>> for EventCategories {
@@ -204,6 +204,12 @@ impl From<Box<str>> for EventCategories {
         )
     }
 }
+//
+impl From<EventCategories> for Box<str> {
+    fn from(categories: EventCategories) -> Self {
+        categories.0.join(",").into()
+    }
+}
 
 #[cfg(test)]
 pub(crate) mod tests {
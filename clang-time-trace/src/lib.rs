@@ -3,6 +3,7 @@
 
 #![deny(missing_docs)]
 
+mod cache;
 mod ctf;
 mod metadata;
 mod stats;
@@ -13,21 +14,24 @@ use self::{
     stats::activity::ActivityStat,
     tree::{ActivityTree, ActivityTreeBuilder},
 };
-use cpparser::{EntityKey, EntityParser, EntityView};
+use cpparser::EntityParser;
 use serde_json as json;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fs::File,
     io::{self, Read},
-    path::Path,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    rc::Rc,
     str::FromStr,
 };
 use thiserror::Error;
 
 // Reexport types which appear in the public interface
 pub use self::{
-    ctf::{Duration, Pid, Timestamp, DAY, HOUR, MICROSECOND, MILLISECOND, MINUTE, SECOND},
-    metadata::NameParseError,
+    cache::ClangTraceCacheError,
+    ctf::{Duration, Pid, Tid, Timestamp, DAY, HOUR, MICROSECOND, MILLISECOND, MINUTE, SECOND},
+    metadata::{NameParseError, ProcessMetadata},
     stats::{
         activity::{
             argument::{
@@ -44,35 +48,58 @@ pub use self::{
 pub use cpparser::{
     asylum::path::{InternedComponent, PathError},
     display::{CustomDisplay, DisplayState},
-    PathComponentKey, PathKey,
+    EntityKey, EntityView, OwnedEntity, PathComponentKey, PathKey, SharedEntity,
 };
 pub use json::Error as CtfParseError;
 
 /// Interned path
 pub type InternedPath<'interner> = cpparser::InternedPath<'interner>;
 
+/// Time spent in one top-level compilation phase
+///
+/// See `ClangTrace::phase_breakdown()` for more information.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseDuration {
+    /// Total duration of the phase
+    pub duration: Duration,
+
+    /// Fraction of the ExecuteCompiler duration spent in this phase
+    pub fraction: f64,
+}
+
 /// Simplified -ftime-trace profile from a clang execution
 pub struct ClangTrace {
     /// Clang activities recorded by -ftime-trace
     activities: ActivityTree,
 
     /// Interned C++ entities and file paths within activities
-    entities: EntityParser,
+    ///
+    /// This is shared so that parsed entities can outlive borrows of the trace,
+    /// see `shared_entity()`.
+    ///
+    entities: Rc<EntityParser>,
 
     /// Buffer used for symbol demangling
     demangling_buf: String,
 
+    /// Number of activity arguments parsed so far
+    parsed_arguments: usize,
+
     /// Global statistics
     global_stats: HashMap<Box<str>, GlobalStat>,
 
     /// Name of the clang process
     process_name: Box<str>,
 
+    /// Extra process metadata, beyond the process name
+    process_metadata: ProcessMetadata,
+
     /// Pid of the clang process, if not obviously invalid
     pid: Option<Pid>,
 
-    /// Name of the clang thread, if known
-    thread_name: Option<Box<str>>,
+    /// Names of the clang threads, if known
+    thread_names: BTreeMap<Tid, Box<str>>,
 
     /// Beginning of time, if specified
     beginning_of_time: Option<Duration>,
@@ -80,13 +107,55 @@ pub struct ClangTrace {
 //
 impl ClangTrace {
     /// Load from clang -ftime-trace output in a file
+    ///
+    /// If a fresh cache of this file was previously saved with `save_cache()`,
+    /// it is used instead of parsing the file again.
+    ///
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ClangTraceLoadError> {
+        // Use the cache if it is up to date
+        let path = path.as_ref();
+        if let Some(trace) = Self::try_load_cache(path) {
+            return Ok(trace);
+        }
+
         // Load JSON data from the input file and parse it as CTF JSON
         let mut profile_str = String::new();
         File::open(path)?.read_to_string(&mut profile_str)?;
         Ok(Self::from_str(&profile_str)?)
     }
 
+    /// Load all clang -ftime-trace outputs from a directory
+    ///
+    /// Files are selected by matching their name against `glob`, where `*`
+    /// matches any sequence of characters and `?` matches any single
+    /// character, e.g. `*.json`. Subdirectories are not traversed.
+    ///
+    /// Matching files are loaded lazily in path order using `from_file()`.
+    /// Failing to load one file does not prevent loading the others, so the
+    /// path of each file is emitted along with the outcome of loading it.
+    ///
+    pub fn from_dir(
+        dir: impl AsRef<Path>,
+        glob: &str,
+    ) -> io::Result<impl Iterator<Item = (PathBuf, Result<Self, ClangTraceLoadError>)>> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let matches = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| glob_matches(glob, name));
+            if matches && !entry.file_type()?.is_dir() {
+                paths.push(entry.path());
+            }
+        }
+        paths.sort_unstable();
+        Ok(paths.into_iter().map(|path| {
+            let result = Self::from_file(&path);
+            (path, result)
+        }))
+    }
+
     /// Activities that were directly spawned by the clang driver
     ///
     /// From this, you can recursively iterate over child tasks in order to
@@ -99,8 +168,8 @@ impl ClangTrace {
     /// Complete list of activities that clang engaged in
     ///
     /// You can get a temporal trace of everything that happened by sorting this
-    /// in ascending start() order and you can get a flat time profile by
-    /// sorting this in descending self_duration() order. The order in which
+    /// in ascending start() order (see `activities_by_time()`) and you can get
+    /// a flat time profile by sorting this in descending self_duration() order. The order in which
     /// activities are emitted by this iterator is unspecified.
     ///
     /// When using such flat iteration, be careful not to double-count quantites
@@ -110,11 +179,98 @@ impl ClangTrace {
         self.activities.all_activities()
     }
 
+    /// Complete list of activities that clang engaged in, in timeline order
+    ///
+    /// Activities are emitted in ascending start() order, with ties broken by
+    /// descending duration() so that parents are emitted before the children
+    /// which started at the same time as them. This is the order in which a
+    /// timeline display would want to lay them out.
+    ///
+    pub fn activities_by_time(&self) -> impl Iterator<Item = ActivityTrace> + Clone {
+        let mut ids = self
+            .all_activities()
+            .map(|activity| (activity.start(), activity.duration(), activity.id()))
+            .collect::<Vec<_>>();
+        ids.sort_by(|(start1, duration1, _), (start2, duration2, _)| {
+            start1
+                .partial_cmp(start2)
+                .and_then(|order| Some(order.then(duration2.partial_cmp(duration1)?)))
+                .expect("No NaNs expected in time-trace")
+        });
+        ids.into_iter()
+            .map(move |(_, _, id)| self.activity_trace(id))
+    }
+
+    /// Activities that were directly spawned by the clang driver and belong
+    /// to a certain category
+    ///
+    /// Activities which do not belong to the category are filtered out along
+    /// with their children, whether these belong to the category or not.
+    ///
+    pub fn root_activities_in_category<'self_>(
+        &'self_ self,
+        category: &'self_ str,
+    ) -> impl Iterator<Item = ActivityTrace<'self_>> + Clone {
+        self.root_activities()
+            .filter(move |activity| activity.has_category(category))
+    }
+
+    /// Complete list of activities that clang engaged in, restricted to those
+    /// which belong to a certain category
+    ///
+    /// See `all_activities()` for more documentation.
+    ///
+    pub fn all_activities_in_category<'self_>(
+        &'self_ self,
+        category: &'self_ str,
+    ) -> impl Iterator<Item = ActivityTrace<'self_>> + Clone {
+        self.all_activities()
+            .filter(move |activity| activity.has_category(category))
+    }
+
+    /// Complete list of activities of a certain type (e.g. "InstantiateClass")
+    ///
+    /// See `all_activities()` for more documentation.
+    ///
+    pub fn find_activities<'self_>(
+        &'self_ self,
+        name: &'self_ str,
+    ) -> impl Iterator<Item = ActivityTrace<'self_>> + Clone {
+        self.all_activities()
+            .filter(move |activity| activity.activity().name() == name)
+    }
+
+    /// First activity of a certain type in `all_activities()` order, if any
+    pub fn find_first(&self, name: &str) -> Option<ActivityTrace> {
+        self.all_activities()
+            .find(|activity| activity.activity().name() == name)
+    }
+
     /// Retrieve an activity by a previously acquired identifier
     pub fn activity_trace(&self, id: ActivityTraceId) -> ActivityTrace {
         self.activities.activity_trace(id)
     }
 
+    /// Number of activities that clang engaged in
+    ///
+    /// This is the number of items produced by `all_activities()`, and an
+    /// upper bound on the value of `ActivityTraceId::index()`.
+    ///
+    pub fn activity_count(&self) -> usize {
+        self.activities.activity_count()
+    }
+
+    /// Number of activities whose self-duration was clamped to zero
+    ///
+    /// Self-durations are computed by subtracting the duration of children
+    /// from that of their parent, which can yield tiny negative values due to
+    /// floating-point rounding. These are treated as zero, and counted here
+    /// for diagnostic purposes.
+    ///
+    pub fn clamped_self_durations(&self) -> usize {
+        self.activities.clamped_self_durations()
+    }
+
     /// Global statistics on clang activities
     ///
     /// LLVM has double-counting protection when an activity calls itself
@@ -126,19 +282,157 @@ impl ClangTrace {
         &self.global_stats
     }
 
+    /// "Total *" aggregate pseudo-activities, by decreasing total duration
+    ///
+    /// These are the same as global_stats(), in a form that is more amenable
+    /// to display. Since their durations overlap with those of regular
+    /// activities, they are never part of the activity tree.
+    pub fn totals(&self) -> Box<[(&str, &GlobalStat)]> {
+        let mut totals = self
+            .global_stats
+            .iter()
+            .map(|(name, stat)| (&**name, stat))
+            .collect::<Box<[_]>>();
+        totals.sort_unstable_by(|(_, s1), (_, s2)| {
+            s2.total_duration()
+                .partial_cmp(&s1.total_duration())
+                .expect("No NaNs expected in time-trace")
+        });
+        totals
+    }
+
+    /// Breakdown of the compilation into its top-level phases (Frontend,
+    /// Backend...), by decreasing total duration
+    ///
+    /// Phases are the activities directly spawned by ExecuteCompiler, grouped
+    /// by activity type. Their fractions are computed with respect to the
+    /// total duration of ExecuteCompiler, whose self-duration is not part of
+    /// any phase so that fractions may not quite add up to 1.
+    ///
+    pub fn phase_breakdown(&self) -> Box<[(ActivityId, PhaseDuration)]> {
+        let compiler = self
+            .root_activities()
+            .filter(|root| *root.activity().id() == ActivityId::ExecuteCompiler);
+        let compiler_duration = compiler
+            .clone()
+            .map(|root| root.duration())
+            .sum::<Duration>();
+        let mut durations = HashMap::<ActivityId, Duration>::new();
+        for root in compiler {
+            for phase in root.direct_children() {
+                *durations.entry(phase.activity().id().clone()).or_default() += phase.duration();
+            }
+        }
+        let mut phases = durations
+            .into_iter()
+            .map(|(id, duration)| {
+                let fraction = duration / compiler_duration;
+                (id, PhaseDuration { duration, fraction })
+            })
+            .collect::<Box<[_]>>();
+        phases.sort_unstable_by(|(_, p1), (_, p2)| {
+            p2.duration
+                .partial_cmp(&p1.duration)
+                .expect("No NaNs expected in time-trace")
+        });
+        phases
+    }
+
+    /// Distribution of the number of direct children per activity
+    ///
+    /// Maps each child count to the number of activities that have exactly
+    /// that many direct children. Leaves are counted in the 0 bucket, and the
+    /// bucket sizes add up to `activity_count()`.
+    ///
+    pub fn fanout_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for activity in self.all_activities() {
+            *histogram
+                .entry(activity.direct_children().count())
+                .or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Critical path of the compilation
+    ///
+    /// This is the chain of nested activities, going from a root activity down
+    /// to a leaf activity, whose total duration is maximal. Activities are
+    /// listed from the root to the leaf, and the path is empty if the trace
+    /// has no activities.
+    ///
+    pub fn critical_path(&self) -> Box<[ActivityTraceId]> {
+        // Total duration of the longest chain starting at each activity
+        fn chain_duration(activity: &ActivityTrace, memo: &mut [Option<Duration>]) -> Duration {
+            if let Some(duration) = memo[activity.id().index()] {
+                return duration;
+            }
+            let duration = activity.duration()
+                + activity
+                    .direct_children()
+                    .map(|child| chain_duration(&child, memo))
+                    .fold(0.0, Duration::max);
+            memo[activity.id().index()] = Some(duration);
+            duration
+        }
+        // Activity with the longest chain among a set of candidates
+        fn longest<'a>(
+            activities: impl Iterator<Item = ActivityTrace<'a>>,
+            memo: &mut [Option<Duration>],
+        ) -> Option<ActivityTrace<'a>> {
+            activities
+                .map(|activity| (chain_duration(&activity, memo), activity))
+                .max_by(|(d1, _), (d2, _)| {
+                    d1.partial_cmp(d2).expect("No NaNs expected in time-trace")
+                })
+                .map(|(_, activity)| activity)
+        }
+
+        // Follow the longest chain from the roots down to a leaf
+        let mut memo = vec![None; self.activity_count()];
+        let mut path = Vec::new();
+        let mut next = longest(self.root_activities(), &mut memo).map(|root| root.id());
+        while let Some(id) = next {
+            path.push(id);
+            let activity = self.activity_trace(id);
+            next = longest(activity.direct_children(), &mut memo).map(|child| child.id());
+        }
+        path.into_boxed_slice()
+    }
+
     /// Name of the clang process that acquired this data
     pub fn process_name(&self) -> &str {
         &self.process_name
     }
 
+    /// Extra metadata that clang attached to the process name, if any
+    ///
+    /// The Trace Event Format does not specify these, but clang may use them
+    /// to record e.g. the compiler version or the command line.
+    ///
+    pub fn process_metadata(&self) -> &ProcessMetadata {
+        &self.process_metadata
+    }
+
     /// Process identifier of the clang process that acquired this data
     pub fn pid(&self) -> Option<Pid> {
         self.pid
     }
 
     /// Name of the clang thread that acquired this data
+    ///
+    /// If several threads were named, this is the name of the thread with the
+    /// lowest thread ID, which is clang's main thread.
+    ///
     pub fn thread_name(&self) -> Option<&str> {
-        self.thread_name.as_ref().map(|s| s.as_ref())
+        self.thread_names.values().next().map(|s| s.as_ref())
+    }
+
+    /// Names of all the clang threads that acquired this data, by thread ID
+    pub fn thread_names(&self) -> impl Iterator<Item = (Tid, &str)> {
+        self.thread_names
+            .iter()
+            .map(|(&tid, name)| (tid, name.as_ref()))
     }
 
     /// Beginning of time
@@ -150,9 +444,151 @@ impl ClangTrace {
         self.beginning_of_time
     }
 
+    /// Base timestamp of this trace
+    ///
+    /// This is the beginning of time if clang emitted it, or 0 otherwise.
+    /// Adding it to activity start timestamps gives absolute timestamps that
+    /// can be compared across traces.
+    ///
+    pub fn base_timestamp(&self) -> Timestamp {
+        self.beginning_of_time.unwrap_or(0.0)
+    }
+
+    /// Parse a C++ entity name using the same logic as activity arguments
+    ///
+    /// This is mainly useful for diagnostics purposes, e.g. finding out why a
+    /// demangled symbol could not be parsed into a C++ entity.
+    ///
+    pub fn parse_entity(&mut self, entity: &str) -> Result<EntityKey, ActivityArgumentError> {
+        RawActivityArgument::parse_entity(entity, &self.entities)
+    }
+
+    /// Estimate of the heap memory used by this trace, in bytes
+    ///
+    /// This accounts for activities, their string arguments, interned C++
+    /// entities and file paths, and global statistics. It is computed from
+    /// container sizes, so allocator overhead is not taken into account.
+    ///
+    pub fn memory_footprint(&self) -> usize {
+        let global_stats = self
+            .global_stats
+            .keys()
+            .map(|name| std::mem::size_of::<(Box<str>, GlobalStat)>() + name.len())
+            .sum::<usize>();
+        self.activities.memory_footprint()
+            + self.entities.memory_footprint()
+            + self.demangling_buf.capacity()
+            + global_stats
+            + self.process_name.len()
+            + self
+                .thread_names
+                .values()
+                .map(|name| std::mem::size_of::<(Tid, Box<str>)>() + name.len())
+                .sum::<usize>()
+    }
+
+    /// Unique source files that activities refer to, in order of first reference
+    ///
+    /// This parses the arguments of all activities that take a file path, so
+    /// these paths are interned along the way.
+    ///
+    pub fn referenced_files(&mut self) -> impl Iterator<Item = InternedPath> + '_ {
+        let raw_args = self
+            .all_activities()
+            .map(|activity| activity.activity().raw_argument().clone())
+            .filter(|arg| arg.arg_type() == ActivityArgumentType::FilePathOrModule)
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+        for arg in raw_args {
+            if let Ok(ParsedActivityArgument::FilePath(path)) = arg.parse(self) {
+                if seen.insert(path) {
+                    files.push(path);
+                }
+            }
+        }
+        files.into_iter().map(|path| self.file_path(path))
+    }
+
+    /// Number of activity arguments that were parsed so far
+    ///
+    /// Activity arguments are only parsed when explicitly requested via
+    /// `RawActivityArgument::parse()` or `parse_all_arguments()`, so anything
+    /// that only needs activity names and durations (like a flat profile) can
+    /// be computed without paying for argument parsing. This counter lets you
+    /// check that this is the case.
+    ///
+    pub fn parsed_argument_count(&self) -> usize {
+        self.parsed_arguments
+    }
+
+    /// Parse the arguments of all activities, using multiple threads
+    ///
+    /// The result has one entry per activity, in `ActivityTraceId::index()`
+    /// order. Symbol demangling, which dominates argument parsing time, is
+    /// spread across `num_threads` threads. Parsed entities are then interned
+    /// on the calling thread in activity order, so the interner need not be
+    /// shared and the result does not depend on the number of threads.
+    ///
+    pub fn parse_all_arguments(
+        &mut self,
+        num_threads: NonZeroUsize,
+    ) -> Box<[Result<ParsedActivityArgument, ActivityArgumentError>]> {
+        // Extract symbols to be demangled (Rc cannot be sent across threads)
+        let raw_args = self
+            .all_activities()
+            .map(|activity| activity.activity().raw_argument().clone())
+            .collect::<Vec<_>>();
+        let symbols = raw_args
+            .iter()
+            .map(|arg| match arg.arg_type() {
+                ActivityArgumentType::Symbol | ActivityArgumentType::SymbolOpt => arg
+                    .detail()
+                    .map(|d| Box::from(RawActivityArgument::strip_symbol_parentheses(&d))),
+                _ => None,
+            })
+            .collect::<Vec<Option<Box<str>>>>();
+
+        // Demangle symbols in parallel
+        let num_threads = num_threads.get();
+        let chunk_size = ((symbols.len() + num_threads - 1) / num_threads).max(1);
+        let mut demangled = std::thread::scope(|scope| {
+            let workers = symbols
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut demangling_buf = String::new();
+                        chunk
+                            .iter()
+                            .map(|symbol| {
+                                let symbol = symbol.as_deref()?;
+                                RawActivityArgument::demangle(symbol, &mut demangling_buf)
+                                    .then(|| Box::<str>::from(demangling_buf.as_str()))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("Demangling thread panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        // Parse arguments in activity order
+        self.parsed_arguments += raw_args.len();
+        raw_args
+            .into_iter()
+            .zip(demangled.iter_mut())
+            .map(|(arg, demangled)| {
+                arg.parse_with_demangler(&self.entities, |_symbol| demangled.take().map(Rc::from))
+            })
+            .collect()
+    }
+
     /// Access the entity parser and symbol demangling buffer
-    pub(crate) fn parser_and_demangling_buf(&mut self) -> (&mut EntityParser, &mut String) {
-        (&mut self.entities, &mut self.demangling_buf)
+    pub(crate) fn parser_and_demangling_buf(&mut self) -> (&EntityParser, &mut String) {
+        (&self.entities, &mut self.demangling_buf)
     }
 
     /// Access a file path using a PathKey
@@ -165,6 +601,15 @@ impl ClangTrace {
         self.entities.entity(key)
     }
 
+    /// Access a parsed C++ entity without borrowing from the trace
+    ///
+    /// This is useful when an entity must be displayed repeatedly, e.g. at
+    /// various widths, while the trace keeps being used in the meantime.
+    ///
+    pub fn shared_entity(&self, key: EntityKey) -> SharedEntity {
+        SharedEntity::new(self.entities.clone(), key)
+    }
+
     /// Log how the entity parser was used so far
     ///
     /// This is internally used in EntityParser development to adjust tuning
@@ -215,6 +660,16 @@ impl ClangTrace {
             parser.num_decl_operators(),
             parser.max_declarator_len().unwrap_or(0)
         );
+        info!(
+            "- Requires-expressions: {} total Requirements, max {} Requirements/set",
+            parser.num_requirements(),
+            parser.max_requirements_len().unwrap_or(0)
+        );
+        info!(
+            "- Requires-expression parameters: {} total parameters, max {} parameters/set",
+            parser.num_requires_parameters(),
+            parser.max_requires_parameters_len().unwrap_or(0)
+        );
     }
 }
 //
@@ -252,10 +707,12 @@ impl FromStr for ClangTrace {
         };
 
         // Process the trace events
-        let mut activities = ActivityTreeBuilder::with_capacity(profile_ctf.traceEvents.len() - 1);
+        let num_events = profile_ctf.traceEvents.len();
+        let mut thread_activities = BTreeMap::<Tid, Vec<ActivityStat>>::new();
         let mut global_stats = HashMap::new();
         let mut process_name = None;
-        let mut thread_name = None;
+        let mut process_metadata = HashMap::new();
+        let mut thread_names = BTreeMap::new();
         let mut clang_pid = None;
         let merge_pid =
             |curr_pid: &mut Option<Pid>, proposed_pid: Pid| match (*curr_pid, proposed_pid) {
@@ -269,17 +726,23 @@ impl FromStr for ClangTrace {
         //
         for event in profile_ctf.traceEvents.into_vec() {
             match event {
-                // Durations associated with a timestamp greater than 1µs are activity profiles
+                // Durations associated with a timestamp greater than 1µs are
+                // activity profiles, unless they are "Total *" aggregates
                 TraceEvent::X {
                     ref duration_event, ..
-                } if duration_event.ts > 1.0 => {
-                    // Parse activity statistics and insert the new activity
-                    // into the activity tree
+                } if duration_event.ts > 1.0 && !GlobalStat::is_total(duration_event) => {
+                    // Parse activity statistics and set the new activity
+                    // aside until all activities of its thread are known
                     merge_pid(&mut clang_pid, duration_event.pid)?;
-                    activities.insert(ActivityStat::parse(event)?)?;
+                    let activity = ActivityStat::parse(event)?;
+                    thread_activities
+                        .entry(activity.tid())
+                        .or_default()
+                        .push(activity);
                 }
 
-                // Durations associated with a lower timestamp (typically 100ns) are global stats
+                // Durations associated with a lower timestamp (typically 100ns)
+                // and "Total *" aggregates are global stats
                 TraceEvent::X {
                     ref duration_event, ..
                 } => {
@@ -296,7 +759,7 @@ impl FromStr for ClangTrace {
                     // Name of the clang process
                     MetadataEvent::process_name { ref pid, .. } => {
                         merge_pid(&mut clang_pid, *pid)?;
-                        let name = metadata::parse_process_name(m)?;
+                        let (name, metadata) = metadata::parse_process_name(m)?;
                         if let Some(process_name) = process_name {
                             return Err(ClangTraceParseError::DuplicateProcessName(
                                 process_name,
@@ -304,22 +767,22 @@ impl FromStr for ClangTrace {
                             ));
                         } else {
                             process_name = Some(name);
+                            process_metadata = metadata;
                         }
                     }
 
-                    // Name of the clang thread
+                    // Name of a clang thread
                     MetadataEvent::thread_name { ref pid, .. } => {
                         if let Some(pid) = pid {
                             merge_pid(&mut clang_pid, *pid)?;
                         }
-                        let name = metadata::parse_thread_name(m)?;
-                        if let Some(thread_name) = thread_name {
+                        let (tid, name) = metadata::parse_thread_name(m)?;
+                        if let Some(thread_name) = thread_names.insert(tid, name) {
+                            let name = thread_names[&tid].clone();
                             return Err(ClangTraceParseError::DuplicateThreadName(
                                 thread_name,
                                 name,
                             ));
-                        } else {
-                            thread_name = Some(name);
                         }
                     }
 
@@ -332,6 +795,12 @@ impl FromStr for ClangTrace {
             }
         }
 
+        // Build one activity tree per thread, within a single ActivityTree
+        let mut activities = ActivityTreeBuilder::with_capacity(num_events - 1);
+        for activity in thread_activities.into_values().flatten() {
+            activities.insert(activity)?;
+        }
+
         // Ignore blatantly wrong PIDs reported by older clang
         let pid = clang_pid.filter(|&pid| pid > 1);
 
@@ -339,11 +808,13 @@ impl FromStr for ClangTrace {
         if let Some(process_name) = process_name {
             Ok(Self {
                 activities: activities.build(),
-                entities: EntityParser::new(),
+                entities: Rc::new(EntityParser::new()),
                 demangling_buf: String::new(),
+                parsed_arguments: 0,
                 global_stats,
                 process_name,
-                thread_name,
+                process_metadata,
+                thread_names,
                 pid,
                 beginning_of_time,
             })
@@ -353,6 +824,37 @@ impl FromStr for ClangTrace {
     }
 }
 
+/// Truth that a file name matches a glob pattern made of `*` and `?` wildcards
+fn glob_matches(glob: &str, name: &str) -> bool {
+    let glob = glob.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut glob_idx, mut name_idx) = (0, 0);
+    // Position of the last `*` in the glob, and of the end of the name
+    // characters that it currently matches, for backtracking
+    let mut last_star = None;
+    while name_idx < name.len() {
+        match glob.get(glob_idx) {
+            Some('*') => {
+                last_star = Some((glob_idx, name_idx));
+                glob_idx += 1;
+            }
+            Some(&c) if c == '?' || c == name[name_idx] => {
+                glob_idx += 1;
+                name_idx += 1;
+            }
+            _ => {
+                let Some((star_idx, star_name_idx)) = last_star else {
+                    return false;
+                };
+                last_star = Some((star_idx, star_name_idx + 1));
+                glob_idx = star_idx + 1;
+                name_idx = star_name_idx + 1;
+            }
+        }
+    }
+    glob[glob_idx..].iter().all(|&c| c == '*')
+}
+
 /// What can go wrong while loading clang's -ftime-trace data from a file
 #[derive(Error, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -409,7 +911,7 @@ pub enum ClangTraceParseError {
     #[error("failed to parse name ({0})")]
     NameParseError(#[from] Box<NameParseError>),
 
-    /// Encountered two occurences of the process name
+    /// Encountered two occurences of the same thread's name
     #[error("encountered thread name twice (\"{0}\" then \"{1}\")")]
     DuplicateThreadName(Box<str>, Box<str>),
 
@@ -540,6 +1042,7 @@ mod tests {
         );
         assert_eq!(trace.pid(), None);
         assert_eq!(trace.beginning_of_time(), None);
+        assert_eq!(trace.base_timestamp(), 0.0);
         assert_eq!(trace.thread_name(), None);
 
         // Check flat activity list
@@ -573,6 +1076,29 @@ mod tests {
             assert_eq!(root.duration(), *root_duration);
         });
         assert_eq!(root_iter.next(), None);
+
+        // Check that activity indices are dense and unique
+        assert_eq!(trace.activity_count(), expected_activities.len());
+        let mut seen = vec![false; trace.activity_count()];
+        for activity_trace in trace.all_activities() {
+            let id = activity_trace.id();
+            assert!(id.index() < trace.activity_count());
+            assert!(!std::mem::replace(&mut seen[id.index()], true));
+            assert_eq!(trace.activity_trace(id), activity_trace);
+        }
+        assert!(seen.into_iter().all(|seen| seen));
+
+        // Check that the span of each activity contains those of its children
+        for activity_trace in trace.all_activities() {
+            assert_eq!(
+                activity_trace.end(),
+                activity_trace.start() + activity_trace.duration()
+            );
+            for child in activity_trace.direct_children() {
+                assert!(child.start() >= activity_trace.start());
+                assert!(child.end() <= activity_trace.end());
+            }
+        }
     }
 
     #[test]
@@ -689,6 +1215,7 @@ mod tests {
         );
         assert_eq!(trace.pid(), Some(42));
         assert_eq!(trace.beginning_of_time(), Some(42.0));
+        assert_eq!(trace.base_timestamp(), 42.0);
         assert_eq!(trace.thread_name(), Some("clang"));
 
         // Check flat activity list
@@ -722,6 +1249,297 @@ mod tests {
             assert_eq!(root.duration(), *root_duration);
         });
         assert_eq!(root_iter.next(), None);
+
+        // Check timeline order
+        assert_eq!(
+            trace
+                .activities_by_time()
+                .map(|activity_trace| activity_trace.activity().id().clone())
+                .collect::<Vec<_>>(),
+            [
+                ActivityId::ExecuteCompiler,
+                ActivityId::Frontend,
+                ActivityId::Backend,
+                ActivityId::CodeGenPasses,
+            ]
+        );
+    }
+
+    #[test]
+    fn activities_by_time() {
+        // Activities are emitted in end order, and some parents and children
+        // start at the same time
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        { "ph": "X", "pid": 1, "tid": 1, "ts": 10, "dur": 20, "name": "Source", "args": { "detail": "a.h" } },
+        { "ph": "X", "pid": 1, "tid": 1, "ts": 10, "dur": 40, "name": "Frontend" },
+        { "ph": "X", "pid": 1, "tid": 1, "ts": 60, "dur": 30, "name": "Backend" },
+        { "ph": "X", "pid": 1, "tid": 1, "ts": 10, "dur": 90, "name": "ExecuteCompiler" },
+        {
+            "ph": "M",
+            "pid": 1,
+            "tid": 0,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-14"
+            }
+        }
+    ]
+}"#,
+        )
+        .unwrap();
+
+        let timeline = trace
+            .activities_by_time()
+            .map(|activity_trace| {
+                (
+                    activity_trace.activity().id().clone(),
+                    activity_trace.start(),
+                    activity_trace.duration(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            timeline,
+            [
+                (ActivityId::ExecuteCompiler, 10.0, 90.0),
+                (ActivityId::Frontend, 10.0, 40.0),
+                (ActivityId::Source, 10.0, 20.0),
+                (ActivityId::Backend, 60.0, 30.0),
+            ]
+        );
+        assert!(timeline.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(timeline.len(), trace.activity_count());
+    }
+
+    #[test]
+    fn categorized_trace() {
+        // Build a trace whose activities belong to two categories
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 1.3,
+            "dur": 6787.7,
+            "cat": "frontend",
+            "name": "Frontend"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 6789.3,
+            "dur": 5554.2,
+            "cat": "backend,codegen",
+            "name": "CodeGenPasses"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 6789.1,
+            "dur": 5554.5,
+            "cat": "backend",
+            "name": "Backend"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 1.1,
+            "dur": 12343.8,
+            "name": "ExecuteCompiler"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 12345.0,
+            "dur": 10.0,
+            "cat": "backend",
+            "name": "Optimizer"
+        },
+        {
+            "ph":"M",
+            "pid": 42,
+            "tid": 42,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-14.0.0"
+            }
+        }
+    ]
+}"#,
+        )
+        .expect("This is a known-good parse which should not fail");
+
+        // Check per-activity categories and threads
+        let categories = trace
+            .all_activities()
+            .map(|activity_trace| {
+                assert_eq!(activity_trace.tid(), 42);
+                activity_trace.categories().to_vec()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            [
+                vec!["frontend".into()],
+                vec!["backend".into(), "codegen".into()],
+                vec!["backend".into()],
+                vec![],
+                vec!["backend".into()],
+            ]
+        );
+
+        // Check category filtering
+        let activity_ids = |activities: &mut dyn Iterator<Item = ActivityTrace>| {
+            activities
+                .map(|activity_trace| activity_trace.activity().id().clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            activity_ids(&mut trace.all_activities_in_category("frontend")),
+            [ActivityId::Frontend]
+        );
+        assert_eq!(
+            activity_ids(&mut trace.all_activities_in_category("backend")),
+            [
+                ActivityId::CodeGenPasses,
+                ActivityId::Backend,
+                ActivityId::Optimizer
+            ]
+        );
+        assert_eq!(
+            activity_ids(&mut trace.all_activities_in_category("codegen")),
+            [ActivityId::CodeGenPasses]
+        );
+        assert_eq!(
+            activity_ids(&mut trace.all_activities_in_category("nope")),
+            []
+        );
+        assert_eq!(
+            activity_ids(&mut trace.root_activities_in_category("backend")),
+            [ActivityId::Optimizer]
+        );
+        assert_eq!(
+            activity_ids(&mut trace.root_activities_in_category("frontend")),
+            []
+        );
+    }
+
+    #[test]
+    fn multi_thread_trace() {
+        // Build a trace with two threads whose activities are interleaved in
+        // the input and overlap each other in time
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 43,
+            "ts": 6100.0,
+            "dur": 900.0,
+            "name": "CodeGenPasses"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 1.3,
+            "dur": 6787.7,
+            "name": "Frontend"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 42,
+            "ts": 1.1,
+            "dur": 12343.8,
+            "name": "ExecuteCompiler"
+        },
+        {
+            "ph": "X",
+            "pid": 42,
+            "tid": 43,
+            "ts": 6000.0,
+            "dur": 7000.0,
+            "name": "Backend"
+        },
+        {
+            "ph":"M",
+            "pid": 42,
+            "tid": 42,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-14.0.0"
+            }
+        },
+        {
+            "ph":"M",
+            "pid": 42,
+            "tid": 42,
+            "ts": 0,
+            "cat": "",
+            "name": "thread_name",
+            "args": {
+                "name": "clang"
+            }
+        },
+        {
+            "ph":"M",
+            "pid": 42,
+            "tid": 43,
+            "ts": 0,
+            "cat": "",
+            "name": "thread_name",
+            "args": {
+                "name": "clang worker"
+            }
+        }
+    ]
+}"#,
+        )
+        .expect("This is a known-good parse which should not fail");
+
+        // Check thread names
+        assert_eq!(trace.thread_name(), Some("clang"));
+        assert_eq!(
+            trace.thread_names().collect::<Vec<_>>(),
+            [(42, "clang"), (43, "clang worker")]
+        );
+
+        // Each thread has its own root, and activities only nest within
+        // their own thread
+        assert_eq!(trace.activity_count(), 4);
+        let roots = trace
+            .root_activities()
+            .map(|root| (root.tid(), root.activity().id().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            roots,
+            [(43, ActivityId::Backend), (42, ActivityId::ExecuteCompiler)]
+        );
+        for root in trace.root_activities() {
+            let children = root.direct_children().collect::<Vec<_>>();
+            assert_eq!(children.len(), 1);
+            assert_eq!(children[0].tid(), root.tid());
+            assert_eq!(
+                children[0].parent().map(|parent| parent.id()),
+                Some(root.id())
+            );
+        }
     }
 
     macro_rules! expect_err {
@@ -746,6 +1564,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn process_metadata() {
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        {
+            "ph": "M",
+            "pid": 42,
+            "tid": 42,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-17",
+                "detail": "clang version 17.0.6",
+                "argv": ["clang++", "-c", "main.cpp"]
+            }
+        }
+    ]
+}"#,
+        )
+        .expect("This is a known-good parse which should not fail");
+        assert_eq!(trace.process_name(), "clang-17");
+        assert_eq!(
+            trace.process_metadata(),
+            &maplit::hashmap! {
+                "detail".into() => serde_json::json!("clang version 17.0.6"),
+                "argv".into() => serde_json::json!(["clang++", "-c", "main.cpp"]),
+            }
+        );
+    }
+
     #[test]
     fn invalid_beginning_of_time() {
         assert_matches!(
@@ -834,7 +1684,7 @@ mod tests {
     #[test]
     fn invalid_global_stat() {
         assert_matches!(
-            // Invalid start timestamp
+            // Low start timestamp without a "Total " prefix
             expect_err!(ClangTrace::from_str(
                 r#"{
     "traceEvents": [{
@@ -843,7 +1693,7 @@ mod tests {
         "tid": 1,
         "ts": 0.2,
         "dur": 12345,
-        "name": "Total ExecuteCompiler",
+        "name": "ExecuteCompiler",
         "args": {
             "count": 1,
             "avg ms": 12345
@@ -855,6 +1705,278 @@ mod tests {
         );
     }
 
+    #[test]
+    fn timestamped_totals() {
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 20,
+            "dur": 500,
+            "name": "Frontend"
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 10,
+            "dur": 1000,
+            "name": "ExecuteCompiler"
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 10,
+            "dur": 1000,
+            "name": "Total ExecuteCompiler",
+            "args": {
+                "count": 1,
+                "avg ms": 1
+            }
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 0,
+            "dur": 500,
+            "name": "Total Frontend",
+            "args": {
+                "count": 1,
+                "avg ms": 0.5
+            }
+        },
+        {
+            "ph": "M",
+            "pid": 1,
+            "tid": 0,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-14"
+            }
+        }
+    ]
+}"#,
+        )
+        .unwrap();
+
+        // Totals do not show up in the activity tree...
+        assert_eq!(trace.activity_count(), 2);
+        let root_duration = trace
+            .root_activities()
+            .map(|root| root.duration())
+            .sum::<Duration>();
+        assert_eq!(root_duration, 1000.0);
+
+        // ...but can be queried separately
+        assert_eq!(
+            &trace.totals()[..],
+            &[
+                ("ExecuteCompiler", &GlobalStat::new(1000.0, 1)),
+                ("Frontend", &GlobalStat::new(500.0, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn memory_footprint() {
+        // Build a trace with a certain number of sequential source activities
+        let trace_with_activities = |count: usize| {
+            let events = (0..count)
+                .map(|i| {
+                    format!(
+                        r#"{{
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": {ts},
+            "dur": 10,
+            "name": "Source",
+            "args": {{
+                "detail": "/path/to/header{i}.h"
+            }}
+        }},"#,
+                        ts = 10 * (i + 1)
+                    )
+                })
+                .collect::<String>();
+            ClangTrace::from_str(&format!(
+                r#"{{
+    "traceEvents": [
+        {events}
+        {{
+            "ph": "M",
+            "pid": 1,
+            "tid": 0,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {{
+                "name": "clang-14"
+            }}
+        }}
+    ]
+}}"#
+            ))
+            .unwrap()
+        };
+
+        let small = trace_with_activities(10);
+        let large = trace_with_activities(1000);
+        assert_eq!(small.activity_count(), 10);
+        assert_eq!(large.activity_count(), 1000);
+        assert!(small.memory_footprint() > 0);
+        assert!(large.memory_footprint() > small.memory_footprint());
+    }
+
+    #[test]
+    fn parallel_argument_parsing() {
+        // Build a trace featuring all kinds of symbol and entity arguments
+        let events = [
+            ("Source", "/path/to/header.h"),
+            ("InstantiateFunction", "std::vector<int>::push_back"),
+            ("ADCEPass", "_ZN3foo3barEv"),
+            ("ADCEPass", "(_Z1fi)"),
+            ("ADCEPass", "main"),
+            ("ADCEPass", "__cxx_global_var_init.1"),
+            ("CodeGen Function", "foo::bar"),
+            ("ADCEPass", "_ZNSt6vectorIiSaIiEE9push_backERKi"),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, detail))| {
+            format!(
+                r#"{{
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": {ts},
+            "dur": 10,
+            "name": "{name}",
+            "args": {{
+                "detail": "{detail}"
+            }}
+        }},"#,
+                ts = 10 * (i + 1)
+            )
+        })
+        .collect::<String>();
+        let trace_json = format!(
+            r#"{{
+    "traceEvents": [
+        {events}
+        {{
+            "ph": "M",
+            "pid": 1,
+            "tid": 0,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {{
+                "name": "clang-14"
+            }}
+        }}
+    ]
+}}"#
+        );
+
+        // Parse arguments serially, one activity at a time
+        let mut serial_trace = ClangTrace::from_str(&trace_json).unwrap();
+        let serial = serial_trace
+            .all_activities()
+            .map(|activity| activity.activity().raw_argument().clone())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|arg| arg.parse(&mut serial_trace))
+            .collect::<Box<[_]>>();
+        assert!(serial.iter().all(Result::is_ok));
+        assert_eq!(serial_trace.parsed_argument_count(), serial.len());
+
+        // Parallel parsing must produce the same results, down to interned keys
+        for num_threads in [1, 2, 3, 16] {
+            let mut parallel_trace = ClangTrace::from_str(&trace_json).unwrap();
+            let parallel =
+                parallel_trace.parse_all_arguments(NonZeroUsize::new(num_threads).unwrap());
+            assert_eq!(parallel, serial);
+            assert_eq!(parallel_trace.parsed_argument_count(), serial.len());
+        }
+    }
+
+    #[test]
+    fn raw_argument_str() {
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 20,
+            "dur": 10,
+            "name": "InstantiateFunction",
+            "args": {
+                "detail": "std::vector<int,  std::allocator<int> >::push_back"
+            }
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 40,
+            "dur": 10,
+            "name": "ParseClass",
+            "args": {
+                "detail": "<unparseable \"entity\""
+            }
+        },
+        {
+            "ph": "X",
+            "pid": 1,
+            "tid": 1,
+            "ts": 10,
+            "dur": 100,
+            "name": "ExecuteCompiler"
+        },
+        {
+            "ph": "M",
+            "pid": 1,
+            "tid": 0,
+            "ts": 0,
+            "cat": "",
+            "name": "process_name",
+            "args": {
+                "name": "clang-14"
+            }
+        }
+    ]
+}"#,
+        )
+        .unwrap();
+        let raw_argument = |id: ActivityId| {
+            trace
+                .all_activities()
+                .find(|activity| activity.activity().id() == &id)
+                .expect("activity should be present")
+                .raw_argument_str()
+                .map(Box::<str>::from)
+        };
+        assert_eq!(
+            raw_argument(ActivityId::InstantiateFunction),
+            Some("std::vector<int,  std::allocator<int> >::push_back".into())
+        );
+        assert_eq!(
+            raw_argument(ActivityId::ParseClass),
+            Some("<unparseable \"entity\"".into())
+        );
+        assert_eq!(raw_argument(ActivityId::ExecuteCompiler), None);
+    }
+
     #[test]
     fn duplicate_global_stat() {
         assert_matches!(
@@ -1085,4 +2207,66 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn glob_matches() {
+        assert!(super::glob_matches("*.json", "main.cpp.json"));
+        assert!(super::glob_matches("*.json", ".json"));
+        assert!(!super::glob_matches("*.json", "main.json.crofiler-cache"));
+        assert!(super::glob_matches("a?c*", "abcdef"));
+        assert!(!super::glob_matches("a?c*", "ac"));
+        assert!(super::glob_matches("*a*b", "xaxaxb"));
+        assert!(!super::glob_matches("*a*b", "xaxaxbx"));
+        assert!(super::glob_matches("exact", "exact"));
+        assert!(!super::glob_matches("exact", "exactly"));
+    }
+
+    #[test]
+    fn from_dir() {
+        // Set up a directory with two valid traces, one garbage trace and one
+        // file that does not match the glob
+        let dir =
+            std::env::temp_dir().join(format!("crofiler-from-dir-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let trace = |process_name: &str| {
+            format!(
+                r#"{{
+    "traceEvents": [
+        {{ "ph": "X", "pid": 1, "tid": 0, "ts": 10, "dur": 100, "name": "ExecuteCompiler" }},
+        {{ "ph": "M", "pid": 1, "tid": 0, "ts": 0, "cat": "", "name": "process_name", "args": {{ "name": "{process_name}" }} }}
+    ]
+}}"#
+            )
+        };
+        std::fs::write(dir.join("a.json"), trace("clang-a")).unwrap();
+        std::fs::write(dir.join("b.json"), "this is not JSON").unwrap();
+        std::fs::write(dir.join("c.json"), trace("clang-c")).unwrap();
+        std::fs::write(dir.join("notes.txt"), trace("clang-notes")).unwrap();
+
+        // Load errors are reported on a per-file basis
+        let results = ClangTrace::from_dir(&dir, "*.json")
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, dir.join("a.json"));
+        assert_eq!(
+            results[0].1.as_ref().ok().map(ClangTrace::process_name),
+            Some("clang-a")
+        );
+        assert_eq!(results[1].0, dir.join("b.json"));
+        assert_matches!(
+            results[1].1.as_ref().err(),
+            Some(ClangTraceLoadError::Parse(_))
+        );
+        assert_eq!(results[2].0, dir.join("c.json"));
+        assert_eq!(
+            results[2].1.as_ref().ok().map(ClangTrace::process_name),
+            Some("clang-c")
+        );
+
+        // Missing directories are reported upfront
+        assert!(ClangTrace::from_dir(dir.join("missing"), "*.json").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
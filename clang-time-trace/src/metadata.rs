@@ -1,10 +1,20 @@
 //! Parsing of metadata from clang's -ftime-trace output
 
-use crate::ctf::events::metadata::{MetadataEvent, MetadataOptions, NameArgs};
+use crate::ctf::{
+    events::metadata::{MetadataEvent, MetadataOptions, NameArgs},
+    Tid,
+};
+use serde_json as json;
+use std::collections::HashMap;
 use thiserror::Error;
 
-/// Parse the clang process name
-pub fn parse_process_name(m: MetadataEvent) -> Result<Box<str>, Box<NameParseError>> {
+/// Extra process metadata, beyond the process name
+pub type ProcessMetadata = HashMap<Box<str>, json::Value>;
+
+/// Parse the clang process name, along with any extra process metadata
+pub fn parse_process_name(
+    m: MetadataEvent,
+) -> Result<(Box<str>, ProcessMetadata), Box<NameParseError>> {
     match m {
         MetadataEvent::process_name {
             pid,
@@ -16,19 +26,18 @@ pub fn parse_process_name(m: MetadataEvent) -> Result<Box<str>, Box<NameParseErr
                     ts: Some(ts),
                     tts: None,
                 },
-        } if extra.is_empty()
-            && cat.0.is_empty()
+        } if cat.0.is_empty()
             && ts == 0.0
             && ((pid == 1 && tid == Some(0)) || tid == Some(pid)) =>
         {
-            Ok(name)
+            Ok((name, extra))
         }
         _ => Err(Box::new(NameParseError::UnexpectedInput(m))),
     }
 }
 
-/// Parse the clang thread name
-pub fn parse_thread_name(m: MetadataEvent) -> Result<Box<str>, Box<NameParseError>> {
+/// Parse the name of a clang thread, along with the thread's ID
+pub fn parse_thread_name(m: MetadataEvent) -> Result<(Tid, Box<str>), Box<NameParseError>> {
     match m {
         MetadataEvent::thread_name {
             tid,
@@ -40,7 +49,7 @@ pub fn parse_thread_name(m: MetadataEvent) -> Result<Box<str>, Box<NameParseErro
                     ts: Some(ts),
                     tts: None,
                 },
-        } if extra.is_empty() && cat.0.is_empty() && ts == 0.0 && pid == Some(tid) => Ok(name),
+        } if extra.is_empty() && cat.0.is_empty() && ts == 0.0 && pid.is_some() => Ok((tid, name)),
         _ => Err(Box::new(NameParseError::UnexpectedInput(m))),
     }
 }
@@ -57,8 +66,6 @@ pub enum NameParseError {
 mod tests {
     use super::*;
     use crate::{ctf::EventCategories, Pid};
-    use serde_json as json;
-    use std::collections::HashMap;
 
     fn test_name_parser(mock_name: &str, test_process_name: bool) {
         // Have a way to generate good and bad test inputs
@@ -87,11 +94,12 @@ mod tests {
         };
 
         // Valid parse_process_name input
-        let tested_parser = if test_process_name {
-            super::parse_process_name
-        } else {
-            super::parse_thread_name
-        };
+        let tested_parser: fn(MetadataEvent) -> Result<Box<str>, Box<NameParseError>> =
+            if test_process_name {
+                |m| super::parse_process_name(m).map(|(name, _extra)| name)
+            } else {
+                |m| super::parse_thread_name(m).map(|(_tid, name)| name)
+            };
         if test_process_name {
             // Only the process_name parser accepts legacy clang 10 PID/TID duos
             assert_eq!(
@@ -152,36 +160,41 @@ mod tests {
             ));
         }
 
-        // Unexpected (PID, TID) pair
-        test_unexpected_input(make_event(
-            true,
-            Some(42),
-            HashMap::new(),
-            Some(0),
-            Some(EventCategories::default()),
-            Some(0.0),
-            None,
-        ));
-        test_unexpected_input(make_event(
-            true,
-            Some(42),
-            HashMap::new(),
-            Some(43),
-            Some(EventCategories::default()),
-            Some(0.0),
-            None,
-        ));
+        // Unexpected (PID, TID) pair for process names, whereas any thread of
+        // the process may be named
+        for tid in [0, 43] {
+            let event = make_event(
+                true,
+                Some(42),
+                HashMap::new(),
+                Some(tid),
+                Some(EventCategories::default()),
+                Some(0.0),
+                None,
+            );
+            if test_process_name {
+                test_unexpected_input(event);
+            } else {
+                assert_eq!(super::parse_thread_name(event), Ok((tid, name.clone())));
+            }
+        }
 
-        // Unexpected extra metadata
-        test_unexpected_input(make_event(
+        // Extra metadata is preserved on process names, unexpected elsewhere
+        let extra = maplit::hashmap! { "wtf".into() => json::json!("") };
+        let event = make_event(
             true,
             Some(42),
-            maplit::hashmap! { "wtf".into() => json::json!("") },
+            extra.clone(),
             Some(42),
             Some(EventCategories::default()),
             Some(0.0),
             None,
-        ));
+        );
+        if test_process_name {
+            assert_eq!(super::parse_process_name(event), Ok((name.clone(), extra)));
+        } else {
+            test_unexpected_input(event);
+        }
 
         // Unexpected TID absence (process_name specific)
         if test_process_name {
@@ -4,6 +4,7 @@ use crate::{ClangTrace, InternedPath, PathError, PathKey};
 use cpp_demangle::{DemangleOptions, ParseOptions, Symbol as MangledSymbol};
 use cpparser::{nom, EntityKey, EntityParser, EntityView};
 use log::{info, warn};
+use serde::{Deserialize, Serialize};
 use std::{cell::RefCell, path::Path, rc::Rc};
 use thiserror::Error;
 
@@ -20,7 +21,7 @@ const CPP_DEMANGLE_RECURSION_LIMIT: u32 = 1024;
 // has already assessed that if a detail field should be present, it is present,
 // so the Option can be unwrapped with confidence.
 //
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct RawActivityArgument {
     /// How this activity argument should be parsed
     arg_type: ActivityArgumentType,
@@ -46,11 +47,25 @@ impl RawActivityArgument {
         self.detail.clone()
     }
 
+    /// Borrow the raw "detail" string from JSON, if any
+    pub fn detail_str(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Estimate of the heap memory used by the "detail" string, in bytes
+    pub(crate) fn memory_footprint(&self) -> usize {
+        self.detail.as_ref().map_or(0, |detail| {
+            // Rc allocations hold a strong and weak count besides the string
+            2 * std::mem::size_of::<usize>() + detail.len()
+        })
+    }
+
     /// Parse the activity argument
     pub fn parse(
         self,
         trace: &mut ClangTrace,
     ) -> Result<ParsedActivityArgument, ActivityArgumentError> {
+        trace.parsed_arguments += 1;
         let (parser, demangling_buf) = trace.parser_and_demangling_buf();
         self.parse_impl(parser, demangling_buf)
     }
@@ -58,8 +73,25 @@ impl RawActivityArgument {
     /// Lower-level version of parse() used in unit tests
     fn parse_impl(
         self,
-        parser: &mut EntityParser,
+        parser: &EntityParser,
         demangling_buf: &mut String,
+    ) -> Result<ParsedActivityArgument, ActivityArgumentError> {
+        self.parse_with_demangler(parser, |symbol| {
+            Self::demangle(symbol, demangling_buf).then(|| demangling_buf.as_str().into())
+        })
+    }
+
+    /// Version of parse() where symbol demangling is delegated to a callback
+    ///
+    /// The callback receives symbols with clang's surrounding parentheses
+    /// removed, and should return the demangled symbol if demangling succeeds.
+    /// This allows demangling to be carried out ahead of time, possibly in
+    /// parallel, while entity parsing remains on the thread owning the trace.
+    ///
+    pub(crate) fn parse_with_demangler(
+        self,
+        parser: &EntityParser,
+        mut demangle: impl FnMut(&str) -> Option<Rc<str>>,
     ) -> Result<ParsedActivityArgument, ActivityArgumentError> {
         let detail = RefCell::new(self.detail);
         let has_detail = || detail.borrow().is_some();
@@ -67,8 +99,8 @@ impl RawActivityArgument {
         let detail = || detail_opt().expect("Presence should be checked upstream");
         //
         let mut symbol_arg =
-            |parser: &mut EntityParser| -> Result<ParsedSymbol, ActivityArgumentError> {
-                Self::parse_symbol(detail(), parser, demangling_buf)
+            |parser: &EntityParser| -> Result<ParsedSymbol, ActivityArgumentError> {
+                Self::parse_symbol(detail(), parser, &mut demangle)
             };
         //
         let parse_unnamed_loop_arg =
@@ -118,33 +150,36 @@ impl RawActivityArgument {
                     Ok(ParsedActivityArgument::Nothing)
                 }
             }
+
+            ActivityArgumentType::Other => Ok(ParsedActivityArgument::Other(detail())),
         }
     }
 
     /// Parse a "detail" argument payload that contains a C++ entity name
-    fn parse_entity(
+    pub(crate) fn parse_entity(
         s: &str,
-        parser: &mut EntityParser,
+        parser: &EntityParser,
     ) -> Result<EntityKey, ActivityArgumentError> {
         parser.parse_entity(s).map_err(|e| {
             ActivityArgumentError::from(nom::error::Error::new(Box::<str>::from(e.input), e.code))
         })
     }
 
-    /// Parse a "detail" argument payload that contains a C++ symbol
-    fn parse_symbol(
-        mut symbol: Rc<str>,
-        parser: &mut EntityParser,
-        demangling_buf: &mut String,
-    ) -> Result<ParsedSymbol, ActivityArgumentError> {
+    /// Remove the parentheses that clang may put around symbol names
+    pub(crate) fn strip_symbol_parentheses(symbol: &str) -> &str {
         // Clang recently got this great idea of surrounding symbol names with
         // parentheses, which we must undo if needed
         if symbol.starts_with('(') && symbol.ends_with(')') {
-            symbol = Rc::from(&symbol[1..symbol.len() - 1]);
+            &symbol[1..symbol.len() - 1]
+        } else {
+            symbol
         }
+    }
 
+    /// Demangle a C++ symbol into a buffer, telling if demangling succeeded
+    pub(crate) fn demangle(symbol: &str, demangling_buf: &mut String) -> bool {
         let demangling_result = MangledSymbol::new_with_options(
-            &*symbol,
+            symbol,
             &ParseOptions::default().recursion_limit(CPP_DEMANGLE_RECURSION_LIMIT),
         )
         .map(|s| {
@@ -157,21 +192,34 @@ impl RawActivityArgument {
                     .recursion_limit(CPP_DEMANGLE_RECURSION_LIMIT),
             )
         });
+        matches!(demangling_result, Ok(Ok(())))
+    }
+
+    /// Parse a "detail" argument payload that contains a C++ symbol
+    fn parse_symbol(
+        mut symbol: Rc<str>,
+        parser: &EntityParser,
+        demangle: impl FnOnce(&str) -> Option<Rc<str>>,
+    ) -> Result<ParsedSymbol, ActivityArgumentError> {
+        let stripped = Self::strip_symbol_parentheses(&symbol);
+        if stripped.len() != symbol.len() {
+            symbol = Rc::from(stripped);
+        }
 
-        let mut parse_demangled = |entity: Rc<str>| -> ParsedSymbol {
+        let parse_demangled = |entity: Rc<str>| -> ParsedSymbol {
             if let Ok(parsed) = Self::parse_entity(&entity, parser) {
                 ParsedSymbol::Parsed(parsed)
             } else {
                 ParsedSymbol::Demangled(entity)
             }
         };
-        let parsed = match demangling_result {
+        let parsed = match demangle(&symbol) {
             // Mangled symbol was successfully demangled, intern it along with the rest
-            Ok(Ok(())) => parse_demangled(demangling_buf.clone().into()),
+            Some(demangled) => parse_demangled(demangled),
 
             // Symbol failed to demangle, try some patterns that cpp_demangle
             // should not reject but actually does reject before giving up
-            Ok(Err(_)) | Err(_) => match &*symbol {
+            None => match &*symbol {
                 "main" | "__clang_call_terminate" => parse_demangled(symbol),
                 _ => ParsedSymbol::MaybeMangled(symbol),
             },
@@ -207,7 +255,7 @@ impl RawActivityArgument {
 }
 
 /// Empirically observed activity argument parsing logics for time-trace entries
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum ActivityArgumentType {
     /// No argument
     Nothing,
@@ -232,6 +280,10 @@ pub enum ActivityArgumentType {
 
     /// Either the "<unnamed loop>" constant string or nothing
     UnnamedLoopOpt,
+
+    /// Arguments of an unknown activity that do not follow the usual layout
+    /// of a single "detail" string, kept in their JSON form
+    Other,
 }
 //
 impl ActivityArgumentType {
@@ -344,6 +396,9 @@ pub enum ParsedActivityArgument {
 
     /// The "[module]" constant string
     Module,
+
+    /// Arguments of an unknown kind, in JSON form
+    Other(Rc<str>),
 }
 //
 impl ParsedActivityArgument {
@@ -357,6 +412,7 @@ impl ParsedActivityArgument {
             ParsedActivityArgument::Symbol(m) => ActivityArgument::Symbol(m.resolve(trace)),
             ParsedActivityArgument::UnnamedLoop => ActivityArgument::UnnamedLoop,
             ParsedActivityArgument::Module => ActivityArgument::Module,
+            ParsedActivityArgument::Other(s) => ActivityArgument::Other(s),
         }
     }
 }
@@ -384,6 +440,14 @@ pub enum ActivityArgument<'trace> {
 
     /// The "[module]" constant string
     Module,
+
+    /// Arguments of an unknown kind, in JSON form
+    ///
+    /// Newer clang releases may emit activity arguments that this crate does
+    /// not know about. They are kept verbatim so that they can at least be
+    /// displayed, with keys sorted for the sake of reproducibility.
+    ///
+    Other(&'trace str),
 }
 
 /// Stored data about a C++ symbol that we tried to demangle and parse
@@ -463,11 +527,24 @@ mod tests {
                 arg_type: ActivityArgumentType::Nothing,
                 detail: None
             }
-            .parse_impl(&mut EntityParser::new(), &mut String::new()),
+            .parse_impl(&EntityParser::new(), &mut String::new()),
             Ok(ParsedActivityArgument::Nothing)
         );
     }
 
+    #[test]
+    fn parse_other() {
+        const MOCK_ARGS: &str = r#"{"bytes":1024}"#;
+        assert_eq!(
+            RawActivityArgument {
+                arg_type: ActivityArgumentType::Other,
+                detail: Some(MOCK_ARGS.into())
+            }
+            .parse_impl(&EntityParser::new(), &mut String::new()),
+            Ok(ParsedActivityArgument::Other(MOCK_ARGS.into()))
+        );
+    }
+
     #[test]
     fn parse_string() {
         const MOCK_STR: &str = "X86 DAG->DAG Instruction Selection";
@@ -476,7 +553,7 @@ mod tests {
                 arg_type: ActivityArgumentType::String,
                 detail: Some(MOCK_STR.into())
             }
-            .parse_impl(&mut EntityParser::new(), &mut String::new()),
+            .parse_impl(&EntityParser::new(), &mut String::new()),
             Ok(ParsedActivityArgument::String(MOCK_STR.into()))
         );
     }
@@ -485,14 +562,14 @@ mod tests {
     fn parse_path() {
         const MOCK_PATH: &str =
             "/mnt/acts/Core/include/Acts/TrackFinder/CombinatorialKalmanFilter.hpp";
-        let mut parser = EntityParser::new();
+        let parser = EntityParser::new();
         let path_key = parser.intern_path(MOCK_PATH);
         assert_eq!(
             RawActivityArgument {
                 arg_type: ActivityArgumentType::FilePathOrModule,
                 detail: Some(MOCK_PATH.into())
             }
-            .parse_impl(&mut parser, &mut String::new()),
+            .parse_impl(&parser, &mut String::new()),
             Ok(ParsedActivityArgument::FilePath(path_key))
         );
     }
@@ -500,7 +577,7 @@ mod tests {
     #[test]
     fn parse_entity() {
         const MOCK_ENTITY: &str = "Acts::Test::MeasurementCreator";
-        let mut parser = EntityParser::new();
+        let parser = EntityParser::new();
         let entity_key = parser
             .parse_entity(MOCK_ENTITY)
             .expect("Known-good parse, shouldn't fail");
@@ -509,7 +586,7 @@ mod tests {
                 arg_type: ActivityArgumentType::CppEntity,
                 detail: Some(MOCK_ENTITY.into())
             }
-            .parse_impl(&mut parser, &mut String::new()),
+            .parse_impl(&parser, &mut String::new()),
             Ok(ParsedActivityArgument::CppEntity(entity_key))
         );
     }
@@ -518,7 +595,7 @@ mod tests {
     fn parse_symbol() {
         // Commonalities betwwen symbol tests
         let test_symbol =
-            |entity_parser: &mut EntityParser, symbol: &str, expected_parse: ParsedSymbol| {
+            |entity_parser: &EntityParser, symbol: &str, expected_parse: ParsedSymbol| {
                 for arg_type in [
                     ActivityArgumentType::Symbol,
                     ActivityArgumentType::SymbolOpt,
@@ -528,27 +605,23 @@ mod tests {
                             arg_type,
                             detail: Some(symbol.into())
                         }
-                        .parse_impl(&mut *entity_parser, &mut String::new()),
+                        .parse_impl(entity_parser, &mut String::new()),
                         Ok(ParsedActivityArgument::Symbol(expected_parse.clone()))
                     );
                 }
             };
-        let mut parser = EntityParser::new();
+        let parser = EntityParser::new();
 
         // Symbol that demangles
         const VALID: &str = "_ZN4Acts4Test29comb_kalman_filter_zero_field11test_methodEv";
         let key = parser
             .parse_entity("Acts::Test::comb_kalman_filter_zero_field::test_method()")
             .expect("Known-good parse, shouldn't fail");
-        test_symbol(&mut parser, VALID, ParsedSymbol::Parsed(key));
+        test_symbol(&parser, VALID, ParsedSymbol::Parsed(key));
 
         // Symbol that doesn't demangle
         const INVALID: &str = "__cxx_global_var_init.1";
-        test_symbol(
-            &mut parser,
-            INVALID,
-            ParsedSymbol::MaybeMangled(INVALID.into()),
-        );
+        test_symbol(&parser, INVALID, ParsedSymbol::MaybeMangled(INVALID.into()));
 
         // Optional symbol that isn't present
         assert_eq!(
@@ -556,7 +629,7 @@ mod tests {
                 arg_type: ActivityArgumentType::SymbolOpt,
                 detail: None
             }
-            .parse_impl(&mut parser, &mut String::new()),
+            .parse_impl(&parser, &mut String::new()),
             Ok(ParsedActivityArgument::Nothing)
         );
     }
@@ -573,7 +646,7 @@ mod tests {
                     arg_type,
                     detail: Some("<unnamed loop>".into())
                 }
-                .parse_impl(&mut EntityParser::new(), &mut String::new()),
+                .parse_impl(&EntityParser::new(), &mut String::new()),
                 Ok(ParsedActivityArgument::UnnamedLoop)
             );
         }
@@ -584,7 +657,7 @@ mod tests {
                 arg_type: ActivityArgumentType::UnnamedLoopOpt,
                 detail: None
             }
-            .parse_impl(&mut EntityParser::new(), &mut String::new()),
+            .parse_impl(&EntityParser::new(), &mut String::new()),
             Ok(ParsedActivityArgument::Nothing)
         );
     }
@@ -5,19 +5,22 @@ pub mod argument;
 
 use self::argument::{ActivityArgumentType, RawActivityArgument};
 use super::ArgParseError;
-use crate::ctf::{events::duration::DurationEvent, Duration, Timestamp, TraceEvent};
+use crate::ctf::{
+    events::duration::DurationEvent, Duration, EventCategories, Tid, Timestamp, TraceEvent,
+};
 use phf::phf_map;
+use serde::{Deserialize, Serialize};
 use serde_json as json;
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{self, Display, Formatter},
     rc::Rc,
 };
 use thiserror::Error;
 
 /// Clang activity with timing information
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct ActivityStat {
     /// What clang was doing
     activity: Activity,
@@ -27,6 +30,12 @@ pub struct ActivityStat {
 
     /// How long it did it
     duration: Duration,
+
+    /// Thread which did it
+    tid: Tid,
+
+    /// Categories which this activity belongs to, if any
+    categories: EventCategories,
 }
 //
 impl ActivityStat {
@@ -37,9 +46,18 @@ impl ActivityStat {
             activity,
             start,
             duration,
+            tid: 0,
+            categories: EventCategories::default(),
         }
     }
 
+    /// Change the thread which did this activity
+    #[cfg(test)]
+    pub(crate) fn with_tid(mut self, tid: Tid) -> Self {
+        self.tid = tid;
+        self
+    }
+
     /// Decode a TraceEvent which is expected to contain a timed activity
     #[allow(clippy::result_large_err)]
     pub fn parse(t: TraceEvent) -> Result<Self, ActivityStatParseError> {
@@ -47,11 +65,11 @@ impl ActivityStat {
             TraceEvent::X {
                 duration_event:
                     DurationEvent {
-                        pid,
+                        pid: _,
                         tid,
                         ts,
                         name: Some(name),
-                        cat: None,
+                        cat,
                         tts: None,
                         args,
                         stack_trace: None,
@@ -59,12 +77,14 @@ impl ActivityStat {
                 dur,
                 tdur: None,
                 end_stack_trace: None,
-            } if (pid == 1 && tid == 0) || tid == pid => {
+            } => {
                 let activity = Activity::parse(name, args)?;
                 Ok(Self {
                     activity,
                     start: ts,
                     duration: dur,
+                    tid,
+                    categories: cat.unwrap_or_default(),
                 })
             }
             _ => Err(ActivityStatParseError::UnexpectedInput(t)),
@@ -90,6 +110,33 @@ impl ActivityStat {
     pub fn end(&self) -> Timestamp {
         self.start + self.duration
     }
+
+    /// Which thread did it
+    pub fn tid(&self) -> Tid {
+        self.tid
+    }
+
+    /// Categories which this activity belongs to
+    ///
+    /// Clang does not currently emit activity categories, but other tools that
+    /// produce or aggregate time-trace data may do so.
+    ///
+    pub fn categories(&self) -> &[Box<str>] {
+        &self.categories.0
+    }
+
+    /// Truth that this activity belongs to a certain category
+    pub fn has_category(&self, category: &str) -> bool {
+        self.categories().iter().any(|c| &**c == category)
+    }
+
+    /// Estimate of the heap memory owned by this activity, in bytes
+    pub(crate) fn memory_footprint(&self) -> usize {
+        let categories = self.categories();
+        self.activity.arg.memory_footprint()
+            + std::mem::size_of_val(categories)
+            + categories.iter().map(|c| c.len()).sum::<usize>()
+    }
 }
 
 /// What can go wrong while parsing an activity profile
@@ -105,7 +152,7 @@ pub enum ActivityStatParseError {
 }
 
 /// Activity that Clang can engage in during the compilation process
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Activity {
     /// Machine identifier that can be translated back into a human identifier
     pub(crate) id: ActivityId,
@@ -191,12 +238,17 @@ impl Activity {
                         Err(e) => Err(e),
                     }
                 }
+
+                ActivityArgumentType::Other => {
+                    unreachable!("Known activities have a known argument layout")
+                }
             };
             (id, arg_result)
         } else {
             // Otherwise, infer argument type using best-effort heuristics
             log::error!("Encountered unknown activity {name:?} with arguments {args:?}...");
             let id = ActivityId::UnknownActivity(Box::new(name));
+            let other_args = args.clone();
             let arg_result = match Self::parse_detail_arg(args) {
                 Ok(detail) => {
                     let arg_type = ActivityArgumentType::infer_from_detail(&detail);
@@ -207,7 +259,20 @@ impl Activity {
                     ActivityArgumentType::Nothing,
                     None,
                 )),
-                Err(e) => Err(e),
+                Err(_) => {
+                    // Arguments of an unknown layout are kept in JSON form
+                    let other_args = other_args
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect::<BTreeMap<_, _>>();
+                    let other_args = json::to_string(&other_args)
+                        .expect("JSON values should be serializable to JSON");
+                    log::error!("...which are kept in JSON form");
+                    Ok(RawActivityArgument::new(
+                        ActivityArgumentType::Other,
+                        Some(other_args.into()),
+                    ))
+                }
             };
             (id, arg_result)
         };
@@ -277,7 +342,7 @@ impl Activity {
 macro_rules! generate_activities {
     ($($string:literal => ($enum:ident, $arg:ident)),* $(,)?) => {
         /// Clang activity identifier
-        #[derive(Clone, Debug, Hash, Eq, PartialEq, strum::AsRefStr)]
+        #[derive(Clone, Debug, Deserialize, Hash, Eq, PartialEq, Serialize, strum::AsRefStr)]
         pub enum ActivityId {
             /// Unknown clang activity
             //
@@ -296,6 +361,20 @@ macro_rules! generate_activities {
         }
         //
         impl ActivityId {
+            /// Identifier of the activity with a certain name, as featured in
+            /// clang time-trace events, or UnknownActivity if it is not known
+            pub fn from_name(name: &str) -> Self {
+                ACTIVITIES
+                    .get(name)
+                    .map(|(id, _arg_type)| id.clone())
+                    .unwrap_or_else(|| ActivityId::UnknownActivity(Box::new(name.into())))
+            }
+
+            /// Names of all known clang activities, in no particular order
+            pub fn known_names() -> impl Iterator<Item = &'static str> {
+                ACTIVITIES.keys().copied()
+            }
+
             /// Activity name, as featured in clang time-trace events
             pub fn name(&self) -> &str {
                 if let ActivityId::UnknownActivity(name) = self {
@@ -471,11 +550,29 @@ mod tests {
             },
             start: 12.3,
             duration: 45.6,
+            tid: 42,
+            categories: EventCategories(vec!["frontend".into()].into_boxed_slice()),
         };
         assert_eq!(stat.activity(), &stat.activity);
         assert_eq!(stat.start(), stat.start);
         assert_eq!(stat.duration(), stat.duration);
         assert_eq!(stat.end(), stat.start + stat.duration);
+        assert_eq!(stat.tid(), stat.tid);
+        assert_eq!(stat.categories(), &stat.categories.0[..]);
+        assert!(stat.has_category("frontend"));
+        assert!(!stat.has_category("backend"));
+    }
+
+    #[test]
+    fn known_names() {
+        let names = ActivityId::known_names().collect::<Vec<_>>();
+        assert!(names.contains(&"InstantiateClass"));
+        for name in names {
+            let id = ActivityId::from_name(name);
+            assert!(!matches!(id, ActivityId::UnknownActivity(_)));
+            assert_eq!(id.name(), name);
+        }
+        assert!(!ActivityId::known_names().any(|name| name == "NotAnActivity"));
     }
 
     fn test_valid_activity(args: Option<HashMap<Box<str>, json::Value>>, expected: &Activity) {
@@ -520,6 +617,8 @@ mod tests {
                 activity: expected.clone(),
                 start,
                 duration,
+                tid: 0,
+                categories: EventCategories::default(),
             })
         );
         assert_eq!(
@@ -528,6 +627,40 @@ mod tests {
                 activity: expected.clone(),
                 start,
                 duration,
+                tid: 42,
+                categories: EventCategories::default(),
+            })
+        );
+        let categories = EventCategories::from(Box::<str>::from("frontend,cpp"));
+        assert_eq!(
+            ActivityStat::parse(make_event(
+                true,
+                42,
+                42,
+                Some(categories.clone()),
+                None,
+                None,
+                None,
+                None
+            ),),
+            Ok(ActivityStat {
+                activity: expected.clone(),
+                start,
+                duration,
+                tid: 42,
+                categories,
+            })
+        );
+
+        // Activities from secondary threads are accepted as well
+        assert_eq!(
+            ActivityStat::parse(make_event(true, 123, 456, None, None, None, None, None),),
+            Ok(ActivityStat {
+                activity: expected.clone(),
+                start,
+                duration,
+                tid: 456,
+                categories: EventCategories::default(),
             })
         );
 
@@ -539,17 +672,6 @@ mod tests {
             )
         };
         test_bad_input(make_event(false, 1, 0, None, None, None, None, None));
-        test_bad_input(make_event(true, 123, 456, None, None, None, None, None));
-        test_bad_input(make_event(
-            true,
-            1,
-            0,
-            Some(EventCategories::default()),
-            None,
-            None,
-            None,
-            None,
-        ));
         test_bad_input(make_event(true, 1, 0, None, Some(start), None, None, None));
         test_bad_input(make_event(
             true,
@@ -583,6 +705,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn activity_id_from_name() {
+        for (name, id) in [
+            ("Frontend", ActivityId::Frontend),
+            ("CodeGen Function", ActivityId::CodeGenFunction),
+            ("PassManager<Function>", ActivityId::FunctionPassManager),
+            (
+                "Frontendd",
+                ActivityId::UnknownActivity(Box::new("Frontendd".into())),
+            ),
+        ] {
+            let actual = ActivityId::from_name(name);
+            assert_eq!(actual, id);
+            assert_eq!(actual.name(), name);
+        }
+    }
+
     #[test]
     fn unknown_activity() {
         let activity = Box::<str>::from("ThisIsMadness");
@@ -595,6 +734,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_activity_arguments() {
+        let activity = Box::<str>::from("ThisIsMadness");
+        let parse = |args: &[(&str, json::Value)]| {
+            Activity::parse(
+                activity.clone(),
+                Some(
+                    args.iter()
+                        .map(|(k, v)| (Box::<str>::from(*k), v.clone()))
+                        .collect(),
+                ),
+            )
+        };
+        let expected = |arg_type, detail: &str| {
+            Ok(Activity {
+                id: ActivityId::UnknownActivity(Box::new(activity.clone())),
+                arg: RawActivityArgument::new(arg_type, Some(detail.into())),
+            })
+        };
+
+        // Unknown activities with a detail string get their argument inferred...
+        assert_eq!(
+            parse(&[("detail", json::json!("/path/to/file.cpp"))]),
+            expected(ActivityArgumentType::FilePathOrModule, "/path/to/file.cpp")
+        );
+
+        // ...and other arguments are kept in a reproducible JSON form
+        assert_eq!(
+            parse(&[
+                ("size", json::json!(42)),
+                ("detail", json::json!("Something")),
+                ("kind", json::json!({"b": 1, "a": [true]})),
+            ]),
+            expected(
+                ActivityArgumentType::Other,
+                r#"{"detail":"Something","kind":{"a":[true],"b":1},"size":42}"#
+            )
+        );
+        assert_eq!(
+            parse(&[("detail", json::json!(3.5))]),
+            expected(ActivityArgumentType::Other, r#"{"detail":3.5}"#)
+        );
+    }
+
     #[test]
     fn nullary_activities() {
         let nullary_test = |id: &ActivityId, arg_parser| {
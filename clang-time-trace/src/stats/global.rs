@@ -5,6 +5,7 @@ use crate::{
     ctf::{events::duration::DurationEvent, TraceEvent},
     Duration,
 };
+use serde::{Deserialize, Serialize};
 use serde_json as json;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -14,7 +15,7 @@ use thiserror::Error;
 /// According to the LLVM source code, this is the sum of of the durations of
 /// the topmost activities of this type in the LLVM call stack: if an activity
 /// recursively calls itself, double-counting is avoided.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct GlobalStat {
     /// Execution duration
     total_duration: Duration,
@@ -32,6 +33,17 @@ impl GlobalStat {
         }
     }
 
+    /// Truth that a duration event looks like a "Total *" aggregate
+    ///
+    /// Such events sum up the durations of other events, so they should not
+    /// be mistaken for activities even if they have a nonzero timestamp.
+    pub fn is_total(event: &DurationEvent) -> bool {
+        event
+            .name
+            .as_deref()
+            .is_some_and(|name| name.starts_with("Total "))
+    }
+
     /// Decode a TraceEvent which is expected to contain global statistics
     #[allow(clippy::result_large_err)]
     pub fn parse(t: TraceEvent) -> Result<(Box<str>, Self), GlobalStatParseError> {
@@ -51,7 +63,7 @@ impl GlobalStat {
                 dur,
                 tdur: None,
                 end_stack_trace: None,
-            } if tid != 0 && ts == 0.0 => {
+            } if tid != 0 && (ts == 0.0 || name.starts_with("Total ")) => {
                 // Global stats should have a name starting with a "Total "
                 // string, which we shall strip since it brings no useful info
                 let name = if let Some(stripped) = name.strip_prefix("Total ") {
@@ -1,9 +1,10 @@
 //! Caller<->callee tree of activities which clang engaged in
 
 use crate::{
-    ctf::{Duration, Timestamp},
+    ctf::{Duration, Tid, Timestamp},
     stats::activity::{Activity, ActivityStat},
 };
+use serde::{Deserialize, Serialize};
 use std::{
     fmt::{self, Debug, Formatter},
     num::NonZeroUsize,
@@ -13,7 +14,7 @@ use std::{
 use thiserror::Error;
 
 /// Tree of activities which clang engaged in
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct ActivityTree {
     /// Clang activities recorded by -ftime-trace
     activities: Box<[ActivityNode]>,
@@ -31,6 +32,10 @@ pub struct ActivityTree {
 
     /// Start of the list of root activities, at the end of the activity_tree
     first_root_idx: usize,
+
+    /// Number of activities whose self-duration was clamped to zero
+    #[serde(default)]
+    clamped_self_durations: usize,
 }
 //
 impl ActivityTree {
@@ -79,10 +84,37 @@ impl ActivityTree {
     pub fn activity_trace(&self, id: ActivityTraceId) -> ActivityTrace {
         ActivityTrace {
             tree: self,
-            activity: &self.activities[id],
-            activity_idx: id,
+            activity: &self.activities[id.0],
+            activity_idx: id.0,
         }
     }
+
+    /// Number of activities that clang engaged in
+    ///
+    /// See `ClangTrace::activity_count` for documentation.
+    ///
+    pub fn activity_count(&self) -> usize {
+        self.activities.len()
+    }
+
+    /// Number of activities whose self-duration was clamped to zero
+    ///
+    /// See `ClangTrace::clamped_self_durations` for documentation.
+    ///
+    pub fn clamped_self_durations(&self) -> usize {
+        self.clamped_self_durations
+    }
+
+    /// Estimate of the heap memory used by the activity tree, in bytes
+    pub fn memory_footprint(&self) -> usize {
+        std::mem::size_of_val(&self.activities[..])
+            + std::mem::size_of_val(&self.children[..])
+            + self
+                .activities
+                .iter()
+                .map(|node| node.stat.memory_footprint())
+                .sum::<usize>()
+    }
 }
 
 /// Hierarchical view of an activity which clang engaged in
@@ -101,7 +133,7 @@ pub struct ActivityTrace<'a> {
 impl ActivityTrace<'_> {
     /// Identifier that can be used to refer to this ActivityTrace
     pub fn id(&self) -> ActivityTraceId {
-        self.activity_idx
+        ActivityTraceId(self.activity_idx)
     }
 
     /// What clang was doing
@@ -109,7 +141,20 @@ impl ActivityTrace<'_> {
         self.activity.stat.activity()
     }
 
+    /// Argument of this activity, verbatim from the trace's "detail" string
+    ///
+    /// This does not go through argument parsing, and is thus available even
+    /// for arguments that fail to parse. None means that clang did not emit
+    /// any "detail" string for this activity.
+    ///
+    pub fn raw_argument_str(&self) -> Option<&str> {
+        self.activity().raw_argument().detail_str()
+    }
+
     /// When clang started doing this activity
+    ///
+    /// This is the original `ts` of the trace event, in the trace's
+    /// microsecond time base (see also `ClangTrace::beginning_of_time()`).
     pub fn start(&self) -> Timestamp {
         self.activity.stat.start()
     }
@@ -125,10 +170,36 @@ impl ActivityTrace<'_> {
     }
 
     /// When clang stopped doing this activity
+    ///
+    /// This is the original `ts + dur` of the trace event, in the same time
+    /// base as `start()`.
     pub fn end(&self) -> Timestamp {
         self.activity.stat.end()
     }
 
+    /// Thread which performed this activity
+    pub fn tid(&self) -> Tid {
+        self.activity.stat.tid()
+    }
+
+    /// Categories which this activity belongs to
+    ///
+    /// See `ActivityStat::categories()` for documentation.
+    ///
+    pub fn categories(&self) -> &[Box<str>] {
+        self.activity.stat.categories()
+    }
+
+    /// Truth that this activity belongs to a certain category
+    pub fn has_category(&self, category: &str) -> bool {
+        self.activity.stat.has_category(category)
+    }
+
+    /// Truth that this activity did not spawn any other activity
+    pub fn is_leaf(&self) -> bool {
+        self.activity.children_indices.is_empty()
+    }
+
     /// Activities that were directly spawned by this activity
     ///
     /// Like `ClangTrace::root_activities()`, but for children of one activity
@@ -169,6 +240,55 @@ impl ActivityTrace<'_> {
             }
         })
     }
+
+    /// Share of this activity's duration in the duration of its parent
+    ///
+    /// Root activities have no parent, and are considered to take up all of
+    /// the time that their nonexistent parent would have spent. Children of a
+    /// parent with zero duration are considered to take up none of its time.
+    ///
+    pub fn parent_fraction(&self) -> f64 {
+        self.parent()
+            .map_or(1.0, |parent| fraction(self.duration(), parent.duration()))
+    }
+
+    /// Share of this activity's duration in the total duration of the root
+    /// activities of its tree
+    ///
+    /// If the root activities have a total duration of zero, this is zero.
+    ///
+    pub fn root_fraction(&self) -> f64 {
+        let root_duration = self
+            .tree
+            .root_activities()
+            .map(|root| root.duration())
+            .sum::<Duration>();
+        fraction(self.duration(), root_duration)
+    }
+}
+//
+impl<'a> ActivityTrace<'a> {
+    /// Activities that were spawned while processing this activity, either
+    /// directly or indirectly, in depth-first pre-order
+    ///
+    /// This yields the same activities as `all_children()`, but each activity
+    /// comes right before its own children, which suits hierarchical displays.
+    ///
+    pub fn descendants(&self) -> impl Iterator<Item = ActivityTrace<'a>> + Clone {
+        self.descendants_with_depth()
+            .map(|(activity, _depth)| activity)
+    }
+
+    /// Like `descendants()`, but also tells the depth of each activity below
+    /// this one, where direct children have a depth of 1
+    pub fn descendants_with_depth(
+        &self,
+    ) -> impl Iterator<Item = (ActivityTrace<'a>, usize)> + Clone {
+        Descendants {
+            tree: self.tree,
+            stack: vec![self.activity.children_indices.clone()],
+        }
+    }
 }
 //
 impl Debug for ActivityTrace<'_> {
@@ -181,11 +301,58 @@ impl Debug for ActivityTrace<'_> {
     }
 }
 
+/// Depth-first iterator over the descendants of an activity
+#[derive(Clone)]
+struct Descendants<'a> {
+    /// Tree which the activities belong to
+    tree: &'a ActivityTree,
+
+    /// Children that remain to be visited at each depth, as ranges of indices
+    /// in the global ActivityTree::children array
+    stack: Vec<Range<usize>>,
+}
+//
+impl<'a> Iterator for Descendants<'a> {
+    type Item = (ActivityTrace<'a>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.stack.len();
+            let Some(child_idx) = self.stack.last_mut()?.next() else {
+                self.stack.pop();
+                continue;
+            };
+            let activity_idx = self.tree.children[child_idx];
+            let activity = &self.tree.activities[activity_idx];
+            self.stack.push(activity.children_indices.clone());
+            let trace = ActivityTrace {
+                tree: self.tree,
+                activity,
+                activity_idx,
+            };
+            return Some((trace, depth));
+        }
+    }
+}
+
 /// Identifier that can be used to refer to an ActivityTrace
-pub type ActivityTraceId = usize;
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ActivityTraceId(usize);
+//
+impl ActivityTraceId {
+    /// Dense index of this activity within the trace
+    ///
+    /// Activity indices go from 0 to `ClangTrace::activity_count()` (excluded)
+    /// and each activity of a trace has a distinct index. They can therefore
+    /// be used to store per-activity data in a `Vec` rather than a `HashMap`.
+    ///
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
 
 /// Individual clang activity within the activity tree
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
 struct ActivityNode {
     /// Activity nature and associated time span
     stat: ActivityStat,
@@ -220,6 +387,15 @@ pub struct ActivityTreeBuilder {
 
     /// Final timestamp of the last collected activity
     last_end: Timestamp,
+
+    /// Thread of the last collected activity, if any
+    current_tid: Option<Tid>,
+
+    /// Index of the first activity of the current thread in `activities`
+    thread_start_idx: usize,
+
+    /// Number of self-durations that were clamped to zero so far
+    clamped_self_durations: usize,
 }
 //
 impl ActivityTreeBuilder {
@@ -230,6 +406,9 @@ impl ActivityTreeBuilder {
             activities: Vec::with_capacity(capacity),
             children: Vec::with_capacity(capacity),
             last_end: Timestamp::MIN,
+            current_tid: None,
+            thread_start_idx: 0,
+            clamped_self_durations: 0,
         }
     }
 
@@ -239,7 +418,29 @@ impl ActivityTreeBuilder {
     /// of ascending end timestamp, as clang does (and is expected to continue
     /// doing undefinitely, as that's the simplest implementation for them).
     ///
+    /// Activities from different threads are not nested into each other, each
+    /// thread gets its own set of roots. They must be provided one thread
+    /// after another, in order of increasing thread ID, and the end timestamp
+    /// ordering requirement only applies within a thread.
+    ///
     pub fn insert(&mut self, activity: ActivityStat) -> Result<(), ActivityTreeError> {
+        // Start a new thread block when the thread changes
+        let tid = activity.tid();
+        match self.current_tid {
+            Some(current_tid) if tid < current_tid => {
+                return Err(ActivityTreeError::UnexpectedThreadOrder {
+                    prev: current_tid,
+                    current: tid,
+                });
+            }
+            Some(current_tid) if tid == current_tid => {}
+            _ => {
+                self.current_tid = Some(tid);
+                self.thread_start_idx = self.activities.len();
+                self.last_end = Timestamp::MIN;
+            }
+        }
+
         // Check assumption that activities are provided in order of increasing
         // end timestamp (this means that a parent activity follows the sequence
         // of its transitive children, which eases tree building).
@@ -257,14 +458,15 @@ impl ActivityTreeBuilder {
         let current_idx = self.activities.len();
         let first_child_idx = self.children.len();
         let mut first_related_idx = current_idx;
-        let mut child_candidates = &mut self.activities[..];
+        let thread_start_idx = self.thread_start_idx;
+        let mut child_candidates = &mut self.activities[thread_start_idx..];
         let mut children_duration = 0.0;
         //
         while let Some((candidate, next_candidates)) = child_candidates.split_last_mut() {
             // Abort once we find a candidate which starts and ends before we do:
             // that's not a child, and we know no further child will come before
             // that through the input ordering property asserted above.
-            let candidate_idx = next_candidates.len();
+            let candidate_idx = thread_start_idx + next_candidates.len();
             if candidate.stat.start() < start {
                 if candidate.stat.end() <= start {
                     break;
@@ -286,15 +488,20 @@ impl ActivityTreeBuilder {
             // Ignore transitive children of this child for direct child lookup,
             // but add them to our set of transitive children.
             first_related_idx = candidate.first_related_idx;
-            child_candidates = &mut next_candidates[..first_related_idx];
+            child_candidates = &mut next_candidates[..first_related_idx - thread_start_idx];
         }
 
         // Append this children list at the end of the tree and
         // reset the accumulator.
         let children_indices = first_child_idx..self.children.len();
 
-        // Fill profile
-        let self_duration = activity.duration() - children_duration;
+        // Fill profile, treating slightly negative self-durations caused by
+        // floating-point rounding as zero
+        let mut self_duration = activity.duration() - children_duration;
+        if self_duration < 0.0 && self_duration >= -SELF_DURATION_TOLERANCE * activity.duration() {
+            self_duration = 0.0;
+            self.clamped_self_durations += 1;
+        }
         self.activities.push(ActivityNode {
             stat: activity,
             first_related_idx,
@@ -331,10 +538,28 @@ impl ActivityTreeBuilder {
             activities: self.activities.into_boxed_slice(),
             children: self.children.into_boxed_slice(),
             first_root_idx,
+            clamped_self_durations: self.clamped_self_durations,
         }
     }
 }
 
+/// Ratio of a duration to a total duration, which is zero if the total is zero
+fn fraction(duration: Duration, total: Duration) -> f64 {
+    if total == 0.0 {
+        0.0
+    } else {
+        duration / total
+    }
+}
+
+/// Relative tolerance below which negative self-durations are clamped to zero
+///
+/// Self-durations are computed by subtracting the children durations from the
+/// activity duration, which can yield tiny negative values due to rounding
+/// when the children take up all of the activity's time.
+///
+const SELF_DURATION_TOLERANCE: Duration = 1e-9;
+
 /// What can go wrong while inserting activities into an ActivityTree
 #[derive(Error, Debug, PartialEq)]
 pub enum ActivityTreeError {
@@ -348,6 +573,16 @@ pub enum ActivityTreeError {
         current: Timestamp,
     },
 
+    /// Activities were not grouped by thread in increasing thread ID order
+    #[error("activities not grouped by increasing thread ID (thread {current} comes after thread {prev})")]
+    UnexpectedThreadOrder {
+        /// Activities from this thread...
+        prev: Tid,
+
+        /// ...were followed by activities from this lower-numbered thread
+        current: Tid,
+    },
+
     /// Activities do not properly nest, as mandated by the CTF format
     #[error("activities do not nest ({prev_desc} partially overlaps {current_desc})")]
     PartialActivityOverlap {
@@ -408,6 +643,8 @@ mod tests {
         assert_le!(node.start(), node.end());
         assert_eq!(node.duration(), node.end() - node.start());
         assert_le!(node.self_duration(), node.duration());
+        assert_eq!(node.tid(), node.activity.stat.tid());
+        assert_eq!(node.categories(), node.activity.stat.categories());
 
         // Test parent lookup
         let tree = &node.tree;
@@ -438,6 +675,9 @@ mod tests {
             );
         }
 
+        // Test leaf detection
+        assert_eq!(node.is_leaf(), node.direct_children().next().is_none());
+
         // Test hierarchical child iterator + individual child nodes
         for (child_activity, &expected_idx) in node
             .direct_children()
@@ -494,6 +734,7 @@ mod tests {
                 activities: vec![activity_node].into_boxed_slice(),
                 children: vec![0].into_boxed_slice(),
                 first_root_idx: 0,
+                clamped_self_durations: 0,
             }
         );
         test_tree(tree);
@@ -598,11 +839,61 @@ mod tests {
                 activities: vec![subchild1, child1, subchild2, child2, root].into_boxed_slice(),
                 children: vec![0, 2, 3, 1, 4].into_boxed_slice(),
                 first_root_idx: 4,
+                clamped_self_durations: 0,
             }
         );
+
+        // Check duration fractions
+        let root = tree.root_activities().next().unwrap();
+        assert_eq!(root.parent_fraction(), 1.0);
+        assert_eq!(root.root_fraction(), 1.0);
+        let backend = root
+            .direct_children()
+            .find(|child| child.activity().id() == &ActivityId::Backend)
+            .unwrap();
+        assert_eq!(backend.parent_fraction(), 0.6e6 / 4.2e6);
+        assert_eq!(backend.root_fraction(), 0.6e6 / 4.2e6);
+        let codegen = backend.direct_children().next().unwrap();
+        assert_eq!(codegen.parent_fraction(), 0.4e6 / 0.6e6);
+        assert_eq!(codegen.root_fraction(), 0.4e6 / 4.2e6);
         test_tree(tree);
     }
 
+    #[test]
+    fn clamp_negative_self_duration() {
+        // Children which take up all of their parent's time, with durations
+        // such that 0.3 - (0.1 + 0.2) is slightly negative in floating-point
+        let mut builder = ActivityTreeBuilder::with_capacity(3);
+        let activity = |id, start, duration| {
+            ActivityStat::new(
+                Activity {
+                    id,
+                    arg: RawActivityArgument::new(ActivityArgumentType::Nothing, None),
+                },
+                start,
+                duration,
+            )
+        };
+        assert_lt!(0.3 - (0.1 + 0.2), 0.0);
+        static EXPECT_MSG: &str = "Test is designed so this doesn't fail";
+        builder
+            .insert(activity(ActivityId::Frontend, 0.1, 0.1))
+            .expect(EXPECT_MSG);
+        builder
+            .insert(activity(ActivityId::Backend, 0.2, 0.2))
+            .expect(EXPECT_MSG);
+        builder
+            .insert(activity(ActivityId::ExecuteCompiler, 0.1, 0.3))
+            .expect(EXPECT_MSG);
+        let tree = builder.build();
+
+        // The root's self-duration should have been clamped, and counted
+        let root = tree.root_activities().next().unwrap();
+        assert_eq!(root.direct_children().count(), 2);
+        assert_eq!(root.self_duration(), 0.0);
+        assert_eq!(tree.clamped_self_durations(), 1);
+    }
+
     #[test]
     fn build_error_unordered_timestamps() {
         let mut builder = ActivityTreeBuilder::with_capacity(2);
@@ -664,4 +955,97 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn build_multi_thread_tree() {
+        // Thread 1 has a root with one child, thread 2 has a single root that
+        // overlaps both of them in time without being related to them
+        let make_stat = |start, duration, tid| {
+            ActivityStat::new(
+                Activity {
+                    id: ActivityId::ExecuteCompiler,
+                    arg: RawActivityArgument::new(ActivityArgumentType::Nothing, None),
+                },
+                start,
+                duration,
+            )
+            .with_tid(tid)
+        };
+        let mut builder = ActivityTreeBuilder::with_capacity(3);
+        for stat in [
+            make_stat(1.0, 2.0, 1),
+            make_stat(0.0, 10.0, 1),
+            make_stat(0.5, 4.5, 2),
+        ] {
+            builder
+                .insert(stat)
+                .expect("Test is designed so this doesn't fail");
+        }
+        let tree = builder.build();
+
+        // Each thread gets its own roots, and children stay in their thread
+        let roots = tree.root_activities().collect::<Vec<_>>();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].tid(), 2);
+        assert!(roots[0].is_leaf());
+        assert_eq!(roots[1].tid(), 1);
+        let children = roots[1].direct_children().collect::<Vec<_>>();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].tid(), 1);
+        assert_eq!(roots[1].self_duration(), 8.0);
+        test_tree(tree);
+    }
+
+    #[test]
+    fn build_error_unordered_threads() {
+        let mut builder = ActivityTreeBuilder::with_capacity(2);
+        let activity = ActivityStat::new(
+            Activity {
+                id: ActivityId::ExecuteCompiler,
+                arg: RawActivityArgument::new(ActivityArgumentType::Nothing, None),
+            },
+            1.2,
+            3.4,
+        );
+        builder
+            .insert(activity.clone().with_tid(2))
+            .expect("Test is designed so this doesn't fail");
+        assert_eq!(
+            builder.insert(activity.with_tid(1)),
+            Err(ActivityTreeError::UnexpectedThreadOrder {
+                prev: 2,
+                current: 1
+            })
+        );
+    }
+
+    #[test]
+    fn zero_duration_fractions() {
+        // Build a tree where a zero-duration root has a zero-duration child
+        let make_stat = |start| {
+            ActivityStat::new(
+                Activity {
+                    id: ActivityId::ExecuteCompiler,
+                    arg: RawActivityArgument::new(ActivityArgumentType::Nothing, None),
+                },
+                start,
+                0.0,
+            )
+        };
+        let mut builder = ActivityTreeBuilder::with_capacity(2);
+        for stat in [make_stat(1.0), make_stat(1.0)] {
+            builder
+                .insert(stat)
+                .expect("Test is designed so this doesn't fail");
+        }
+        let tree = builder.build();
+
+        // Fractions of a zero duration are zero rather than NaN
+        let root = tree.root_activities().next().unwrap();
+        assert_eq!(root.parent_fraction(), 1.0);
+        assert_eq!(root.root_fraction(), 0.0);
+        let child = root.direct_children().next().unwrap();
+        assert_eq!(child.parent_fraction(), 0.0);
+        assert_eq!(child.root_fraction(), 0.0);
+    }
 }
@@ -4,13 +4,18 @@
 //       https://github.com/proptest-rs/proptest/issues/447
 #![allow(unknown_lints, non_local_definitions)]
 
+use fnv::FnvHasher;
 use serde::Deserialize;
 use serde_json as json;
 use shlex::Shlex;
 use std::{
-    collections::HashMap,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    hash::{Hash, Hasher},
     io,
     path::{Path, PathBuf},
+    process::Command,
     str::FromStr,
     time::Duration,
 };
@@ -82,15 +87,77 @@ impl CompilationDatabase {
         self.0.get(input_path)
     }
 
+    /// List the database entries affected by changes since a git revision
+    ///
+    /// An entry is affected if its input file, or one of the headers listed in
+    /// its depfile (see `DatabaseEntry::depfile()`), differs from `git_ref`
+    /// in the working tree, or is untracked.
+    ///
+    /// Git is run in the working directory, which must be located inside of
+    /// the git repository that contains the sources.
+    ///
+    pub fn entries_changed_since(
+        &self,
+        git_ref: &str,
+    ) -> Result<Vec<&DatabaseEntry>, ChangedFilesError> {
+        // Collect the paths of changed files
+        let toplevel = PathBuf::from(git(&["rev-parse", "--show-toplevel"])?.trim_end());
+        let changed = git(&["diff", "--name-only", "-z", git_ref, "--"])?;
+        let untracked = git(&[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "-z",
+            "--full-name",
+        ])?;
+        let changed = changed
+            .split('\0')
+            .chain(untracked.split('\0'))
+            .filter(|path| !path.is_empty())
+            .map(|path| canonicalize(&toplevel.join(path)))
+            .collect::<HashSet<_>>();
+
+        // Find the entries that depend on them
+        Ok(self
+            .entries()
+            .filter(|entry| {
+                changed.contains(&entry.absolute_input())
+                    || entry
+                        .dependencies()
+                        .iter()
+                        .any(|dep| changed.contains(&canonicalize(dep)))
+            })
+            .collect())
+    }
+
     /// Check if a full-build profile seems up to date
     pub fn profile_freshness(&self, path: &Path) -> io::Result<ProductFreshness> {
-        Self::product_freshness(self.entries().map(DatabaseEntry::input), path)
+        self.profile_freshness_with(path, FreshnessCheck::Mtime)
+    }
+
+    /// Like `profile_freshness()`, but with a configurable freshness check
+    pub fn profile_freshness_with(
+        &self,
+        path: &Path,
+        check: FreshnessCheck,
+    ) -> io::Result<ProductFreshness> {
+        Self::product_freshness(self.0.values(), path, check)
+    }
+
+    /// Record the content hash of the inputs of a full-build profile
+    ///
+    /// This should be called after the profile has been successfully produced,
+    /// for the benefit of later `FreshnessCheck::ContentHash` checks.
+    ///
+    pub fn save_profile_hash(&self, path: &Path) -> io::Result<()> {
+        Self::save_content_hash(self.0.values(), path)
     }
 
-    /// Check if some build derivative seems up to date
+    /// Check if some build derivative of a set of entries seems up to date
     fn product_freshness<'a>(
-        inputs: impl Iterator<Item = &'a Path>,
+        entries: impl Iterator<Item = &'a DatabaseEntry> + Clone,
         output: impl AsRef<Path>,
+        check: FreshnessCheck,
     ) -> io::Result<ProductFreshness> {
         // Check build product existence and mtime
         let output = output.as_ref();
@@ -102,14 +169,59 @@ impl CompilationDatabase {
             Err(other) => return Err(other),
         };
 
+        // If a content hash was recorded, it is authoritative
+        if check == FreshnessCheck::ContentHash {
+            match std::fs::read_to_string(Self::content_hash_path(output)) {
+                Ok(stored) => {
+                    return Ok(
+                        if u64::from_str_radix(stored.trim(), 16).ok()
+                            == Some(Self::content_hash(entries)?)
+                        {
+                            ProductFreshness::Fresh
+                        } else {
+                            ProductFreshness::Outdated
+                        },
+                    );
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(other) => return Err(other),
+            }
+        }
+
         // Compare product mtime to compilation database mtime
         if product_mtime < Path::new(Self::location()).metadata()?.modified()? {
             return Ok(ProductFreshness::Outdated);
         }
 
         // Compare to mtime of every input file
-        for input in inputs {
-            if product_mtime < input.metadata()?.modified()? {
+        for entry in entries.clone() {
+            if product_mtime < entry.input().metadata()?.modified()? {
+                return Ok(ProductFreshness::Outdated);
+            }
+        }
+
+        // Compare to mtime of every compiler, since compiler upgrades also
+        // invalidate build products. Compilers which cannot be found are
+        // ignored, as they would not affect freshness before.
+        let mut compilers = HashSet::new();
+        let mut unresolved = HashSet::new();
+        for entry in entries {
+            match entry.compiler_path() {
+                Some(compiler) => {
+                    compilers.insert(compiler);
+                }
+                None => {
+                    let program = entry.program().map(|p| p.as_ref().to_owned());
+                    if unresolved.insert(program.clone()) {
+                        log::warn!(
+                            "Could not find compiler {program:?}, ignoring it in freshness checks"
+                        );
+                    }
+                }
+            }
+        }
+        for compiler in compilers {
+            if product_mtime < compiler.metadata()?.modified()? {
                 return Ok(ProductFreshness::Outdated);
             }
         }
@@ -120,17 +232,101 @@ impl CompilationDatabase {
             product_mtime.elapsed().ok(),
         ))
     }
+
+    /// Location of the content hash associated with a build product
+    fn content_hash_path(output: &Path) -> PathBuf {
+        let mut path = OsString::from(output);
+        path.push(".crofiler-hash");
+        path.into()
+    }
+
+    /// Hash the build commands and input file contents of a set of entries
+    fn content_hash<'a>(entries: impl Iterator<Item = &'a DatabaseEntry>) -> io::Result<u64> {
+        let mut entries = entries.collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|entry| entry.input());
+        let mut hasher = FnvHasher::default();
+        for entry in entries {
+            entry.directory.hash(&mut hasher);
+            entry.command.hash(&mut hasher);
+            entry.file.hash(&mut hasher);
+            let contents = std::fs::read(entry.absolute_input())?;
+            contents.hash(&mut hasher);
+        }
+        Ok(hasher.finish())
+    }
+
+    /// Record the content hash of a set of entries next to their build product
+    fn save_content_hash<'a>(
+        entries: impl Iterator<Item = &'a DatabaseEntry>,
+        output: &Path,
+    ) -> io::Result<()> {
+        std::fs::write(
+            Self::content_hash_path(output),
+            format!("{:016x}\n", Self::content_hash(entries)?),
+        )
+    }
 }
 //
 impl FromStr for CompilationDatabase {
-    type Err = json::Error;
+    type Err = DatabaseParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let entries = json::from_str::<Vec<DatabaseEntry>>(s)?;
+        // Parse the outer JSON array leniently first, then validate entries
+        // one by one so that errors can point to the faulty entry
+        let raw_entries = json::from_str::<Vec<json::Value>>(s)?;
+        let entries = raw_entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, raw_entry)| {
+                DatabaseEntry::deserialize(&raw_entry).map_err(|error| {
+                    DatabaseParseError::BadEntry {
+                        index,
+                        context: DatabaseParseError::context(&raw_entry),
+                        error,
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Self::from_entries(entries))
     }
 }
 
+/// Failure to parse a compilation database
+#[derive(Debug, Error)]
+pub enum DatabaseParseError {
+    /// Compilation database is not a JSON array
+    #[error("expected a JSON array of entries ({0})")]
+    BadJson(#[from] json::Error),
+
+    /// One of the compilation database entries is malformed
+    #[error("entry #{index} is invalid ({error}) in {context}")]
+    BadEntry {
+        /// Index of the entry within the database, starting at 0
+        index: usize,
+
+        /// Excerpt of the entry's JSON source
+        context: Box<str>,
+
+        /// What's wrong with the entry (missing field, unexpected type...)
+        error: json::Error,
+    },
+}
+//
+impl DatabaseParseError {
+    /// Maximal length of the JSON excerpt used as error context
+    const MAX_CONTEXT_LEN: usize = 80;
+
+    /// Produce a short excerpt of a bad JSON entry for error reporting
+    fn context(raw_entry: &json::Value) -> Box<str> {
+        let mut context = raw_entry.to_string();
+        if let Some((end, _)) = context.char_indices().nth(Self::MAX_CONTEXT_LEN) {
+            context.truncate(end);
+            context.push('…');
+        }
+        context.into()
+    }
+}
+
 /// Failure to load the CompilationDatabase from disk
 #[derive(Debug, Error)]
 pub enum DatabaseLoadError {
@@ -144,7 +340,84 @@ pub enum DatabaseLoadError {
 
     /// Failed to parse the compilation database
     #[error("failed to parse compilation database ({0})")]
-    ParseError(#[from] json::Error),
+    ParseError(#[from] DatabaseParseError),
+}
+
+/// Failure to find out which database entries were affected by changes
+#[derive(Debug, Error)]
+pub enum ChangedFilesError {
+    /// Failed to run git
+    #[error("failed to run git ({0})")]
+    GitUnavailable(#[from] io::Error),
+
+    /// Git reported an error, e.g. because this is not a git repository
+    #[error("git {0} failed ({1})")]
+    GitFailed(Box<str>, Box<str>),
+}
+
+/// GCC-like compiler flags whose operand is passed as a separate argument
+const OPERAND_FLAGS: &[&str] = &[
+    "-o",
+    "-MF",
+    "-MT",
+    "-MQ",
+    "-I",
+    "-D",
+    "-U",
+    "-x",
+    "-include",
+    "-imacros",
+    "-isystem",
+    "-iquote",
+    "-idirafter",
+    "-iprefix",
+    "-iwithprefix",
+    "-iwithprefixbefore",
+    "-isysroot",
+    "-Xclang",
+    "-Xpreprocessor",
+    "-Xassembler",
+    "-Xlinker",
+    "-arch",
+    "-target",
+];
+
+/// Failure to determine the output file of a compilation database entry
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum OutputError {
+    /// The command has neither a `-c` nor a `-o` flag
+    #[error("command does not look like a compilation command")]
+    NotACompileCommand,
+
+    /// The command compiles something, but has no `-o` flag
+    #[error("compilation command has no -o flag")]
+    NoOutputFlag,
+
+    /// The `-o` flag is the last argument of the command
+    #[error("-o flag is not followed by an output path")]
+    MissingOutputPath,
+
+    /// The output path does not end with a file name
+    #[error("output path {0:?} has no file name")]
+    NoFileName(Box<Path>),
+}
+
+/// Run a git command in the working directory, returning its output
+fn git(args: &[&str]) -> Result<String, ChangedFilesError> {
+    let output = Command::new("git").args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(ChangedFilesError::GitFailed(
+            args.join(" ").into(),
+            String::from_utf8_lossy(&output.stderr).trim().into(),
+        ))
+    }
+}
+
+/// Canonicalize a path if possible (e.g. it may have been deleted)
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
 }
 
 /// One entry from the compilation database
@@ -192,6 +465,25 @@ impl DatabaseEntry {
         self.full_args().next()
     }
 
+    /// Location of the compiler executable
+    ///
+    /// Programs without a directory component are looked up in PATH like a
+    /// shell would, others are resolved relative to the working directory of
+    /// this entry (see `current_dir()`). Will return None if the compiler
+    /// cannot be found.
+    ///
+    pub fn compiler_path(&self) -> Option<PathBuf> {
+        let program = self.program()?;
+        let program = Path::new(program.as_ref());
+        if program.components().count() > 1 {
+            let path = self.directory.join(program);
+            return path.is_file().then_some(path);
+        }
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(program))
+            .find(|path| path.is_file())
+    }
+
     /// Arguments to the executable
     pub fn args(&self) -> impl Iterator<Item = impl AsRef<str>> + '_ {
         self.full_args().skip(1)
@@ -202,24 +494,44 @@ impl DatabaseEntry {
         &self.file
     }
 
+    /// Absolute input file path
+    ///
+    /// Unlike `input()`, which returns the path as written in the database,
+    /// this resolves paths which are relative to `current_dir()`, and tries to
+    /// canonicalize the result (ignoring failures to do so).
+    ///
+    pub fn absolute_input(&self) -> PathBuf {
+        canonicalize(&self.directory.join(&self.file))
+    }
+
     /// Output file path
     ///
     /// This parses the arguments assuming a GCC-like `-o <output>` syntax.
-    /// Will return None if basic syntax assumptions do not look fullfilled.
+    /// Will return an error explaining what went wrong if basic syntax
+    /// assumptions do not look fullfilled.
     ///
-    pub fn output(&self) -> Option<PathBuf> {
+    pub fn output(&self) -> Result<PathBuf, OutputError> {
         // If CMake gives us the output path, use it
         if let Some(out) = self.output.as_ref() {
-            return Some(out.to_path_buf());
+            return Ok(out.to_path_buf());
         }
 
         // Start from working directory provided by cmake
         let mut result = PathBuf::from(&*self.directory);
 
         // Parse arguments, extract file name and (assumed relative) path
-        let rel_output = self.args().skip_while(|arg| arg.as_ref() != "-o").nth(1)?;
-        let rel_output = Path::new(rel_output.as_ref());
-        let file_name = rel_output.file_name()?;
+        let Some(rel_output) = self.flag_operand("-o") else {
+            return if self.args().any(|arg| arg.as_ref() == "-c") {
+                Err(OutputError::NoOutputFlag)
+            } else {
+                Err(OutputError::NotACompileCommand)
+            };
+        };
+        let rel_output = rel_output.ok_or(OutputError::MissingOutputPath)?;
+        let rel_output = Path::new(&rel_output);
+        let file_name = rel_output
+            .file_name()
+            .ok_or_else(|| OutputError::NoFileName(rel_output.into()))?;
 
         // Add output path to working directory, try to canonicalize
         // (ignore failures to do so, that's not critical), add file name
@@ -232,12 +544,109 @@ impl DatabaseEntry {
         result.push(file_name);
 
         // Emit result
-        Some(result)
+        Ok(result)
+    }
+
+    /// Output file path, if it can be determined (see `output()`)
+    pub fn output_opt(&self) -> Option<PathBuf> {
+        self.output().ok()
+    }
+
+    /// Depfile path
+    ///
+    /// This parses the arguments assuming a GCC-like `-MF <depfile>` syntax,
+    /// as used by CMake's Makefile and Ninja generators. Will return None if
+    /// no depfile is specified.
+    ///
+    pub fn depfile(&self) -> Option<PathBuf> {
+        let depfile = self.flag_operand("-MF")??;
+        Some(self.directory.join(depfile))
+    }
+
+    /// Operand of a GCC-like `<flag> <operand>` argument
+    ///
+    /// Operands of the other flags that take a separate argument (see
+    /// `OPERAND_FLAGS`) are skipped, so that e.g. the `-o` in `-MT -o` is not
+    /// mistaken for an output flag. The outer Option tells if `flag` was found
+    /// and the inner one if it was followed by an operand.
+    ///
+    fn flag_operand(&self, flag: &str) -> Option<Option<String>> {
+        let mut args = self.args();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            if arg == flag {
+                return Some(args.next().map(|operand| operand.as_ref().to_owned()));
+            }
+            if OPERAND_FLAGS.contains(&arg) {
+                args.next();
+            }
+        }
+        None
+    }
+
+    /// Files that this entry depends on according to its depfile
+    ///
+    /// Will return an empty list if there is no depfile, e.g. because the
+    /// entry has not been built yet.
+    ///
+    pub fn dependencies(&self) -> Box<[PathBuf]> {
+        let Some(contents) = self
+            .depfile()
+            .and_then(|depfile| std::fs::read_to_string(depfile).ok())
+        else {
+            return Box::default();
+        };
+
+        // Depfiles use Makefile syntax: "target: dep1 dep2 \\\n dep3 ..." where
+        // spaces within file names are escaped with a backslash
+        let Some((_target, deps)) = contents.split_once(": ") else {
+            return Box::default();
+        };
+        let mut result = Vec::new();
+        let mut current = String::new();
+        let mut chars = deps.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek().is_some_and(|next| *next == ' ') => {
+                    current.push(' ');
+                    chars.next();
+                }
+                '\\' if chars.peek().is_some_and(|next| *next == '\n') => {}
+                c if c.is_whitespace() => {
+                    if !current.is_empty() {
+                        result.push(self.directory.join(std::mem::take(&mut current)));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            result.push(self.directory.join(current));
+        }
+        result.into()
     }
 
     /// Check if a file derived from this source file seems up to date
     pub fn derived_freshness(&self, output_path: &Path) -> io::Result<ProductFreshness> {
-        CompilationDatabase::product_freshness(std::iter::once(self.input()), output_path)
+        self.derived_freshness_with(output_path, FreshnessCheck::Mtime)
+    }
+
+    /// Like `derived_freshness()`, but with a configurable freshness check
+    pub fn derived_freshness_with(
+        &self,
+        output_path: &Path,
+        check: FreshnessCheck,
+    ) -> io::Result<ProductFreshness> {
+        CompilationDatabase::product_freshness(std::iter::once(self), output_path, check)
+    }
+
+    /// Record the content hash of this entry's inputs next to a derived file
+    ///
+    /// This should be called after the derived file has been successfully
+    /// produced, for the benefit of later `FreshnessCheck::ContentHash` checks.
+    ///
+    pub fn save_derived_hash(&self, output_path: &Path) -> io::Result<()> {
+        CompilationDatabase::save_content_hash(std::iter::once(self), output_path)
     }
 
     /// Command components
@@ -259,7 +668,32 @@ impl FromStr for DatabaseEntry {
     }
 }
 
+/// Strategy used to tell if a build product is up to date
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FreshnessCheck {
+    /// Compare file modification times
+    ///
+    /// This is cheap, but fragile across checkouts and build caches, and can
+    /// only prove that a build product is outdated.
+    ///
+    #[default]
+    Mtime,
+
+    /// Compare a hash of the build commands and input file contents with the
+    /// one that was saved when the build product was produced
+    ///
+    /// Falls back to modification times if no hash was saved.
+    ///
+    ContentHash,
+}
+
 /// Result of a build profile/output freshness query
+///
+/// Freshness states are ordered from least to most fresh: `Nonexistent` <
+/// `Outdated` < `MaybeOutdated` < `Fresh`. Among `MaybeOutdated` products,
+/// younger ones are considered fresher, and an unknown age is considered
+/// staler than any known age.
+///
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ProductFreshness {
     /// Build product has not been produced yet
@@ -275,6 +709,12 @@ pub enum ProductFreshness {
     /// filesystem timestamps and the build product seems to be from the future.
     ///
     MaybeOutdated(Option<Duration>),
+
+    /// Build product was produced from the current inputs
+    ///
+    /// This can only be asserted by `FreshnessCheck::ContentHash`.
+    ///
+    Fresh,
 }
 //
 impl ProductFreshness {
@@ -284,6 +724,52 @@ impl ProductFreshness {
             ProductFreshness::Nonexistent => false,
             ProductFreshness::Outdated => true,
             ProductFreshness::MaybeOutdated(_age) => true,
+            ProductFreshness::Fresh => true,
+        }
+    }
+
+    /// Age of the build product, if known
+    ///
+    /// `Fresh` products are considered to have a zero age, while nonexistent
+    /// and provably outdated products have no meaningful age.
+    ///
+    pub fn age(&self) -> Option<Duration> {
+        match self {
+            ProductFreshness::Nonexistent | ProductFreshness::Outdated => None,
+            ProductFreshness::MaybeOutdated(age) => *age,
+            ProductFreshness::Fresh => Some(Duration::ZERO),
+        }
+    }
+
+    /// Rank of this freshness state's variant, from least to most fresh
+    fn rank(&self) -> u8 {
+        match self {
+            ProductFreshness::Nonexistent => 0,
+            ProductFreshness::Outdated => 1,
+            ProductFreshness::MaybeOutdated(_age) => 2,
+            ProductFreshness::Fresh => 3,
+        }
+    }
+}
+//
+impl PartialOrd for ProductFreshness {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+//
+impl Ord for ProductFreshness {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (ProductFreshness::MaybeOutdated(age1), ProductFreshness::MaybeOutdated(age2)) => {
+                match (age1, age2) {
+                    (None, None) => Ordering::Equal,
+                    (None, Some(_)) => Ordering::Less,
+                    (Some(_), None) => Ordering::Greater,
+                    (Some(age1), Some(age2)) => age2.cmp(age1),
+                }
+            }
+            _ => self.rank().cmp(&other.rank()),
         }
     }
 }
@@ -334,6 +820,43 @@ pub(crate) mod tests {
         assert!(ProductFreshness::MaybeOutdated(None).exists());
         assert!(ProductFreshness::MaybeOutdated(Some(Duration::new(0, 0))).exists());
         assert!(ProductFreshness::MaybeOutdated(Some(Duration::new(0, 1))).exists());
+        assert!(ProductFreshness::Fresh.exists());
+
+        assert_eq!(ProductFreshness::Nonexistent.age(), None);
+        assert_eq!(ProductFreshness::Outdated.age(), None);
+        assert_eq!(ProductFreshness::MaybeOutdated(None).age(), None);
+        let age = Duration::from_secs(42);
+        assert_eq!(ProductFreshness::MaybeOutdated(Some(age)).age(), Some(age));
+        assert_eq!(ProductFreshness::Fresh.age(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn freshness_ordering() {
+        let mut states = [
+            ProductFreshness::Fresh,
+            ProductFreshness::MaybeOutdated(Some(Duration::from_secs(1))),
+            ProductFreshness::Outdated,
+            ProductFreshness::MaybeOutdated(None),
+            ProductFreshness::Nonexistent,
+            ProductFreshness::MaybeOutdated(Some(Duration::from_secs(60))),
+        ];
+        states.sort();
+        assert_eq!(
+            states,
+            [
+                ProductFreshness::Nonexistent,
+                ProductFreshness::Outdated,
+                ProductFreshness::MaybeOutdated(None),
+                ProductFreshness::MaybeOutdated(Some(Duration::from_secs(60))),
+                ProductFreshness::MaybeOutdated(Some(Duration::from_secs(1))),
+                ProductFreshness::Fresh,
+            ]
+        );
+        assert!(ProductFreshness::Outdated < ProductFreshness::Fresh);
+        assert_eq!(
+            ProductFreshness::MaybeOutdated(None).cmp(&ProductFreshness::MaybeOutdated(None)),
+            Ordering::Equal
+        );
     }
 
     #[test]
@@ -353,7 +876,8 @@ pub(crate) mod tests {
         assert_eq!(empty.program().unwrap().as_ref(), "xxx");
         assert_eq!(empty.args().count(), 0);
         assert_eq!(empty.input(), Path::new("/etc/fstab"));
-        assert_eq!(empty.output(), None);
+        assert_eq!(empty.output(), Err(OutputError::NotACompileCommand));
+        assert_eq!(empty.output_opt(), None);
         WORKING_DIRECTORY.lock().unwrap().with(&tmp_workdir, || {
             assert_eq!(
                 empty.derived_freshness(Path::new("/")).unwrap(),
@@ -403,7 +927,20 @@ pub(crate) mod tests {
             assert_eq!(actual.as_ref(), expected);
         }
         assert_eq!(entry.input(), input_path);
-        assert_eq!(entry.output(), Some(abs_output_path.clone()));
+        assert_eq!(entry.absolute_input(), input_path.canonicalize().unwrap());
+        assert_eq!(entry.output(), Ok(abs_output_path.clone()));
+        assert_eq!(entry.output_opt(), Some(abs_output_path.clone()));
+        std::fs::create_dir(tmp_input_dir.path().join("build")).unwrap();
+        let relative_entry = DatabaseEntry {
+            file: Path::new("../input.cpp").into(),
+            directory: tmp_input_dir.path().join("build").into(),
+            ..entry.clone()
+        };
+        assert_eq!(relative_entry.input(), Path::new("../input.cpp"));
+        assert_eq!(
+            relative_entry.absolute_input(),
+            input_path.canonicalize().unwrap()
+        );
         WORKING_DIRECTORY.lock().unwrap().with(&tmp_workdir, || {
             // Products may not exist yet
             let derived_path = tmp_output_base.path().join("stuff.json");
@@ -444,6 +981,57 @@ pub(crate) mod tests {
         });
     }
 
+    #[test]
+    fn output_errors() {
+        let entry = |command: &str| DatabaseEntry {
+            directory: Path::new("/").into(),
+            command: command.into(),
+            file: Path::new("/etc/fstab").into(),
+            output: None,
+        };
+        assert_eq!(
+            entry("cc input.cpp").output(),
+            Err(OutputError::NotACompileCommand)
+        );
+        assert_eq!(
+            entry("cc -c input.cpp").output(),
+            Err(OutputError::NoOutputFlag)
+        );
+        assert_eq!(
+            entry("cc -c input.cpp -o").output(),
+            Err(OutputError::MissingOutputPath)
+        );
+        assert_eq!(
+            entry("cc -c input.cpp -o ..").output(),
+            Err(OutputError::NoFileName(Path::new("..").into()))
+        );
+        assert_eq!(entry("cc -c input.cpp -o ..").output_opt(), None);
+        assert_eq!(
+            entry("cc -c input.cpp -MF -o").output(),
+            Err(OutputError::NoOutputFlag)
+        );
+    }
+
+    #[test]
+    fn output_with_operand_flags() {
+        let entry = |command: &str| DatabaseEntry {
+            directory: Path::new("/").into(),
+            command: command.into(),
+            file: Path::new("/input.cpp").into(),
+            output: None,
+        };
+        for command in [
+            "cc -MD -MT out.o -MF dep.d -o out.o -c input.cpp",
+            "cc -MD -o out.o -MT out.o -MF dep.d -c input.cpp",
+            "cc -MF dep.d -c input.cpp -o out.o -MF other.d",
+            "cc -isystem -o -MT -o -MQ -o -o out.o -MF dep.d -c input.cpp",
+        ] {
+            let entry = entry(command);
+            assert_eq!(entry.output(), Ok(PathBuf::from("/out.o")), "{command}");
+            assert_eq!(entry.depfile(), Some(PathBuf::from("/dep.d")), "{command}");
+        }
+    }
+
     #[test]
     fn compilation_database() {
         let tmp_input_dir_1 = tempfile::tempdir().unwrap();
@@ -535,4 +1123,227 @@ pub(crate) mod tests {
             );
         });
     }
+
+    #[test]
+    fn entries_changed_since() {
+        // Set up a git repository with three committed source files
+        let tmp_repo = tempfile::tempdir().unwrap();
+        let repo = tmp_repo.path().canonicalize().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(&repo)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        for file in ["a.cpp", "b.cpp", "c.cpp", "c.h"] {
+            std::fs::write(repo.join(file), "int x;\n").unwrap();
+        }
+        std::fs::write(repo.join("c.d"), "c.o: c.cpp \\\n c.h\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Initial commit"]);
+
+        // Build the matching compilation database
+        let db = CompilationDatabase::from_entries(["a", "b", "c"].into_iter().map(|name| {
+            DatabaseEntry::new(
+                repo.clone(),
+                format!("c++ -MD -MT {name}.o -MF {name}.d -c {name}.cpp -o {name}.o"),
+                repo.join(format!("{name}.cpp")),
+                None,
+            )
+        }));
+        let entry_c = db.entry(&repo.join("c.cpp")).unwrap();
+        assert_eq!(entry_c.depfile(), Some(repo.join("c.d")));
+        assert_eq!(
+            &*entry_c.dependencies(),
+            [repo.join("c.cpp"), repo.join("c.h")]
+        );
+
+        WORKING_DIRECTORY.lock().unwrap().with(&repo, || {
+            // Nothing changed initially
+            assert!(db.entries_changed_since("HEAD").unwrap().is_empty());
+
+            // Modifying a source file only affects its own entry
+            std::fs::write(repo.join("a.cpp"), "int y;\n").unwrap();
+            let changed = db.entries_changed_since("HEAD").unwrap();
+            assert_eq!(changed, [db.entry(&repo.join("a.cpp")).unwrap()]);
+
+            // Modifying a header affects the entries that include it
+            git(&["commit", "-q", "-a", "-m", "Change a.cpp"]);
+            std::fs::write(repo.join("c.h"), "int y;\n").unwrap();
+            let changed = db.entries_changed_since("HEAD").unwrap();
+            assert_eq!(changed, [entry_c]);
+
+            // Older revisions are also supported
+            let mut changed = db.entries_changed_since("HEAD~1").unwrap();
+            changed.sort_by_key(|entry| entry.input().to_path_buf());
+            assert_eq!(changed, [db.entry(&repo.join("a.cpp")).unwrap(), entry_c]);
+        });
+
+        // Outside of a git repository, an error is reported
+        let tmp_workdir = tempfile::tempdir().unwrap();
+        WORKING_DIRECTORY.lock().unwrap().with(&tmp_workdir, || {
+            assert_matches!(
+                db.entries_changed_since("HEAD"),
+                Err(ChangedFilesError::GitFailed(..))
+            );
+        });
+    }
+
+    #[test]
+    fn bad_database_entry() {
+        let db = r#"[
+            {"directory": "/build", "command": "c++ -c a.cpp", "file": "/src/a.cpp"},
+            {"directory": "/build", "command": "c++ -c b.cpp", "file": "/src/b.cpp"},
+            {"directory": "/build", "command": "c++ -c c.cpp"}
+        ]"#;
+        let error = CompilationDatabase::from_str(db).unwrap_err();
+        assert_matches!(error, DatabaseParseError::BadEntry { index: 2, .. });
+        let message = error.to_string();
+        assert!(message.contains("entry #2"), "{message}");
+        assert!(message.contains("missing field `file`"), "{message}");
+        assert!(message.contains("c++ -c c.cpp"), "{message}");
+
+        assert_matches!(
+            CompilationDatabase::from_str("{}"),
+            Err(DatabaseParseError::BadJson(_))
+        );
+    }
+
+    #[test]
+    fn compiler_freshness() {
+        // Set up a fake compiler, an input and a build product
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let compiler_path = tmp_dir.path().join("fakecc");
+        File::create(&compiler_path).unwrap();
+        let input_path = tmp_dir.path().join("input.cpp");
+        File::create(&input_path).unwrap();
+        let product_path = tmp_dir.path().join("input.o");
+        let entry = DatabaseEntry::new(
+            tmp_dir.path(),
+            "./fakecc -c input.cpp -o input.o",
+            input_path.clone(),
+            None,
+        );
+        assert_eq!(entry.compiler_path(), Some(compiler_path.clone()));
+
+        // Compilers that cannot be found are ignored
+        let unknown_entry = DatabaseEntry::new(
+            tmp_dir.path(),
+            "not-a-compiler-anywhere -c input.cpp",
+            input_path,
+            None,
+        );
+        assert_eq!(unknown_entry.compiler_path(), None);
+
+        WORKING_DIRECTORY.lock().unwrap().with(&tmp_dir, || {
+            File::create(CompilationDatabase::location()).unwrap();
+            std::thread::sleep(FS_CLOCK_GRANULARITY);
+            File::create(&product_path).unwrap();
+            assert_matches!(
+                entry.derived_freshness(&product_path).unwrap(),
+                ProductFreshness::MaybeOutdated(Some(_))
+            );
+            assert_matches!(
+                unknown_entry.derived_freshness(&product_path).unwrap(),
+                ProductFreshness::MaybeOutdated(Some(_))
+            );
+
+            // Build products are outdated once the compiler is upgraded
+            std::thread::sleep(FS_CLOCK_GRANULARITY);
+            touch(&compiler_path).unwrap();
+            assert_eq!(
+                entry.derived_freshness(&product_path).unwrap(),
+                ProductFreshness::Outdated
+            );
+            assert_matches!(
+                unknown_entry.derived_freshness(&product_path).unwrap(),
+                ProductFreshness::MaybeOutdated(Some(_))
+            );
+        });
+    }
+
+    #[test]
+    fn content_hash_freshness() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let input_path = tmp_dir.path().join("input.cpp");
+        std::fs::write(&input_path, "int main() {}\n").unwrap();
+        let product_path = tmp_dir.path().join("input.json");
+        let entry = DatabaseEntry::new(
+            tmp_dir.path(),
+            "c++ -c input.cpp -o input.o",
+            input_path.clone(),
+            None,
+        );
+        let check = |entry: &DatabaseEntry| {
+            entry
+                .derived_freshness_with(&product_path, FreshnessCheck::ContentHash)
+                .unwrap()
+        };
+
+        WORKING_DIRECTORY.lock().unwrap().with(&tmp_dir, || {
+            File::create(CompilationDatabase::location()).unwrap();
+            assert_eq!(check(&entry), ProductFreshness::Nonexistent);
+
+            // Without a saved hash, modification times are used
+            std::thread::sleep(FS_CLOCK_GRANULARITY);
+            File::create(&product_path).unwrap();
+            assert_matches!(check(&entry), ProductFreshness::MaybeOutdated(_));
+            entry.save_derived_hash(&product_path).unwrap();
+            assert_eq!(check(&entry), ProductFreshness::Fresh);
+
+            // Touching the input without changing it keeps the product fresh
+            std::thread::sleep(FS_CLOCK_GRANULARITY);
+            touch(&input_path).unwrap();
+            assert_eq!(
+                entry.derived_freshness(&product_path).unwrap(),
+                ProductFreshness::Outdated
+            );
+            assert_eq!(check(&entry), ProductFreshness::Fresh);
+
+            // Changing the build command makes the product outdated...
+            let other_entry = DatabaseEntry::new(
+                tmp_dir.path(),
+                "c++ -O2 -c input.cpp -o input.o",
+                input_path.clone(),
+                None,
+            );
+            assert_eq!(check(&other_entry), ProductFreshness::Outdated);
+
+            // ...and so does changing the input, even if the product is newer
+            std::fs::write(&input_path, "int main() { return 1; }\n").unwrap();
+            std::thread::sleep(FS_CLOCK_GRANULARITY);
+            touch(&product_path).unwrap();
+            assert_matches!(
+                entry.derived_freshness(&product_path).unwrap(),
+                ProductFreshness::MaybeOutdated(_)
+            );
+            assert_eq!(check(&entry), ProductFreshness::Outdated);
+        });
+
+        // Full-build profiles work the same way
+        let db = CompilationDatabase::from_entries([entry]);
+        WORKING_DIRECTORY.lock().unwrap().with(&tmp_dir, || {
+            let profile_path = Path::new("profile.csv");
+            File::create(profile_path).unwrap();
+            db.save_profile_hash(profile_path).unwrap();
+            std::thread::sleep(FS_CLOCK_GRANULARITY);
+            touch(&input_path).unwrap();
+            assert_eq!(
+                db.profile_freshness_with(profile_path, FreshnessCheck::ContentHash)
+                    .unwrap(),
+                ProductFreshness::Fresh
+            );
+            std::fs::write(&input_path, "int main() { return 2; }\n").unwrap();
+            assert_eq!(
+                db.profile_freshness_with(profile_path, FreshnessCheck::ContentHash)
+                    .unwrap(),
+                ProductFreshness::Outdated
+            );
+        });
+    }
 }
@@ -9,6 +9,17 @@ fn entities(c: &mut Criterion) {
     c.bench_function("entity/unknown", |b| {
         b.iter(|| parser.parse_entity(black_box("<unknown>")))
     });
+    c.bench_function("parse_many/corpus", |b| {
+        let corpus = [
+            "std::vector<int>",
+            "std::map<std::string, std::vector<double>>",
+            "void (*)(const char*, ...)",
+            "<unknown>",
+            "ns::f<T>(int) const",
+            "operator<",
+        ];
+        b.iter(|| parser.parse_many(black_box(corpus).into_iter()))
+    });
 }
 
 criterion_group!(benches, entities);
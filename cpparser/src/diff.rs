@@ -0,0 +1,277 @@
+//! Structural comparison of parsed C++ entities
+
+use crate::{
+    subparsers::{
+        names::{scopes::IdExpressionView, unqualified::UnqualifiedIdView},
+        templates::{TemplateParameterView, TemplateParametersView},
+        types::{specifiers::SimpleTypeView, TypeView},
+    },
+    EntityKey, EntityParser, EntityView,
+};
+use std::fmt::{self, Display, Formatter};
+
+impl EntityParser {
+    /// Find the first structural difference between two parsed entities
+    ///
+    /// Entities are compared by walking their syntax trees in parallel, and
+    /// the walk stops at the first node where they differ. None is returned if
+    /// the entities are identical.
+    ///
+    pub fn diff_entities(&self, a: EntityKey, b: EntityKey) -> Option<EntityDifference> {
+        let (a, b) = (self.entity(a), self.entity(b));
+        if a == b {
+            return None;
+        }
+        let mut path = Vec::new();
+        let (left, right) = match (&a, &b) {
+            (EntityView::Type(a), EntityView::Type(b)) => diff_types(a, b, &mut path),
+            _ => (a.to_string().into(), b.to_string().into()),
+        };
+        Some(EntityDifference {
+            path: path.into(),
+            left,
+            right,
+        })
+    }
+}
+
+/// First structural difference between two C++ entities
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntityDifference {
+    /// Location of the difference, from the outermost to the innermost
+    /// component of the entities (e.g. "template argument 1 of `vector`")
+    ///
+    /// This is empty if the entities differ at the top level.
+    ///
+    pub path: Box<[Box<str>]>,
+
+    /// Display of the first entity's component at this location
+    pub left: Box<str>,
+
+    /// Display of the second entity's component at this location
+    pub right: Box<str>,
+}
+//
+impl Display for EntityDifference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.path.is_empty() {
+            write!(f, "entity")?;
+        }
+        for (idx, component) in self.path.iter().rev().enumerate() {
+            if idx > 0 {
+                write!(f, " in ")?;
+            }
+            write!(f, "{component}")?;
+        }
+        write!(f, " changed from `{}` to `{}`", self.left, self.right)
+    }
+}
+
+/// Displays of the two sides of a difference
+type Sides = (Box<str>, Box<str>);
+
+/// Display both sides of a difference
+fn sides(a: impl Display, b: impl Display) -> Sides {
+    (a.to_string().into(), b.to_string().into())
+}
+
+/// Find the first difference between two distinct types
+fn diff_types(a: &TypeView, b: &TypeView, path: &mut Vec<Box<str>>) -> Sides {
+    if a.attributes() != b.attributes() {
+        path.push("attributes".into());
+        return sides(a.attributes(), b.attributes());
+    }
+    let (a_spec, b_spec) = (a.type_specifier(), b.type_specifier());
+    if a_spec == b_spec {
+        path.push("declarator".into());
+        return sides(a.declarator(), b.declarator());
+    }
+    if a_spec.decl_specifiers() != b_spec.decl_specifiers()
+        || a_spec.cv() != b_spec.cv()
+        || a_spec.alignment() != b_spec.alignment()
+    {
+        return sides(a, b);
+    }
+    match (a_spec.simple_type(), b_spec.simple_type()) {
+        (SimpleTypeView::IdExpression(a_id), SimpleTypeView::IdExpression(b_id))
+        | (SimpleTypeView::DependentName(a_id), SimpleTypeView::DependentName(b_id)) => {
+            diff_id_expressions(&a_id, &b_id, path)
+        }
+        (a_simple, b_simple) => sides(a_simple, b_simple),
+    }
+}
+
+/// Find the first difference between two distinct id-expressions
+fn diff_id_expressions(
+    a: &IdExpressionView,
+    b: &IdExpressionView,
+    path: &mut Vec<Box<str>>,
+) -> Sides {
+    let (a_path, b_path) = (a.path(), b.path());
+    if a_path != b_path {
+        let (a_scopes, b_scopes) = (a_path.scopes(), b_path.scopes());
+        if a_path.is_rooted() != b_path.is_rooted() || a_scopes.len() != b_scopes.len() {
+            path.push("scope".into());
+            return sides(&a_path, &b_path);
+        }
+        let (idx, a_scope, b_scope) = a_scopes
+            .iter()
+            .zip(b_scopes.iter())
+            .enumerate()
+            .find_map(|(idx, (a_scope, b_scope))| {
+                (a_scope != b_scope).then_some((idx, a_scope, b_scope))
+            })
+            .expect("Distinct scope sequences of equal length must differ somewhere");
+        path.push(format!("scope {}", idx + 1).into());
+        if a_scope.function_signature() != b_scope.function_signature() {
+            return sides(a_scope, b_scope);
+        }
+        return diff_unqualified_ids(&a_scope.id(), &b_scope.id(), path);
+    }
+    diff_unqualified_ids(&a.id(), &b.id(), path)
+}
+
+/// Find the first difference between two distinct unqualified ids
+fn diff_unqualified_ids(
+    a: &UnqualifiedIdView,
+    b: &UnqualifiedIdView,
+    path: &mut Vec<Box<str>>,
+) -> Sides {
+    match (a, b) {
+        (
+            UnqualifiedIdView::Named {
+                is_destructor: a_destructor,
+                id: a_name,
+                template_parameters: a_params,
+            },
+            UnqualifiedIdView::Named {
+                is_destructor: b_destructor,
+                id: b_name,
+                template_parameters: b_params,
+            },
+        ) => {
+            if a_destructor != b_destructor || a_name != b_name {
+                if a_params.is_some() || b_params.is_some() {
+                    path.push("template name".into());
+                }
+                return if a_destructor != b_destructor {
+                    sides(a, b)
+                } else {
+                    sides(a_name, b_name)
+                };
+            }
+            match (a_params, b_params) {
+                (
+                    Some(TemplateParametersView(Some(a_list))),
+                    Some(TemplateParametersView(Some(b_list))),
+                ) if a_list.len() == b_list.len() => {
+                    let (idx, a_param, b_param) = a_list
+                        .iter()
+                        .zip(b_list.iter())
+                        .enumerate()
+                        .find_map(|(idx, (a_param, b_param))| {
+                            (a_param != b_param).then_some((idx, a_param, b_param))
+                        })
+                        .expect("Distinct parameter lists of equal length must differ somewhere");
+                    path.push(format!("template argument {} of `{a_name}`", idx + 1).into());
+                    match (a_param, b_param) {
+                        (
+                            TemplateParameterView::TypeLike(a_ty),
+                            TemplateParameterView::TypeLike(b_ty),
+                        ) => diff_types(&a_ty, &b_ty, path),
+                        (a_param, b_param) => sides(a_param, b_param),
+                    }
+                }
+                _ => {
+                    path.push(format!("template arguments of `{a_name}`").into());
+                    sides(
+                        OptionalDisplay(a_params.as_ref()),
+                        OptionalDisplay(b_params.as_ref()),
+                    )
+                }
+            }
+        }
+        _ => sides(a, b),
+    }
+}
+
+/// Display an optional entity, with nothing standing for None
+struct OptionalDisplay<T>(Option<T>);
+//
+impl<T: Display> Display for OptionalDisplay<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        if let Some(inner) = &self.0 {
+            write!(f, "{inner}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::unwrap_parse;
+    use pretty_assertions::assert_eq;
+
+    fn diff(a: &str, b: &str) -> Option<String> {
+        let parser = EntityParser::new();
+        let a = parser.parse_entity(a).unwrap();
+        let b = parser.parse_entity(b).unwrap();
+        parser.diff_entities(a, b).map(|diff| diff.to_string())
+    }
+
+    #[test]
+    fn diff_entities() {
+        // Identical entities do not differ
+        assert_eq!(diff("std::vector<int>", "std::vector<int>"), None);
+
+        // Template argument changes are located
+        let mut parser = EntityParser::new();
+        let a = unwrap_parse(parser.parse_type_like("std::vector<int>"));
+        let b = unwrap_parse(parser.parse_type_like("std::vector<long>"));
+        assert_eq!(
+            parser.diff_entities(EntityKey::Type(a), EntityKey::Type(b)),
+            Some(EntityDifference {
+                path: vec!["template argument 1 of `vector`".into()].into(),
+                left: "int".into(),
+                right: "long".into(),
+            })
+        );
+        assert_eq!(
+            diff("std::map<K, std::pair<int, V>>", "std::map<K, std::pair<int, W>>").as_deref(),
+            Some("template argument 2 of `pair` in template argument 2 of `map` changed from `V` to `W`")
+        );
+
+        // Template name changes are reported as such
+        assert_eq!(
+            diff("std::map<K, V>", "std::vector<V>").as_deref(),
+            Some("template name changed from `map` to `vector`")
+        );
+
+        // Other differences are reported at the deepest common location
+        assert_eq!(
+            diff("ns::A::f", "ns::B::f").as_deref(),
+            Some("scope 2 changed from `A` to `B`")
+        );
+        assert_eq!(
+            diff("std::vector<int>", "std::vector<int, Alloc>").as_deref(),
+            Some("template arguments of `vector` changed from `<int>` to `<int, Alloc>`")
+        );
+        assert_eq!(
+            diff("const int&", "const int*").as_deref(),
+            Some("declarator changed from `&` to `*`")
+        );
+        assert_eq!(
+            diff("alignas(8) int", "alignas(16) int").as_deref(),
+            Some("entity changed from `alignas(8) int` to `alignas(16) int`")
+        );
+        assert_eq!(
+            diff("S<alignas(8) int>", "S<int>").as_deref(),
+            Some("template argument 1 of `S` changed from `alignas(8) int` to `int`")
+        );
+        assert_eq!(
+            diff("<unknown>", "int").as_deref(),
+            Some("entity changed from `<unknown>` to `int`")
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     cell::RefCell,
     fmt::{self, Display, Formatter, Write},
 };
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Trait implemented by entities with a customizable display
 pub trait CustomDisplay {
@@ -32,24 +32,49 @@ pub trait CustomDisplay {
     //        display_impl in the final tree-based display implementation.
     //
     fn bounded_display(&self, max_cols: u16) -> String {
+        self.bounded_display_with(max_cols, &QualificationPolicy::default())
+    }
+
+    /// Variant of bounded_display with a custom name qualification policy
+    fn bounded_display_with(&self, max_cols: u16, qualification: &QualificationPolicy) -> String {
+        self.to_bounded_string_with(max_cols, qualification).0
+    }
+
+    /// Variant of bounded_display that also returns the width of the display
+    /// in terminal columns, which is at most max_cols
+    fn to_bounded_string(&self, max_cols: u16) -> (String, usize) {
+        self.to_bounded_string_with(max_cols, &QualificationPolicy::default())
+    }
+
+    /// Variant of to_bounded_string with a custom name qualification policy
+    fn to_bounded_string_with(
+        &self,
+        max_cols: u16,
+        qualification: &QualificationPolicy,
+    ) -> (String, usize) {
         assert!(max_cols >= 1, "Cannot display anything with 0 columns...");
         let mut prev_display = "…".to_string();
+        let mut prev_width = 1;
         let mut curr_display = String::new();
         for recursion_depth in 0..=self.recursion_depth() {
             write!(
                 &mut curr_display,
                 "{}",
-                self.display(&DisplayState::new(recursion_depth))
+                self.display(
+                    &DisplayState::new(recursion_depth).with_qualification(qualification.clone())
+                )
             )
             .expect("Failed to display entity");
-            if curr_display.width() > max_cols.into() {
+            let curr_width = curr_display.width();
+            if curr_width > max_cols.into() {
                 break;
             } else {
                 std::mem::swap(&mut prev_display, &mut curr_display);
+                prev_width = curr_width;
                 curr_display.clear()
             }
         }
-        prev_display
+        (prev_display, prev_width)
     }
 }
 //
@@ -93,12 +118,29 @@ pub struct DisplayState(RefCell<DisplayStateInner>);
 struct DisplayStateInner {
     /// Recursion limit from current point
     max_recursion: usize,
+
+    /// How qualified names should be displayed
+    qualification: QualificationPolicy,
+
+    /// Maximal number of template arguments to be displayed per template
+    max_template_args: Option<usize>,
+
+    /// Column budget for the items of template argument and function
+    /// parameter lists
+    max_list_cols: Option<usize>,
+
+    /// How anonymous namespace scopes should be displayed
+    anonymous_namespaces: AnonymousNamespacePolicy,
 }
 //
 impl Default for DisplayStateInner {
     fn default() -> Self {
         Self {
             max_recursion: usize::MAX,
+            qualification: QualificationPolicy::default(),
+            max_template_args: None,
+            max_list_cols: None,
+            anonymous_namespaces: AnonymousNamespacePolicy::default(),
         }
     }
 }
@@ -106,7 +148,63 @@ impl Default for DisplayStateInner {
 impl DisplayState {
     /// Set up a display state with certain recursion limits
     pub fn new(max_recursion: usize) -> Self {
-        Self(RefCell::new(DisplayStateInner { max_recursion }))
+        Self(RefCell::new(DisplayStateInner {
+            max_recursion,
+            ..Default::default()
+        }))
+    }
+
+    /// Change how qualified names are displayed
+    pub fn with_qualification(self, qualification: QualificationPolicy) -> Self {
+        self.0.borrow_mut().qualification = qualification;
+        self
+    }
+
+    /// Only display the first `max_args` arguments of template parameter
+    /// lists, replacing the remainder with a `…(+N)` elision marker
+    pub fn with_max_template_args(self, max_args: usize) -> Self {
+        self.0.borrow_mut().max_template_args = Some(max_args);
+        self
+    }
+
+    /// Maximal number of template arguments to be displayed per template
+    pub fn max_template_args(&self) -> Option<usize> {
+        self.0.borrow().max_template_args
+    }
+
+    /// Render the items of template argument and function parameter lists
+    /// using at most `max_cols` columns per list (see `bounded_join`)
+    pub fn with_max_list_cols(self, max_cols: usize) -> Self {
+        self.0.borrow_mut().max_list_cols = Some(max_cols);
+        self
+    }
+
+    /// Column budget for the items of template argument and function
+    /// parameter lists
+    pub fn max_list_cols(&self) -> Option<usize> {
+        self.0.borrow().max_list_cols
+    }
+
+    /// Change how anonymous namespace scopes are displayed
+    pub fn with_anonymous_namespaces(self, policy: AnonymousNamespacePolicy) -> Self {
+        self.0.borrow_mut().anonymous_namespaces = policy;
+        self
+    }
+
+    /// How anonymous namespace scopes should be displayed
+    pub fn anonymous_namespaces(&self) -> AnonymousNamespacePolicy {
+        self.0.borrow().anonymous_namespaces
+    }
+
+    /// Truth that a namespace should be omitted when it is the leading scope
+    /// of a non-rooted qualified name
+    pub fn strips_namespace(&self, name: &str) -> bool {
+        match &self.0.borrow().qualification {
+            QualificationPolicy::Full => false,
+            QualificationPolicy::StripLeading(namespaces) => namespaces
+                .iter()
+                .any(|namespace| namespace.as_ref() == name),
+        }
     }
 
     /// Test if a certain recursion is possible under the current limit
@@ -149,6 +247,118 @@ impl Drop for RecursionGuard<'_> {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct RecursionLimitReached;
 
+/// Join a list of items with a separator, using at most `max_cols` columns
+///
+/// As many items as possible are displayed, and the remaining ones are replaced
+/// with a `…(+N)` elision marker. If the next item does not fit in full, its
+/// beginning is displayed followed by `…`.
+///
+pub fn bounded_join<T: Display>(
+    items: impl IntoIterator<Item = T>,
+    separator: &str,
+    max_cols: usize,
+) -> String {
+    let items = items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>();
+    bounded_join_impl(&items, items.len(), separator, max_cols)
+}
+
+/// Implementation of bounded_join where `items` are the first items of a
+/// list of `total_len` items, the remaining ones being always elided
+pub(crate) fn bounded_join_impl(
+    items: &[String],
+    total_len: usize,
+    separator: &str,
+    max_cols: usize,
+) -> String {
+    debug_assert!(items.len() <= total_len);
+    let mut output = String::new();
+    let mut num_displayed = 0;
+    for item in items {
+        // Account for the separator before this item, and for the elision
+        // marker that would follow it if we stopped there
+        let leading = if num_displayed > 0 { separator } else { "" };
+        let num_elided = total_len - num_displayed - 1;
+        let trailer = if num_elided > 0 {
+            format!("{separator}…(+{num_elided})")
+        } else {
+            String::new()
+        };
+        let used_cols = output.width() + leading.width() + trailer.width();
+
+        // Display items in full as long as they fit...
+        if used_cols + item.width() <= max_cols {
+            output.push_str(leading);
+            output.push_str(item);
+            num_displayed += 1;
+            continue;
+        }
+
+        // ...then display the beginning of the first item that doesn't fit
+        let item_cols = max_cols.saturating_sub(used_cols);
+        if item_cols >= 2 {
+            output.push_str(leading);
+            let mut prefix_cols = 0;
+            for c in item.chars() {
+                let c_cols = c.width().unwrap_or(0);
+                if prefix_cols + c_cols > item_cols - 1 {
+                    break;
+                }
+                prefix_cols += c_cols;
+                output.push(c);
+            }
+            output.push('…');
+            output.push_str(&trailer);
+            return output;
+        }
+        break;
+    }
+
+    // Elide the remaining items
+    let num_elided = total_len - num_displayed;
+    if num_elided > 0 {
+        let leading = if num_displayed > 0 { separator } else { "" };
+        let marker = format!("{leading}…(+{num_elided})");
+        if output.width() + marker.width() <= max_cols {
+            output.push_str(&marker);
+        } else if max_cols >= 1 {
+            // Can only happen when no item was displayed
+            debug_assert_eq!(num_displayed, 0);
+            output.push('…');
+        }
+    }
+    output
+}
+
+/// Policy for displaying the scopes of qualified names
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum QualificationPolicy {
+    /// Display names with their full scope path
+    #[default]
+    Full,
+
+    /// Omit the listed namespaces when they are the leading scope of a
+    /// non-rooted name (e.g. display `std::vector` as `vector`)
+    StripLeading(Box<[Box<str>]>),
+}
+
+/// Policy for displaying anonymous namespace scopes like
+/// `(anonymous namespace)::`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AnonymousNamespacePolicy {
+    /// Display anonymous namespaces as the compiler spelled them
+    #[default]
+    Full,
+
+    /// Display anonymous namespaces as a short `(anon)` marker
+    Compact,
+
+    /// Do not display anonymous namespaces at all
+    Hide,
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -232,6 +442,46 @@ pub(crate) mod tests {
         assert_eq!(CustomDisplayMock(2).bounded_display(6), "((@))");
     }
 
+    // Check that to_bounded_string measures what bounded_display renders
+    #[test]
+    fn to_bounded_string() {
+        for depth in 0..4 {
+            let mock = CustomDisplayMock(depth);
+            for max_cols in 1..10 {
+                let (display, width) = mock.to_bounded_string(max_cols);
+                assert_eq!(display, mock.bounded_display(max_cols));
+                assert_eq!(width, display.width());
+                assert!(width <= max_cols.into());
+            }
+        }
+    }
+
+    // Check that bounded_join works as intended
+    #[test]
+    fn bounded_join() {
+        let items = ["int", "char", "double"];
+        let join = |max_cols| super::bounded_join(items, ", ", max_cols);
+
+        // Lists that fully fit are displayed as is
+        assert_eq!(join(17), "int, char, double");
+        assert_eq!(join(usize::MAX), "int, char, double");
+        assert_eq!(super::bounded_join(Vec::<String>::new(), ", ", 0), "");
+
+        // Lists that partially fit are elided, truncating the last item
+        assert_eq!(join(16), "int, char, doub…");
+        assert_eq!(join(15), "int, ch…, …(+1)");
+        assert_eq!(join(13), "int, …(+2)");
+        assert_eq!(join(10), "int, …(+2)");
+        assert_eq!(join(9), "i…, …(+2)");
+
+        // Lists that don't fit at all are fully elided
+        assert_eq!(join(8), "…(+3)");
+        assert_eq!(join(5), "…(+3)");
+        assert_eq!(join(4), "…");
+        assert_eq!(join(1), "…");
+        assert_eq!(join(0), "");
+    }
+
     // Test option display
     #[test]
     fn name() {
@@ -2,7 +2,7 @@
 
 use crate::{
     asylum::{lasso::Spur, sequence::SequenceKey, InternerKey, Resolver},
-    display::{CustomDisplay, DisplayState},
+    display::{self, CustomDisplay, DisplayState},
     EntityParser,
 };
 use reffers::ARef;
@@ -115,15 +115,57 @@ impl<
     }
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        self.display_impl_with_limits(f, state, None, None)
+    }
+}
+//
+impl<
+        'entities,
+        Item: Clone + Eq + Hash,
+        ItemView: SliceItemView<'entities, Inner = Item>,
+        Key: InternerKey<ImplKey = Range<usize>>,
+    > SliceView<'entities, Item, ItemView, Key>
+{
+    /// Variant of display_impl that only displays the first `max_items`
+    /// items, replacing the remainder with a `…(+N)` elision marker, and
+    /// fits the items within `max_cols` columns (see `bounded_join`)
+    pub(crate) fn display_impl_with_limits(
+        &self,
+        f: &mut Formatter<'_>,
+        state: &DisplayState,
+        max_items: Option<usize>,
+        max_cols: Option<usize>,
+    ) -> Result<(), fmt::Error> {
         write!(f, "{}", ItemView::DISPLAY_HEADER)?;
         if let Ok(_guard) = state.recurse() {
-            let mut iterator = self.iter().peekable();
+            let max_items = max_items.unwrap_or(usize::MAX);
+            if let Some(max_cols) = max_cols {
+                let items = self
+                    .iter()
+                    .take(max_items)
+                    .map(|view| view.display(state).to_string())
+                    .collect::<Vec<_>>();
+                let joined = display::bounded_join_impl(
+                    &items,
+                    self.len(),
+                    ItemView::DISPLAY_SEPARATOR,
+                    max_cols,
+                );
+                return write!(f, "{joined}{}", ItemView::DISPLAY_TRAILER);
+            }
+            let mut iterator = self.iter().take(max_items).peekable();
             while let Some(view) = iterator.next() {
                 view.display_impl(f, state)?;
                 if iterator.peek().is_some() {
                     write!(f, "{}", ItemView::DISPLAY_SEPARATOR)?;
                 }
             }
+            if self.len() > max_items {
+                if max_items > 0 {
+                    write!(f, "{}", ItemView::DISPLAY_SEPARATOR)?;
+                }
+                write!(f, "…(+{})", self.len() - max_items)?;
+            }
         } else if self.iter().count() > 0 {
             write!(f, "…")?;
         }
@@ -266,7 +308,7 @@ mod tests {
     #[test]
     fn equality() {
         let entities1 = EntityParser::new();
-        let mut entities2 = EntityParser::new();
+        let entities2 = EntityParser::new();
         entities2
             .parse_entity("<unknown>")
             .expect("This is a known-good parse which should not fail");
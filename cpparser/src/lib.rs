@@ -2,6 +2,7 @@
 
 #![deny(missing_docs)]
 
+pub mod diff;
 pub mod display;
 mod interning;
 pub mod subparsers;
@@ -17,24 +18,32 @@ use crate::{
         },
         templates::{TemplateParameter, TemplateParameterListKey},
         types::{
-            declarators::{DeclOperator, DeclaratorKey},
+            declarators::{DeclOperator, DeclaratorKey, MemberDeclarator, MemberDeclaratorView},
             specifiers::legacy::{self, LegacyName},
-            TypeKey, TypeLike, TypeView,
+            AliasDeclaration, AliasDeclarationView, TypeKey, TypeLike, TypeView,
+        },
+        values::{
+            requires::{Requirement, RequirementsKey, RequiresParameter, RequiresParametersKey},
+            AfterValue, ValueKey, ValueLike, ValueTrailerKey,
         },
-        values::{AfterValue, ValueKey, ValueLike, ValueTrailerKey},
     },
 };
 use asylum::{
     lasso::{MiniSpur, Rodeo, Spur},
     path, Interner,
 };
-use nom::{error::Error, Parser};
+use nom::{
+    error::{Error, ErrorKind},
+    Parser,
+};
 use nom_supreme::ParserExt;
 use reffers::ARef;
 use std::{
     cell::RefCell,
-    fmt::{self, Display, Formatter},
+    fmt::{self, Debug, Display, Formatter},
+    rc::Rc,
 };
+use thiserror::Error;
 
 /// Re-export asylum version in use
 pub use asylum;
@@ -132,13 +141,19 @@ pub struct EntityParser {
 
     /// Interned declarators
     declarators: RecursiveSequenceInterner<DeclOperator, DeclaratorKey>,
+
+    /// Interned requires-expression requirements
+    requirements: RecursiveSequenceInterner<Requirement, RequirementsKey>,
+
+    /// Interned requires-expression parameter lists
+    requires_parameters: RecursiveSequenceInterner<RequiresParameter, RequiresParametersKey>,
 }
 //
 impl EntityParser {
     /// Set up the parser
     pub fn new() -> Self {
         Self {
-            legacy_name_parser: Box::new(legacy::legacy_name_parser()),
+            legacy_name_parser: Box::new(legacy::legacy_name_parser(false)),
             identifiers: RefCell::new(Rodeo::new()),
             paths: Default::default(),
             types: Default::default(),
@@ -149,16 +164,35 @@ impl EntityParser {
             function_parameters: Default::default(),
             scope_sequences: Default::default(),
             declarators: Default::default(),
+            requirements: Default::default(),
+            requires_parameters: Default::default(),
         }
     }
 
+    /// Enable or disable lenient parsing of legacy C-style primitive types
+    ///
+    /// C++ keywords are case-sensitive, and by default, so is this parser. In
+    /// lenient mode, the keywords of legacy primitive type names (like
+    /// `Unsigned Int`) are matched case-insensitively, which helps with
+    /// non-conforming demanglers and hand-written input. Other keywords are
+    /// unaffected.
+    ///
+    /// Beware that in lenient mode, identifiers that only differ from such
+    /// keywords by case (like a `Float` class) are parsed as primitive types.
+    ///
+    pub fn set_lenient_legacy_names(&mut self, lenient: bool) {
+        self.legacy_name_parser = Box::new(legacy::legacy_name_parser(lenient));
+    }
+
     /// Intern a file path, returning the corresponding key
     ///
     /// This is exposed so that other file paths which are related to those
     /// appearing in C++ entity names, but appear in other context, can be
     /// interned using the same infrastructure for key comparability.
     ///
-    pub fn intern_path(&mut self, path: &str) -> PathKey {
+    /// Like `parse_entity()`, this only needs shared access to the parser.
+    ///
+    pub fn intern_path(&self, path: &str) -> PathKey {
         self.intern_path_imut(path)
     }
 
@@ -175,6 +209,15 @@ impl EntityParser {
         InternedPath::new(ARef::new(self.paths.borrow()), key)
     }
 
+    /// Copy a previously interned path into another parser
+    pub(crate) fn copy_path(&self, key: PathKey, dst: &Self) -> PathKey {
+        let path = self.path(key).to_boxed_path();
+        dst.intern_path_imut(
+            path.to_str()
+                .expect("Interned paths come from an &str, so they must be valid Unicode"),
+        )
+    }
+
     /// Total number of path components across all interned paths so far
     pub fn num_path_components(&self) -> usize {
         self.paths.borrow().num_components()
@@ -190,26 +233,174 @@ impl EntityParser {
         self.paths.borrow().max_path_len()
     }
 
+    /// Estimate of the heap memory used by interned entities, in bytes
+    ///
+    /// This is computed from the number of interned items, and does not
+    /// account for allocator overhead and unused container capacity.
+    ///
+    pub fn memory_footprint(&self) -> usize {
+        use std::mem::size_of;
+        self.identifiers.borrow().current_memory_usage()
+            + self.paths.borrow().memory_footprint()
+            + self.num_types() * size_of::<TypeLike>()
+            + self.num_values() * size_of::<ValueLike>()
+            + self.num_template_parameters() * size_of::<TemplateParameter>()
+            + self.num_after_value() * size_of::<AfterValue>()
+            + self.num_function_arguments() * size_of::<ValueKey>()
+            + self.num_function_parameters() * size_of::<TypeKey>()
+            + self.num_scopes() * size_of::<Scope>()
+            + self.num_decl_operators() * size_of::<DeclOperator>()
+            + self.num_requirements() * size_of::<Requirement>()
+            + self.num_requires_parameters() * size_of::<RequiresParameter>()
+    }
+
+    /// Number of items in each of the interning pools
+    pub fn intern_stats(&self) -> InternStats {
+        InternStats {
+            identifiers: self.num_identifiers(),
+            path_components: self.num_path_components(),
+            unique_path_components: self.num_unique_path_components(),
+            types: self.num_types(),
+            values: self.num_values(),
+            template_parameters: self.num_template_parameters(),
+            after_values: self.num_after_value(),
+            function_arguments: self.num_function_arguments(),
+            function_parameters: self.num_function_parameters(),
+            scopes: self.num_scopes(),
+            decl_operators: self.num_decl_operators(),
+            requirements: self.num_requirements(),
+            requires_parameters: self.num_requires_parameters(),
+        }
+    }
+
+    /// Drop all interned entities, so that their memory can be reclaimed
+    ///
+    /// This is meant for long-running processes that parse entities in
+    /// independent batches, which would otherwise see the parser's memory
+    /// footprint grow indefinitely.
+    ///
+    /// All keys that were previously returned by this parser are invalidated.
+    /// Using them afterwards will result in panics or incorrect entities.
+    ///
+    pub fn clear(&mut self) {
+        *self.identifiers.get_mut() = Rodeo::new();
+        *self.paths.get_mut() = Default::default();
+        *self.types.get_mut() = Default::default();
+        *self.values.get_mut() = Interner::new();
+        self.template_parameter_lists = Default::default();
+        self.value_trailers = Default::default();
+        self.function_arguments = Default::default();
+        self.function_parameters = Default::default();
+        self.scope_sequences = Default::default();
+        self.declarators = Default::default();
+        self.requirements = Default::default();
+        self.requires_parameters = Default::default();
+    }
+
     /// Parse a C++ entity
     ///
-    /// None will be returned upon encountering the special `<unknown>` entity
-    /// that clang occasionally feels like referring to.
+    /// Most entities follow the type grammar, but clang may also refer to
+    /// bitfield member declarations, alias declarations, or to the special
+    /// `<unknown>` entity.
+    ///
+    /// Unlike lower-level parsers, this only needs shared access to the
+    /// parser, so that a parser which is shared via `Rc` (see `SharedEntity`)
+    /// can keep interning new entities.
     ///
     pub fn parse_entity<'source>(
-        &mut self,
+        &self,
         s: &'source str,
     ) -> Result<EntityKey, nom::error::Error<&'source str>> {
         use nom::combinator::eof;
         use nom_supreme::final_parser::final_parser;
-        let type_like = (|s| self.parse_type_like_imut(s)).map(Some);
-        let unknown = Self::parse_unknown_entity.value(None);
-        final_parser(type_like.or(unknown).terminated(eof))(s)
+        let alias = (|s| self.parse_alias_declaration_imut(s)).map(EntityKey::Alias);
+        // Named members without a bitfield width are rejected, as accepting
+        // them would turn any unparsed trailing word into a member name
+        let type_or_member = (|s| self.parse_member_declaration_imut(s))
+            .verify(|(_, member)| member.is_empty() || member.is_bitfield())
+            .map(|(ty, member)| {
+                if member.is_empty() {
+                    EntityKey::Type(ty)
+                } else {
+                    EntityKey::Member(ty, member)
+                }
+            });
+        let unknown = Self::parse_unknown_entity.value(EntityKey::Unknown);
+        final_parser(alias.or(type_or_member).or(unknown).terminated(eof))(s)
+    }
+
+    /// Parse many C++ entities in a row
+    ///
+    /// This is meant for throughput measurements and parser coverage studies
+    /// over a corpus of entity names. Interned data is shared across inputs, as
+    /// it would be in real-world usage. The output has one result per input,
+    /// in the same order, and errors are located by their offset in the input.
+    ///
+    pub fn parse_many<'a>(
+        &mut self,
+        inputs: impl Iterator<Item = &'a str>,
+    ) -> Vec<Result<EntityKey, ParseError>> {
+        inputs
+            .map(|input| {
+                self.parse_entity(input)
+                    .map_err(|error| ParseError::new(input, error))
+            })
+            .collect()
     }
 
     /// Retrieve a previously interned entity
     pub fn entity(&self, key: EntityKey) -> EntityView {
         EntityView::new(key, self)
     }
+
+    /// Copy a previously interned entity into a self-contained value
+    ///
+    /// Unlike an EntityView, the result does not borrow from this parser, and
+    /// can thus outlive it. This is more expensive than retrieving a view, so
+    /// only use it when the entity must be stored independently.
+    ///
+    pub fn to_owned_entity(&self, key: EntityKey) -> OwnedEntity {
+        self.entity(key).to_owned_entity()
+    }
+
+    /// Parse a C++ type, panicking if it is not entirely made of a valid type
+    ///
+    /// This is meant to concisely build types in test suites, and should not
+    /// be used on untrusted input: use `parse_type_like` for that.
+    ///
+    /// ```
+    /// # use cpparser::EntityParser;
+    /// let mut parser = EntityParser::new();
+    /// let ty = parser.force_parse_type("std::vector<int>");
+    /// assert_eq!(parser.type_like(ty).to_string(), "std::vector<int>");
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn force_parse_type(&mut self, s: &str) -> TypeKey {
+        match self.parse_type_like(s) {
+            Ok(("", ty)) => ty,
+            Ok((rest, _)) => panic!("Unexpected trailing input {rest:?} after type {s:?}"),
+            Err(error) => panic!("Failed to parse type {s:?}: {error}"),
+        }
+    }
+
+    /// Parse a C++ entity, panicking on parse errors
+    ///
+    /// This is meant to concisely build entities in test suites, and should
+    /// not be used on untrusted input: use `parse_entity` for that.
+    ///
+    /// ```
+    /// # use cpparser::{EntityKey, EntityParser};
+    /// let mut parser = EntityParser::new();
+    /// let entity = parser.force_parse_entity("<unknown>");
+    /// assert_eq!(entity, EntityKey::Unknown);
+    /// let entity = parser.force_parse_entity("f(int)");
+    /// assert_eq!(parser.entity(entity).to_string(), "f(int)");
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn force_parse_entity(&mut self, s: &str) -> EntityKey {
+        self.parse_entity(s)
+            .unwrap_or_else(|error| panic!("Failed to parse entity {s:?}: {error}"))
+    }
 }
 //
 impl Default for EntityParser {
@@ -218,25 +409,132 @@ impl Default for EntityParser {
     }
 }
 
+/// Number of items in the interning pools of an EntityParser
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct InternStats {
+    /// Unique identifiers
+    pub identifiers: usize,
+
+    /// Path components, across all interned paths
+    pub path_components: usize,
+
+    /// Unique path components
+    pub unique_path_components: usize,
+
+    /// Unique types
+    pub types: usize,
+
+    /// Unique values
+    pub values: usize,
+
+    /// Template parameters, across all interned parameter sets
+    pub template_parameters: usize,
+
+    /// Value trailer items, across all interned value trailers
+    pub after_values: usize,
+
+    /// Function call arguments, across all interned argument sets
+    pub function_arguments: usize,
+
+    /// Function parameters, across all interned parameter sets
+    pub function_parameters: usize,
+
+    /// Scopes, across all interned scope sequences
+    pub scopes: usize,
+
+    /// Declarator components, across all interned declarators
+    pub decl_operators: usize,
+
+    /// Requirements, across all interned requires-expressions
+    pub requirements: usize,
+
+    /// Parameters, across all interned requires-expressions
+    pub requires_parameters: usize,
+}
+
+/// Failure to parse a C++ entity, located within the input
+#[derive(Clone, Copy, Debug, Eq, Error, Hash, PartialEq)]
+#[error("failed to parse C++ entity at byte {offset} ({kind:?})")]
+pub struct ParseError {
+    /// Byte offset of the point in the input where parsing failed
+    pub offset: usize,
+
+    /// Kind of error that the parser reported
+    pub kind: ErrorKind,
+}
+//
+impl ParseError {
+    /// Locate a parsing error within the input string
+    fn new(input: &str, error: Error<&str>) -> Self {
+        Self {
+            offset: input.len() - error.input.len(),
+            kind: error.code,
+        }
+    }
+}
+
 /// Interned C++ entity
-///
-/// None encodes to the special `<unknown>` entity that clang occasionally feels
-/// like referring to.
-///
-pub type EntityKey = Option<TypeKey>;
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EntityKey {
+    /// Something that follows the type grammar
+    Type(TypeKey),
+
+    /// Bitfield member declaration, as in `unsigned flags : 3`
+    Member(TypeKey, MemberDeclarator),
+
+    /// Alias declaration, as in `using X = std::vector<T>`
+    Alias(AliasDeclaration),
+
+    /// The special `<unknown>` entity that clang occasionally feels like
+    /// referring to
+    Unknown,
+}
 
 /// View of a C++ entity
-///
-/// None encodes to the special `<unknown>` entity that clang occasionally feels
-/// like referring to.
-///
 #[derive(PartialEq)]
-pub struct EntityView<'entities>(pub Option<TypeView<'entities>>);
+pub enum EntityView<'entities> {
+    /// Something that follows the type grammar
+    Type(TypeView<'entities>),
+
+    /// Bitfield member declaration, as in `unsigned flags : 3`
+    Member(TypeView<'entities>, MemberDeclaratorView<'entities>),
+
+    /// Alias declaration, as in `using X = std::vector<T>`
+    Alias(AliasDeclarationView<'entities>),
+
+    /// The special `<unknown>` entity that clang occasionally feels like
+    /// referring to
+    Unknown,
+}
 //
 impl<'entities> EntityView<'entities> {
-    /// Build a new-expression view
+    /// Build an entity view
     pub fn new(inner: EntityKey, entities: &'entities EntityParser) -> Self {
-        Self(inner.map(|ty| entities.type_like(ty)))
+        match inner {
+            EntityKey::Type(ty) => Self::Type(entities.type_like(ty)),
+            EntityKey::Member(ty, member) => {
+                Self::Member(entities.type_like(ty), entities.member_declarator(member))
+            }
+            EntityKey::Alias(alias) => Self::Alias(entities.alias_declaration(alias)),
+            EntityKey::Unknown => Self::Unknown,
+        }
+    }
+
+    /// Copy this entity into a self-contained value
+    ///
+    /// See `EntityParser::to_owned_entity()` for more information.
+    ///
+    pub fn to_owned_entity(&self) -> OwnedEntity {
+        let entities = EntityParser::new();
+        let key = match self {
+            Self::Type(ty) => EntityKey::Type(ty.copy_into(&entities)),
+            Self::Member(ty, member) => {
+                EntityKey::Member(ty.copy_into(&entities), member.copy_into(&entities))
+            }
+            Self::Alias(alias) => EntityKey::Alias(alias.copy_into(&entities)),
+            Self::Unknown => EntityKey::Unknown,
+        };
+        OwnedEntity { entities, key }
     }
 }
 //
@@ -248,18 +546,121 @@ impl<'entities> Display for EntityView<'entities> {
 //
 impl<'entities> CustomDisplay for EntityView<'entities> {
     fn recursion_depth(&self) -> usize {
-        self.0.recursion_depth()
+        match self {
+            Self::Type(ty) => ty.recursion_depth(),
+            Self::Member(ty, member) => ty.recursion_depth().max(member.recursion_depth()),
+            Self::Alias(alias) => alias.recursion_depth(),
+            Self::Unknown => 0,
+        }
     }
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
-        if let Some(ty) = &self.0 {
-            ty.display_impl(f, state)
-        } else {
-            write!(f, "<unknown>")
+        match self {
+            Self::Type(ty) => ty.display_impl(f, state),
+            Self::Member(ty, member) => {
+                ty.display_impl(f, state)?;
+                member.display_impl(f, state)
+            }
+            Self::Alias(alias) => alias.display_impl(f, state),
+            Self::Unknown => write!(f, "<unknown>"),
         }
     }
 }
 
+/// Self-contained C++ entity, which does not borrow from an EntityParser
+///
+/// The entity's interned components are copied into a dedicated parser, which
+/// only holds that entity's components.
+///
+pub struct OwnedEntity {
+    /// Dedicated parser holding the entity's components
+    entities: EntityParser,
+
+    /// Key to the entity within the dedicated parser
+    key: EntityKey,
+}
+//
+impl OwnedEntity {
+    /// Access the entity
+    pub fn entity(&self) -> EntityView {
+        self.entities.entity(self.key)
+    }
+}
+//
+impl Debug for OwnedEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_tuple("OwnedEntity")
+            .field(&self.to_string())
+            .finish()
+    }
+}
+//
+impl Display for OwnedEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl CustomDisplay for OwnedEntity {
+    fn recursion_depth(&self) -> usize {
+        self.entity().recursion_depth()
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        self.entity().display_impl(f, state)
+    }
+}
+
+/// C++ entity which shares ownership of the EntityParser that holds it
+///
+/// Unlike an OwnedEntity, this is cheap to build since nothing is copied, but
+/// it keeps the whole parser alive.
+///
+#[derive(Clone)]
+pub struct SharedEntity {
+    /// Parser holding the entity's components
+    entities: Rc<EntityParser>,
+
+    /// Key to the entity within the parser
+    key: EntityKey,
+}
+//
+impl SharedEntity {
+    /// Share a previously interned entity
+    pub fn new(entities: Rc<EntityParser>, key: EntityKey) -> Self {
+        Self { entities, key }
+    }
+
+    /// Access the entity
+    pub fn entity(&self) -> EntityView {
+        self.entities.entity(self.key)
+    }
+}
+//
+impl Debug for SharedEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_tuple("SharedEntity")
+            .field(&self.to_string())
+            .finish()
+    }
+}
+//
+impl Display for SharedEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl CustomDisplay for SharedEntity {
+    fn recursion_depth(&self) -> usize {
+        self.entity().recursion_depth()
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        self.entity().display_impl(f, state)
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -281,13 +682,184 @@ pub(crate) mod tests {
         assert_matches!(
             parser.parse_entity("type_name"),
             Ok(entity) => {
-                assert_eq!(entity, Some(unwrap_parse(parser.parse_type_like("type_name"))));
+                assert_eq!(
+                    entity,
+                    EntityKey::Type(unwrap_parse(parser.parse_type_like("type_name")))
+                );
                 check_custom_display(parser.entity(entity), &["type_name"]);
             }
         );
 
+        // Bitfield member declarations, named or not
+        for (input, displays) in [
+            ("int x : 3", &["int x : 3"][..]),
+            ("unsigned : 0", &["unsigned int : 0"][..]),
+            (
+                "std::uint8_t flags : N + 1",
+                &["…::uint8_t flags : N…", "std::uint8_t flags : N + 1"][..],
+            ),
+        ] {
+            let (ty, member) = unwrap_parse(parser.parse_member_declaration(input));
+            assert_matches!(parser.parse_entity(input), Ok(entity) => {
+                assert_eq!(entity, EntityKey::Member(ty, member));
+                check_custom_display(parser.entity(entity), displays);
+            });
+        }
+
+        // Named members without a bitfield width are not entities
+        assert!(parser.parse_entity("int x").is_err());
+
+        // Alias declarations
+        assert_matches!(
+            parser.parse_entity("using value_type = std::vector<T>"),
+            Ok(entity) => {
+                assert_eq!(
+                    entity,
+                    EntityKey::Alias(unwrap_parse(
+                        parser.parse_alias_declaration("using value_type = std::vector<T>")
+                    ))
+                );
+                check_custom_display(
+                    parser.entity(entity),
+                    &[
+                        "using value_type = …::vector<…>",
+                        "using value_type = std::vector<T>",
+                    ],
+                );
+            }
+        );
+
         // The infamous unknown clang entity
-        assert_eq!(parser.parse_entity("<unknown>"), Ok(None));
-        check_custom_display(parser.entity(None), &["<unknown>"]);
+        assert_eq!(parser.parse_entity("<unknown>"), Ok(EntityKey::Unknown));
+        check_custom_display(parser.entity(EntityKey::Unknown), &["<unknown>"]);
+    }
+
+    #[test]
+    fn parse_many() {
+        let mut parser = EntityParser::new();
+        let inputs = [
+            "std::vector<int>",
+            "std::vector<int",
+            "<unknown>",
+            "void (*)(int)",
+            "",
+            "std::vector<int>",
+        ];
+        let results = parser.parse_many(inputs.into_iter());
+        assert_eq!(results.len(), inputs.len());
+
+        // Successful parses match what parse_entity would produce, and share
+        // interned data
+        let vector = parser.parse_entity(inputs[0]).unwrap();
+        assert_eq!(results[0], Ok(vector));
+        assert_eq!(results[2], Ok(EntityKey::Unknown));
+        assert!(results[3].is_ok());
+        assert_eq!(results[5], Ok(vector));
+
+        // Failed parses report the offset where parsing stopped
+        assert_eq!(results[1].map_err(|e| e.offset), Err(11));
+        assert_eq!(results[4].map_err(|e| e.offset), Err(0));
+    }
+
+    #[test]
+    fn owned_entity() {
+        // Record how an entity is displayed, then copy it and drop its parser,
+        // which also contains unrelated entities
+        let check_owned_entity = |input: &str| {
+            let parser = EntityParser::new();
+            parser
+                .parse_entity("unrelated::entity<with, (lambda at /some/path.cpp:1:2)>")
+                .unwrap();
+            let key = parser.parse_entity(input).unwrap();
+            let displays = {
+                let entity = parser.entity(key);
+                (0..=entity.recursion_depth())
+                    .map(|depth| entity.display(&DisplayState::new(depth)).to_string())
+                    .collect::<Vec<_>>()
+            };
+            let owned = parser.to_owned_entity(key);
+            std::mem::drop(parser);
+
+            // The copy is displayed just like the original
+            let displays = displays.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+            check_custom_display(owned.entity(), &displays);
+            assert_eq!(owned.to_string(), displays[displays.len() - 1]);
+
+            // The copy only holds the entity's own components
+            let fresh = EntityParser::new();
+            fresh.parse_entity(input).unwrap();
+            let (owned_stats, fresh_stats) = (owned.entities.intern_stats(), fresh.intern_stats());
+            assert!(owned_stats.identifiers <= fresh_stats.identifiers);
+            assert!(owned_stats.unique_path_components <= fresh_stats.unique_path_components);
+            assert!(owned_stats.types <= fresh_stats.types);
+            assert!(owned_stats.values <= fresh_stats.values);
+        };
+        check_owned_entity("<unknown>");
+        check_owned_entity("std::map<std::string, std::vector<int>>::at(const std::string&) const");
+        check_owned_entity("(anonymous namespace)::f<(lambda at /path/to/source.cpp:12:34)>");
+        check_owned_entity("void (*)(int, unsigned long)");
+        check_owned_entity("int (Class::*)(float) const && noexcept(N > 2)");
+        check_owned_entity("alignas(16) S<sizeof(T), 42_km, new (p) T[4]>[N]");
+        check_owned_entity("std::enable_if_t<requires (T t) { t.f(); typename T::type; }, bool>");
+        check_owned_entity("operator\"\" _x<ns::{lambda(int)#1}, operator int>");
+        check_owned_entity("decltype(a.b->c(d ? e : f, ++g...))");
+        check_owned_entity("using iterator = typename std::vector<T>::iterator");
+        check_owned_entity("std::uint32_t : sizeof(T) * CHAR_BIT");
+    }
+
+    #[test]
+    fn shared_entity() {
+        // Entities can be shared while the parser keeps interning new ones
+        let parser = Rc::new(EntityParser::new());
+        let key = parser.parse_entity("std::vector<int>").unwrap();
+        let shared = SharedEntity::new(parser.clone(), key);
+        let other = parser.parse_entity("std::map<K, V>").unwrap();
+        assert_ne!(key, other);
+        assert_eq!(shared.to_string(), "std::vector<int>");
+        check_custom_display(shared.clone(), &["…::vector<…>", "std::vector<int>"]);
+
+        // Sharing does not copy the parser, which outlives the original handle
+        std::mem::drop(parser);
+        assert!(Rc::ptr_eq(&shared.entities, &shared.clone().entities));
+        assert_eq!(shared.entity().to_string(), "std::vector<int>");
+    }
+
+    #[test]
+    fn clear() {
+        let mut parser = EntityParser::new();
+        assert_eq!(parser.intern_stats(), InternStats::default());
+
+        // Intern many entities and paths
+        for i in 0..100 {
+            parser
+                .parse_entity(&format!(
+                    "ns{i}::f<std::vector<T{i}*>, {i}>(int (&)[{i}], const char*) const"
+                ))
+                .unwrap();
+            parser.intern_path(&format!("/usr/include/dir{i}/header{i}.h"));
+        }
+        let footprint = parser.memory_footprint();
+        let stats = parser.intern_stats();
+        assert!(stats.identifiers >= 200);
+        assert!(stats.unique_path_components >= 200);
+        assert!(stats.types > 0);
+        assert!(stats.values > 0);
+        assert!(stats.template_parameters > 0);
+        assert!(stats.function_parameters > 0);
+        assert!(stats.scopes > 0);
+        assert!(stats.decl_operators > 0);
+
+        // Clearing the parser drops everything
+        parser.clear();
+        assert_eq!(parser.intern_stats(), InternStats::default());
+        assert_eq!(
+            parser.memory_footprint(),
+            EntityParser::new().memory_footprint()
+        );
+        assert!(parser.memory_footprint() < footprint);
+
+        // The parser remains usable afterwards
+        let entity = parser.parse_entity("std::vector<int>").unwrap();
+        assert_eq!(parser.entity(entity).to_string(), "std::vector<int>");
     }
 }
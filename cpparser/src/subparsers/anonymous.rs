@@ -5,7 +5,7 @@ use crate::{
     display::{CustomDisplay, DisplayState},
     subparsers::{
         functions::{FunctionSignature, FunctionSignatureView},
-        names::atoms::{IdentifierKey, IdentifierView},
+        names::atoms::{self, IdentifierKey, IdentifierView},
     },
     EntityParser, IResult, InternedPath, PathKey,
 };
@@ -42,6 +42,18 @@ impl EntityParser {
             Some(b'(') => ((|s| self.parse_clang_lambda_imut(s)).map(AnonymousEntity::ClangLambda))
                 .or((|s| self.parse_clang_anonymous_imut(s)).map(AnonymousEntity::ClangOther))
                 .parse(s),
+            Some(b'`') => (|s| self.parse_msvc_anonymous_imut(s))
+                .map(AnonymousEntity::ClangOther)
+                .parse(s),
+            Some(b'<') => Self::parse_msvc_lambda
+                .map(AnonymousEntity::MsvcLambda)
+                .parse(s),
+            Some(b'\'') => (|s| self.parse_llvm_lambda_imut(s))
+                .map(AnonymousEntity::LlvmLambda)
+                .parse(s),
+            Some(b'$') => Self::parse_clang_numbered
+                .map(AnonymousEntity::ClangNumbered)
+                .parse(s),
             _ => ((|s| self.parse_libiberty_lambda_imut(s)).map(AnonymousEntity::LibibertyLambda))
                 .or((|s| self.parse_libiberty_unnamed_imut(s))
                     .map(AnonymousEntity::LibibertyUnnamed))
@@ -120,6 +132,70 @@ impl EntityParser {
         ClangAnonymousEntityView::new(a, self)
     }
 
+    /// Parser for MSVC anonymous entities like `` `anonymous namespace' ``
+    ///
+    /// These carry the same information as clang's `(anonymous namespace)`, so
+    /// they are parsed into the same representation.
+    ///
+    pub(crate) fn parse_msvc_anonymous_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, ClangAnonymousEntity> {
+        use nom::{character::complete::char, sequence::delimited};
+        use nom_supreme::tag::complete::tag;
+        delimited(
+            tag("`anonymous "),
+            |s| self.parse_identifier_imut(s),
+            char('\''),
+        )
+        .map(|identifier| ClangAnonymousEntity {
+            identifier: Some(identifier),
+            location: None,
+        })
+        .parse(s)
+    }
+
+    /// Parser for MSVC lambda types `<lambda_<id>>`
+    pub(crate) fn parse_msvc_lambda(s: &str) -> IResult<MsvcLambda> {
+        use nom::{
+            character::complete::{char, u32},
+            sequence::delimited,
+        };
+        use nom_supreme::tag::complete::tag;
+        delimited(tag("<lambda_"), u32, char('>'))
+            .map(MsvcLambda)
+            .parse(s)
+    }
+
+    /// Parser for LLVM demangler lambda types `'lambda<id>'(<param>, ...)`
+    fn parse_llvm_lambda_imut<'source>(&self, s: &'source str) -> IResult<'source, LlvmLambda> {
+        use nom::{
+            character::complete::{char, u32},
+            combinator::opt,
+            sequence::delimited,
+        };
+        use nom_supreme::tag::complete::tag;
+        let id = delimited(tag("'lambda"), opt(u32), char('\''));
+        id.and(|s| self.parse_function_signature_imut(s))
+            .map(|(id, signature)| LlvmLambda { signature, id })
+            .parse(s)
+    }
+
+    /// Access a previously parsed LLVM demangler-style lambda
+    pub(crate) fn llvm_lambda(&self, l: LlvmLambda) -> LlvmLambdaView {
+        LlvmLambdaView::new(l, self)
+    }
+
+    /// Parser for clang's numbered local entities `$_<id>`
+    pub(crate) fn parse_clang_numbered(s: &str) -> IResult<ClangNumberedEntity> {
+        use nom::{character::complete::u32, sequence::preceded};
+        use nom_supreme::tag::complete::tag;
+        preceded(tag("$_"), u32)
+            .terminated(atoms::end_of_identifier)
+            .map(ClangNumberedEntity)
+            .parse(s)
+    }
+
     /// Parser for a libiberty-style anonymous type
     pub(crate) fn parse_libiberty_unnamed_imut<'source>(
         &self,
@@ -168,6 +244,45 @@ impl EntityParser {
     pub(crate) fn source_location(&self, sl: SourceLocation) -> SourceLocationView {
         SourceLocationView::new(sl, self)
     }
+
+    /// Copy a previously parsed source code location into another parser
+    fn copy_source_location(&self, sl: SourceLocation, dst: &Self) -> SourceLocation {
+        SourceLocation {
+            file: self.copy_path(sl.file, dst),
+            location: sl.location,
+        }
+    }
+
+    /// Copy a previously parsed anonymous entity into another parser
+    pub(crate) fn copy_anonymous(&self, a: AnonymousEntity, dst: &Self) -> AnonymousEntity {
+        match a {
+            AnonymousEntity::ClangLambda(ClangLambda(location)) => {
+                AnonymousEntity::ClangLambda(ClangLambda(self.copy_source_location(location, dst)))
+            }
+            AnonymousEntity::ClangOther(ClangAnonymousEntity {
+                identifier,
+                location,
+            }) => AnonymousEntity::ClangOther(ClangAnonymousEntity {
+                identifier: identifier.map(|id| self.copy_identifier(id, dst)),
+                location: location.map(|location| self.copy_source_location(location, dst)),
+            }),
+            AnonymousEntity::LibibertyLambda(LibibertyLambda { signature, id }) => {
+                AnonymousEntity::LibibertyLambda(LibibertyLambda {
+                    signature: self.copy_function_signature(signature, dst),
+                    id,
+                })
+            }
+            AnonymousEntity::LlvmLambda(LlvmLambda { signature, id }) => {
+                AnonymousEntity::LlvmLambda(LlvmLambda {
+                    signature: self.copy_function_signature(signature, dst),
+                    id,
+                })
+            }
+            AnonymousEntity::LibibertyUnnamed(_)
+            | AnonymousEntity::MsvcLambda(_)
+            | AnonymousEntity::ClangNumbered(_) => a,
+        }
+    }
 }
 
 /// Anonymous entities including lambdas
@@ -184,6 +299,16 @@ pub enum AnonymousEntity {
 
     /// Libiberty-style unnamed type `{unamed type#123}`
     LibibertyUnnamed(LibibertyUnnamedType),
+
+    /// MSVC-style lambda with numeric ID `<lambda_1>`
+    MsvcLambda(MsvcLambda),
+
+    /// LLVM demangler-style lambda with optional numeric ID and parameter
+    /// types `'lambda'(...)`, `'lambda0'(...)`
+    LlvmLambda(LlvmLambda),
+
+    /// Clang-style numbered local entity `$_0`
+    ClangNumbered(ClangNumberedEntity),
 }
 //
 impl Default for AnonymousEntity {
@@ -206,6 +331,16 @@ pub enum AnonymousEntityView<'entities> {
 
     /// Libiberty-style unnamed type `{unamed type#123}`
     LibibertyUnnamed(LibibertyUnnamedType),
+
+    /// MSVC-style lambda with numeric ID `<lambda_1>`
+    MsvcLambda(MsvcLambda),
+
+    /// LLVM demangler-style lambda with optional numeric ID and parameter
+    /// types `'lambda'(...)`, `'lambda0'(...)`
+    LlvmLambda(LlvmLambdaView<'entities>),
+
+    /// Clang-style numbered local entity `$_0`
+    ClangNumbered(ClangNumberedEntity),
 }
 //
 impl<'entities> AnonymousEntityView<'entities> {
@@ -218,6 +353,9 @@ impl<'entities> AnonymousEntityView<'entities> {
                 Self::LibibertyLambda(entities.libiberty_lambda(l))
             }
             AnonymousEntity::LibibertyUnnamed(u) => Self::LibibertyUnnamed(u),
+            AnonymousEntity::MsvcLambda(l) => Self::MsvcLambda(l),
+            AnonymousEntity::LlvmLambda(l) => Self::LlvmLambda(entities.llvm_lambda(l)),
+            AnonymousEntity::ClangNumbered(n) => Self::ClangNumbered(n),
         }
     }
 }
@@ -236,6 +374,9 @@ impl<'entities> CustomDisplay for AnonymousEntityView<'entities> {
             Self::ClangOther(_) => 0,
             Self::LibibertyLambda(l) => l.recursion_depth(),
             Self::LibibertyUnnamed(_) => 0,
+            Self::MsvcLambda(_) => 0,
+            Self::LlvmLambda(l) => l.recursion_depth(),
+            Self::ClangNumbered(_) => 0,
         }
     }
 
@@ -245,6 +386,9 @@ impl<'entities> CustomDisplay for AnonymousEntityView<'entities> {
             Self::ClangOther(o) => write!(f, "{o}"),
             Self::LibibertyLambda(l) => l.display_impl(f, state),
             Self::LibibertyUnnamed(u) => write!(f, "{u}"),
+            Self::MsvcLambda(l) => write!(f, "{l}"),
+            Self::LlvmLambda(l) => l.display_impl(f, state),
+            Self::ClangNumbered(n) => write!(f, "{n}"),
         }
     }
 }
@@ -402,6 +546,73 @@ impl<'entities> CustomDisplay for LibibertyLambdaView<'entities> {
     }
 }
 
+/// LLVM demangler-style lambda featuring parameter types and a numeric ID
+///
+/// The numeric ID is absent for the first lambda of a given scope, then
+/// starts at 0 for the second one.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LlvmLambda {
+    /// Parameter types
+    signature: FunctionSignature,
+
+    /// Numeric identifier
+    id: Option<u32>,
+}
+
+/// View of an LLVM demangler-style lambda
+pub struct LlvmLambdaView<'entities> {
+    /// Wrapped LlvmLambda
+    inner: LlvmLambda,
+
+    /// Underlying interned entity storage
+    entities: &'entities EntityParser,
+}
+//
+impl<'entities> LlvmLambdaView<'entities> {
+    /// Build an LLVM lambda view
+    pub fn new(inner: LlvmLambda, entities: &'entities EntityParser) -> Self {
+        Self { inner, entities }
+    }
+
+    /// Parameter types
+    pub fn signature(&self) -> FunctionSignatureView {
+        self.entities.function_signature(self.inner.signature)
+    }
+
+    /// Numeric identifier
+    pub fn id(&self) -> Option<u32> {
+        self.inner.id
+    }
+}
+//
+impl<'entities> PartialEq for LlvmLambdaView<'entities> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.entities, other.entities) && (self.inner == other.inner)
+    }
+}
+//
+impl<'entities> Display for LlvmLambdaView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for LlvmLambdaView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        self.signature().recursion_depth()
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        write!(f, "'lambda")?;
+        if let Some(id) = self.id() {
+            write!(f, "{id}")?;
+        }
+        write!(f, "'")?;
+        self.signature().display_impl(f, state)
+    }
+}
+
 /// Anonymous clang entity
 ///
 /// This models clang's use of `(anonymous)`, `(anonymous <something>)` and
@@ -446,6 +657,12 @@ impl<'entities> ClangAnonymousEntityView<'entities> {
             .location
             .map(|location| self.entities.source_location(location))
     }
+
+    /// Truth that this is an anonymous namespace
+    pub fn is_namespace(&self) -> bool {
+        self.identifier()
+            .is_some_and(|identifier| &*identifier == "namespace")
+    }
 }
 //
 impl<'entities> PartialEq for ClangAnonymousEntityView<'entities> {
@@ -479,6 +696,30 @@ impl Display for LibibertyUnnamedType {
     }
 }
 
+/// Clang-style numbered local entity `$_N`
+///
+/// Clang uses this naming scheme for unnamed local types, including lambdas,
+/// in some of its output.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ClangNumberedEntity(u32);
+//
+impl Display for ClangNumberedEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "$_{}", self.0)
+    }
+}
+
+/// MSVC-style lambda `<lambda_N>`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MsvcLambda(u32);
+//
+impl Display for MsvcLambda {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "<lambda_{}>", self.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -596,6 +837,78 @@ mod tests {
         assert_eq!(format!("{expected}"), "{unnamed type#42}");
     }
 
+    #[test]
+    fn msvc_anonymous() {
+        let parser = EntityParser::new();
+        let expected = unwrap_parse(parser.parse_clang_anonymous_imut("(anonymous namespace)"));
+        assert_eq!(
+            parser.parse_msvc_anonymous_imut("`anonymous namespace'"),
+            Ok(("", expected))
+        );
+        assert_eq!(
+            parser.clang_anonymous(expected).to_string(),
+            "(anonymous namespace)"
+        );
+        assert!(parser
+            .parse_msvc_anonymous_imut("`anonymous namespace")
+            .is_err());
+    }
+
+    #[test]
+    fn msvc_lambda() {
+        let expected = MsvcLambda(1);
+        assert_eq!(
+            EntityParser::parse_msvc_lambda("<lambda_1>"),
+            Ok(("", expected))
+        );
+        assert_eq!(format!("{expected}"), "<lambda_1>");
+        assert!(EntityParser::parse_msvc_lambda("<lambda_>").is_err());
+    }
+
+    #[test]
+    fn llvm_lambda() {
+        let mut parser = EntityParser::new();
+        let mut expected = LlvmLambda {
+            signature: unwrap_parse(parser.parse_function_signature("()")),
+            id: None,
+        };
+        assert_eq!(
+            parser.parse_llvm_lambda_imut("'lambda'()"),
+            Ok(("", expected))
+        );
+        check_custom_display(parser.llvm_lambda(expected), &["'lambda'()"]);
+
+        expected = LlvmLambda {
+            signature: unwrap_parse(parser.parse_function_signature("(int, auto)")),
+            id: Some(0),
+        };
+        assert_eq!(
+            parser.parse_llvm_lambda_imut("'lambda0'(int, auto)"),
+            Ok(("", expected))
+        );
+        check_custom_display(
+            parser.llvm_lambda(expected),
+            &["'lambda0'(…)", "'lambda0'(int, auto)"],
+        );
+        assert!(parser.parse_llvm_lambda_imut("'lambda'").is_err());
+    }
+
+    #[test]
+    fn clang_numbered() {
+        let expected = ClangNumberedEntity(0);
+        assert_eq!(
+            EntityParser::parse_clang_numbered("$_0"),
+            Ok(("", expected))
+        );
+        assert_eq!(format!("{expected}"), "$_0");
+        assert_eq!(
+            EntityParser::parse_clang_numbered("$_12::"),
+            Ok(("::", ClangNumberedEntity(12)))
+        );
+        assert!(EntityParser::parse_clang_numbered("$_").is_err());
+        assert!(EntityParser::parse_clang_numbered("$_0x").is_err());
+    }
+
     #[test]
     fn anonymous() {
         let mut parser = EntityParser::new();
@@ -635,5 +948,33 @@ mod tests {
                 Ok(("", expected))
             );
         }
+
+        {
+            let msvc_anonymous = "`anonymous namespace'";
+            let expected = AnonymousEntity::ClangOther(unwrap_parse(
+                parser.parse_msvc_anonymous_imut(msvc_anonymous),
+            ));
+            assert_eq!(parser.parse_anonymous(msvc_anonymous), Ok(("", expected)));
+        }
+
+        {
+            let msvc_lambda = "<lambda_1>";
+            let expected = AnonymousEntity::MsvcLambda(MsvcLambda(1));
+            assert_eq!(parser.parse_anonymous(msvc_lambda), Ok(("", expected)));
+        }
+
+        {
+            let llvm_lambda = "'lambda'()";
+            let expected = AnonymousEntity::LlvmLambda(unwrap_parse(
+                parser.parse_llvm_lambda_imut(llvm_lambda),
+            ));
+            assert_eq!(parser.parse_anonymous(llvm_lambda), Ok(("", expected)));
+        }
+
+        {
+            let clang_numbered = "$_0";
+            let expected = AnonymousEntity::ClangNumbered(ClangNumberedEntity(0));
+            assert_eq!(parser.parse_anonymous(clang_numbered), Ok(("", expected)));
+        }
     }
 }
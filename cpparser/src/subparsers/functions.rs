@@ -96,6 +96,19 @@ impl EntityParser {
         FunctionArgumentsView::new(a, self.function_arguments.borrow(), self)
     }
 
+    /// Copy a previously parsed list of function arguments into another parser
+    pub(crate) fn copy_function_arguments(
+        &self,
+        a: FunctionArgumentsKey,
+        dst: &Self,
+    ) -> FunctionArgumentsKey {
+        let mut entry = dst.function_arguments.entry();
+        for &value in self.function_arguments.get(a).iter() {
+            entry.push(self.copy_value_like(value, dst));
+        }
+        entry.intern()
+    }
+
     /// Retrieve a function call previously parsed by parse_function_call
     #[cfg(test)]
     pub(crate) fn raw_function_arguments(&self, key: FunctionArgumentsKey) -> ARef<[ValueKey]> {
@@ -126,38 +139,108 @@ impl EntityParser {
         s: &'source str,
     ) -> IResult<'source, FunctionSignature> {
         use nom::{
+            bytes::complete::take_until,
             character::complete::{char, multispace0},
             combinator::opt,
-            sequence::{delimited, preceded, tuple},
+            multi::fold_many0,
+            sequence::{delimited, preceded},
         };
         use nom_supreme::tag::complete::tag;
 
         // ABI indicator (appears in demangled names)
         let abi = delimited(tag("[abi:"), |s| self.parse_identifier_imut(s), char(']'));
 
-        let type_like = |s| self.parse_type_like_imut(s);
-        let trailing_return = preceded(tag("->").and(multispace0), &type_like);
-
-        let mut tuple = tuple((
-            opt(abi),
-            (|s| self.parse_function_parameter_set_imut(s)).terminated(multispace0),
-            Self::parse_cv.terminated(multispace0),
-            Self::parse_reference.terminated(multispace0),
-            opt((|s| self.parse_noexcept_imut(s)).terminated(multispace0)),
-            opt(trailing_return),
-        ))
-        .map(
-            |(abi, parameter_set, cv, reference, noexcept, trailing_return)| FunctionSignature {
-                abi,
-                parameter_set,
-                cv,
-                reference,
-                noexcept,
-                trailing_return,
+        // Trailing specifiers, which clang may emit in any order
+        let cv = Self::parse_cv
+            .verify(|&cv| cv != ConstVolatile::default())
+            .map(TrailingSpecifier::Cv);
+        let reference = Self::parse_reference
+            .verify(|&reference| reference != Reference::None)
+            .map(TrailingSpecifier::Reference);
+        let noexcept = (|s| self.parse_noexcept_imut(s)).map(TrailingSpecifier::Noexcept);
+        let throw_spec = preceded(Self::keyword_parser("throw").and(multispace0), |s| {
+            self.parse_function_parameter_set_imut(s)
+        })
+        .map(TrailingSpecifier::Throw);
+        let attributes =
+            delimited(tag("[["), take_until("]]"), tag("]]")).value(TrailingSpecifier::Attributes);
+        let trailing_return =
+            preceded(tag("->").and(multispace0), |s| self.parse_type_like_imut(s))
+                .map(TrailingSpecifier::TrailingReturn);
+        let requires = preceded(Self::keyword_parser("requires").and(multispace0), |s| {
+            self.parse_value_like_imut(s, false, false)
+        })
+        .map(TrailingSpecifier::Requires);
+        let trailing_specifier = cv
+            .or(reference)
+            .or(noexcept)
+            .or(throw_spec)
+            .or(attributes)
+            .or(trailing_return)
+            .or(requires);
+
+        let (s, calling_convention) =
+            opt(Self::parse_calling_convention.terminated(multispace0)).parse(s)?;
+        let (s, abi) = opt(abi).parse(s)?;
+        let (s, parameter_set) = (|s| self.parse_function_parameter_set_imut(s))
+            .terminated(multispace0)
+            .parse(s)?;
+        fold_many0(
+            trailing_specifier.terminated(multispace0),
+            move || {
+                let signature = FunctionSignature {
+                    calling_convention,
+                    abi,
+                    ..FunctionSignature::from(parameter_set)
+                };
+                (signature, false, false)
             },
-        );
+            |(mut signature, bad, mut seen_noexcept), item| {
+                // Each specifier may only appear once, except for cv
+                // qualifiers which clang sometimes emits redundantly.
+                // noexcept(false) is not recorded in the signature, so we
+                // must separately track whether noexcept was seen at all.
+                let duplicate = match item {
+                    TrailingSpecifier::Cv(cv) => {
+                        signature.cv = signature.cv | cv;
+                        false
+                    }
+                    TrailingSpecifier::Reference(reference) => {
+                        let duplicate = signature.reference != Reference::None;
+                        signature.reference = reference;
+                        duplicate
+                    }
+                    TrailingSpecifier::Noexcept(noexcept) => {
+                        signature.noexcept = noexcept;
+                        std::mem::replace(&mut seen_noexcept, true)
+                    }
+                    TrailingSpecifier::Throw(exceptions) => {
+                        signature.throw_spec.replace(exceptions).is_some()
+                    }
+                    TrailingSpecifier::Attributes => false,
+                    TrailingSpecifier::TrailingReturn(ty) => {
+                        signature.trailing_return.replace(ty).is_some()
+                    }
+                    TrailingSpecifier::Requires(constraint) => {
+                        signature.requires.replace(constraint).is_some()
+                    }
+                };
+                (signature, bad || duplicate, seen_noexcept)
+            },
+        )
+        .verify(|(_signature, bad, _seen_noexcept)| !bad)
+        .map(|(signature, _bad, _seen_noexcept)| signature)
+        .parse(s)
+    }
 
-        tuple.parse(s)
+    /// Parser recognizing an MSVC calling convention keyword
+    pub fn parse_calling_convention(s: &str) -> IResult<CallingConvention> {
+        Self::keywords_parser([
+            ("__cdecl", CallingConvention::Cdecl),
+            ("__thiscall", CallingConvention::Thiscall),
+            ("__stdcall", CallingConvention::Stdcall),
+            ("__fastcall", CallingConvention::Fastcall),
+        ])(s)
     }
 
     /// Access a previously parsed function signature
@@ -165,6 +248,29 @@ impl EntityParser {
         FunctionSignatureView::new(s, self)
     }
 
+    /// Copy a previously parsed function signature into another parser
+    pub(crate) fn copy_function_signature(
+        &self,
+        s: FunctionSignature,
+        dst: &Self,
+    ) -> FunctionSignature {
+        FunctionSignature {
+            calling_convention: s.calling_convention,
+            abi: s.abi.map(|abi| self.copy_identifier(abi, dst)),
+            parameter_set: self.copy_function_parameter_set(s.parameter_set, dst),
+            cv: s.cv,
+            reference: s.reference,
+            noexcept: s
+                .noexcept
+                .map(|arg| arg.map(|value| self.copy_value_like(value, dst))),
+            throw_spec: s
+                .throw_spec
+                .map(|set| self.copy_function_parameter_set(set, dst)),
+            trailing_return: s.trailing_return.map(|ty| self.copy_type_like(ty, dst)),
+            requires: s.requires.map(|value| self.copy_value_like(value, dst)),
+        }
+    }
+
     /// Parser recognizing a function parameter set
     fn parse_function_parameter_set_imut<'source>(
         &self,
@@ -220,6 +326,22 @@ impl EntityParser {
         FunctionParametersView::new(a, self.function_parameters.borrow(), self)
     }
 
+    /// Copy a previously parsed function parameter set into another parser
+    fn copy_function_parameter_set(
+        &self,
+        set: FunctionParameterSet,
+        dst: &Self,
+    ) -> FunctionParameterSet {
+        let mut entry = dst.function_parameters.entry();
+        for &ty in self.function_parameters.get(set.parameters).iter() {
+            entry.push(self.copy_type_like(ty, dst));
+        }
+        FunctionParameterSet {
+            parameters: entry.intern(),
+            variadic: set.variadic,
+        }
+    }
+
     /// Retrieve a function parameter set previously parsed by parse_function_signature
     #[cfg(test)]
     pub(crate) fn raw_function_parameters(&self, key: FunctionParametersKey) -> ARef<[TypeKey]> {
@@ -237,21 +359,32 @@ impl EntityParser {
     }
 
     /// Parser recognizing the noexcept qualifier and its optional argument
-    fn parse_noexcept_imut<'source>(&self, s: &'source str) -> IResult<'source, Option<ValueKey>> {
+    ///
+    /// Like compilers, this canonicalizes `noexcept(true)` into `noexcept`, and
+    /// `noexcept(false)` into the absence of a noexcept qualifier, which is
+    /// represented by the outer layer of Option being None.
+    ///
+    fn parse_noexcept_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, Option<Option<ValueKey>>> {
         use nom::{
             character::complete::{char, multispace0},
             combinator::opt,
             sequence::{delimited, preceded},
         };
-        preceded(
-            Self::keyword_parser("noexcept"),
-            opt(delimited(
-                char('(').and(multispace0),
-                |s| self.parse_value_like_imut(s, false, true),
-                multispace0.and(char(')')),
-            )),
-        )
-        .parse(s)
+        let canonical = delimited(
+            char('(').and(multispace0),
+            Self::keywords_parser([("true", Some(None)), ("false", None)]),
+            multispace0.and(char(')')),
+        );
+        let expression = opt(delimited(
+            char('(').and(multispace0),
+            |s| self.parse_value_like_imut(s, false, true),
+            multispace0.and(char(')')),
+        ))
+        .map(Some);
+        preceded(Self::keyword_parser("noexcept"), canonical.or(expression)).parse(s)
     }
 }
 
@@ -262,6 +395,9 @@ pub type FunctionArgumentsView<'entities> =
 /// Function signature
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct FunctionSignature {
+    /// Calling convention (appears in MSVC names)
+    calling_convention: Option<CallingConvention>,
+
     /// ABI identifier (appears in demangled names)
     abi: Option<IdentifierKey>,
 
@@ -282,23 +418,89 @@ pub struct FunctionSignature {
     ///
     noexcept: Option<Option<ValueKey>>,
 
+    /// Dynamic exception specification (deprecated `throw(...)` qualifier)
+    ///
+    /// The parameter set lists the exception types, and is marked variadic
+    /// for the MSVC-specific `throw(...)` which allows any exception.
+    ///
+    throw_spec: Option<FunctionParameterSet>,
+
     /// Trailing return type
     trailing_return: Option<TypeKey>,
+
+    /// Trailing requires-clause
+    requires: Option<ValueKey>,
 }
 //
 impl From<FunctionParameterSet> for FunctionSignature {
     fn from(parameter_set: FunctionParameterSet) -> Self {
         Self {
+            calling_convention: None,
             abi: None,
             parameter_set,
             cv: ConstVolatile::default(),
             reference: Reference::None,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         }
     }
 }
 
+/// Calling convention of a function, as spelled out by MSVC
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CallingConvention {
+    /// __cdecl
+    Cdecl,
+
+    /// __thiscall
+    Thiscall,
+
+    /// __stdcall
+    Stdcall,
+
+    /// __fastcall
+    Fastcall,
+}
+//
+impl Display for CallingConvention {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let s = match self {
+            Self::Cdecl => "__cdecl",
+            Self::Thiscall => "__thiscall",
+            Self::Stdcall => "__stdcall",
+            Self::Fastcall => "__fastcall",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Trailing specifier of a function signature
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum TrailingSpecifier {
+    /// CV qualifiers
+    Cv(ConstVolatile),
+
+    /// Reference qualifiers
+    Reference(Reference),
+
+    /// noexcept qualifier and its optional argument (None for noexcept(false))
+    Noexcept(Option<Option<ValueKey>>),
+
+    /// Dynamic exception specification
+    Throw(FunctionParameterSet),
+
+    /// Attribute specifier sequence (discarded)
+    Attributes,
+
+    /// Trailing return type
+    TrailingReturn(TypeKey),
+
+    /// Trailing requires-clause
+    Requires(ValueKey),
+}
+
 /// A view of a function signature
 pub struct FunctionSignatureView<'entities> {
     /// Wrapped FunctionSignature
@@ -309,11 +511,16 @@ pub struct FunctionSignatureView<'entities> {
 }
 //
 impl<'entities> FunctionSignatureView<'entities> {
-    /// Build a new-expression view
+    /// Build a function signature view
     pub fn new(inner: FunctionSignature, entities: &'entities EntityParser) -> Self {
         Self { inner, entities }
     }
 
+    /// Calling convention
+    pub fn calling_convention(&self) -> Option<CallingConvention> {
+        self.inner.calling_convention
+    }
+
     /// ABI
     pub fn abi(&self) -> Option<IdentifierView> {
         self.inner.abi.map(|abi| self.entities.identifier(abi))
@@ -352,12 +559,32 @@ impl<'entities> FunctionSignatureView<'entities> {
             .map(|o| o.map(|v| self.entities.value_like(v)))
     }
 
+    /// Dynamic exception specification
+    ///
+    /// If present, this is the list of exception types that the function is
+    /// allowed to throw, along with the truth that it ends with an ellipsis
+    /// (MSVC's `throw(...)`, which allows any exception to be thrown).
+    ///
+    pub fn throw_spec(&self) -> Option<(FunctionParametersView, bool)> {
+        self.inner.throw_spec.map(|exceptions| {
+            (
+                self.entities.function_parameters(exceptions.parameters),
+                exceptions.variadic,
+            )
+        })
+    }
+
     /// Trailing return type
     pub fn trailing_return(&self) -> Option<TypeView> {
         self.inner
             .trailing_return
             .map(|t| self.entities.type_like(t))
     }
+
+    /// Trailing requires-clause
+    pub fn requires(&self) -> Option<ValueView> {
+        self.inner.requires.map(|v| self.entities.value_like(v))
+    }
 }
 //
 impl<'entities> PartialEq for FunctionSignatureView<'entities> {
@@ -377,19 +604,24 @@ impl<'entities> CustomDisplay for FunctionSignatureView<'entities> {
         self.parameters()
             .recursion_depth()
             .max(self.noexcept().recursion_depth())
+            .max(
+                self.throw_spec()
+                    .map_or(0, |(exceptions, _)| exceptions.recursion_depth()),
+            )
             .max(self.trailing_return().recursion_depth())
+            .max(self.requires().recursion_depth())
     }
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        if let Some(calling_convention) = self.calling_convention() {
+            write!(f, "{calling_convention}")?;
+        }
+
         if let Some(abi) = self.abi() {
             write!(f, "[abi:{abi}]")?;
         }
 
-        self.parameters().display_impl(f, state)?;
-        if state.can_recurse() && self.variadic() {
-            write!(f, ", ...")?;
-        }
-        write!(f, ")")?;
+        Self::display_parameter_set(f, state, self.parameters(), self.variadic())?;
 
         let cv = self.cv();
         if cv != ConstVolatile::default() {
@@ -411,15 +643,46 @@ impl<'entities> CustomDisplay for FunctionSignatureView<'entities> {
             }
         }
 
+        if let Some((exceptions, variadic)) = self.throw_spec() {
+            write!(f, " throw")?;
+            Self::display_parameter_set(f, state, exceptions, variadic)?;
+        }
+
         let trailing_return = self.trailing_return();
         if let Some(ty) = trailing_return {
             write!(f, " -> ")?;
             ty.display_impl(f, state)?;
         }
+
+        if let Some(constraint) = self.requires() {
+            write!(f, " requires ")?;
+            constraint.display_impl(f, state)?;
+        }
         Ok(())
     }
 }
 
+//
+impl<'entities> FunctionSignatureView<'entities> {
+    /// Display a parenthesized parameter set, optionally ending with `...`
+    fn display_parameter_set(
+        f: &mut Formatter<'_>,
+        state: &DisplayState,
+        parameters: FunctionParametersView,
+        variadic: bool,
+    ) -> Result<(), fmt::Error> {
+        parameters.display_impl_with_limits(f, state, None, state.max_list_cols())?;
+        if variadic {
+            if parameters.is_empty() {
+                write!(f, "...")?;
+            } else if state.can_recurse() {
+                write!(f, ", ...")?;
+            }
+        }
+        write!(f, ")")
+    }
+}
+
 /// View of a function parameter set
 pub type FunctionParametersView<'entities> =
     SliceView<'entities, TypeKey, TypeView<'entities>, FunctionParametersKey>;
@@ -451,12 +714,69 @@ mod tests {
     #[test]
     fn noexcept() {
         let mut parser = EntityParser::new();
-        assert_eq!(parser.parse_noexcept_imut("noexcept"), Ok(("", None)));
+        assert_eq!(parser.parse_noexcept_imut("noexcept"), Ok(("", Some(None))));
         assert_eq!(
             parser.parse_noexcept_imut("noexcept(123)"),
             Ok((
                 "",
-                Some(unwrap_parse(parser.parse_value_like("123", true, true)))
+                Some(Some(unwrap_parse(
+                    parser.parse_value_like("123", true, true)
+                )))
+            ))
+        );
+
+        // Nested noexcept operator
+        let nested = unwrap_parse(parser.parse_value_like("noexcept(f())", true, true));
+        assert_eq!(parser.value_like(nested).to_string(), "noexcept(f())");
+        assert_eq!(
+            parser.parse_noexcept_imut("noexcept(noexcept(f()))"),
+            Ok(("", Some(Some(nested))))
+        );
+
+        // Boolean combinations of noexcept conditions
+        assert_eq!(
+            parser.parse_noexcept_imut("noexcept(A && B)"),
+            Ok((
+                "",
+                Some(Some(unwrap_parse(
+                    parser.parse_value_like("A && B", true, true)
+                )))
+            ))
+        );
+        let combined = unwrap_parse(parser.parse_value_like(
+            "noexcept(std::declval<T&>() = std::declval<T>()) && noexcept(g())",
+            true,
+            true,
+        ));
+        assert_eq!(
+            parser.parse_noexcept_imut(
+                "noexcept(noexcept(std::declval<T&>() = std::declval<T>()) && noexcept(g()))"
+            ),
+            Ok(("", Some(Some(combined))))
+        );
+        assert_eq!(
+            parser.value_like(combined).to_string(),
+            "noexcept(std::declval<T&>() = std::declval<T>()) && noexcept(g())"
+        );
+
+        // Canonical forms of constant noexcept conditions
+        assert_eq!(
+            parser.parse_noexcept_imut("noexcept(true)"),
+            Ok(("", Some(None)))
+        );
+        assert_eq!(
+            parser.parse_noexcept_imut("noexcept( false )"),
+            Ok(("", None))
+        );
+        assert_eq!(
+            parser.parse_noexcept_imut("noexcept(true && B)"),
+            Ok((
+                "",
+                Some(Some(unwrap_parse(parser.parse_value_like(
+                    "true && B",
+                    true,
+                    true
+                ))))
             ))
         );
     }
@@ -494,85 +814,185 @@ mod tests {
         let check_function_signature = |parser: &mut EntityParser, input, expected, displays| {
             assert_eq!(parser.parse_function_signature(input), Ok(("", expected)));
             check_custom_display(parser.function_signature(expected), displays);
+
+            // The canonical display must parse back into the same signature
+            let canonical = parser.function_signature(expected).to_string();
+            assert_eq!(
+                parser.parse_function_signature(&canonical),
+                Ok(("", expected))
+            );
         };
 
         let mut expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::default(),
             reference: Reference::None,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "()", expected, &["()"]);
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: Some(unwrap_parse(parser.parse_identifier("cxx11"))),
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::default(),
             reference: Reference::None,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "[abi:cxx11]()", expected, &["[abi:cxx11]()"]);
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "(int)"),
             cv: ConstVolatile::default(),
             reference: Reference::None,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "(int)", expected, &["(…)", "(int)"]);
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::CONST,
             reference: Reference::None,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "() const", expected, &["() const"]);
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::default(),
             reference: Reference::RValue,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "() &&", expected, &["() &&"]);
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::default(),
             reference: Reference::None,
             noexcept: Some(None),
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "() noexcept", expected, &["() noexcept"]);
+        check_function_signature(&mut parser, "() noexcept(true)", expected, &["() noexcept"]);
+
+        expected = FunctionSignature {
+            calling_convention: None,
+            abi: None,
+            parameter_set: parameter_set(&mut parser, "()"),
+            cv: ConstVolatile::CONST,
+            reference: Reference::None,
+            noexcept: None,
+            throw_spec: None,
+            trailing_return: None,
+            requires: None,
+        };
+        check_function_signature(
+            &mut parser,
+            "() const noexcept(false)",
+            expected,
+            &["() const"],
+        );
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::VOLATILE,
             reference: Reference::LValue,
             noexcept: None,
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(&mut parser, "() volatile &", expected, &["() volatile &"]);
 
         expected = FunctionSignature {
+            calling_convention: None,
+            abi: None,
+            parameter_set: parameter_set(&mut parser, "()"),
+            cv: ConstVolatile::CONST,
+            reference: Reference::LValue,
+            noexcept: None,
+            throw_spec: None,
+            trailing_return: None,
+            requires: None,
+        };
+        check_function_signature(&mut parser, "() const&", expected, &["() const &"]);
+
+        expected = FunctionSignature {
+            calling_convention: None,
+            abi: None,
+            parameter_set: parameter_set(&mut parser, "()"),
+            cv: ConstVolatile::default(),
+            reference: Reference::LValue,
+            noexcept: None,
+            throw_spec: None,
+            trailing_return: None,
+            requires: None,
+        };
+        check_function_signature(&mut parser, "() &", expected, &["() &"]);
+
+        expected = FunctionSignature {
+            calling_convention: None,
+            abi: None,
+            parameter_set: parameter_set(&mut parser, "()"),
+            cv: ConstVolatile::CONST,
+            reference: Reference::RValue,
+            noexcept: Some(None),
+            throw_spec: None,
+            trailing_return: None,
+            requires: None,
+        };
+        check_function_signature(
+            &mut parser,
+            "() const&& noexcept",
+            expected,
+            &["() const && noexcept"],
+        );
+
+        // Non-canonical qualifier order is accepted, but displayed canonically
+        assert_eq!(
+            parser.parse_function_signature("() & const"),
+            parser.parse_function_signature("() const&")
+        );
+
+        expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::CONST | ConstVolatile::VOLATILE,
             reference: Reference::None,
             noexcept: Some(None),
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(
             &mut parser,
@@ -582,6 +1002,7 @@ mod tests {
         );
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::default(),
@@ -589,7 +1010,9 @@ mod tests {
             noexcept: Some(Some(unwrap_parse(
                 parser.parse_value_like("456", true, true),
             ))),
+            throw_spec: None,
             trailing_return: None,
+            requires: None,
         };
         check_function_signature(
             &mut parser,
@@ -599,14 +1022,181 @@ mod tests {
         );
 
         expected = FunctionSignature {
+            calling_convention: None,
             abi: None,
             parameter_set: parameter_set(&mut parser, "()"),
             cv: ConstVolatile::default(),
             reference: Reference::None,
             noexcept: None,
+            throw_spec: None,
             trailing_return: Some(unwrap_parse(parser.parse_type_like("int"))),
+            requires: None,
         };
         check_function_signature(&mut parser, "() -> int", expected, &["() -> int"]);
+
+        expected = FunctionSignature {
+            calling_convention: Some(CallingConvention::Stdcall),
+            ..FunctionSignature::from(parameter_set(&mut parser, "(int)"))
+        };
+        check_function_signature(
+            &mut parser,
+            "__stdcall (int)",
+            expected,
+            &["__stdcall(…)", "__stdcall(int)"],
+        );
+
+        expected = FunctionSignature {
+            throw_spec: Some(parameter_set(&mut parser, "()")),
+            ..FunctionSignature::from(parameter_set(&mut parser, "()"))
+        };
+        check_function_signature(&mut parser, "() throw()", expected, &["() throw()"]);
+
+        expected = FunctionSignature {
+            throw_spec: Some(parameter_set(&mut parser, "(std::bad_alloc)")),
+            ..FunctionSignature::from(parameter_set(&mut parser, "()"))
+        };
+        check_function_signature(
+            &mut parser,
+            "() throw(std::bad_alloc)",
+            expected,
+            &[
+                "() throw(…)",
+                "() throw(…::bad_alloc)",
+                "() throw(std::bad_alloc)",
+            ],
+        );
+
+        expected = FunctionSignature {
+            cv: ConstVolatile::CONST,
+            throw_spec: Some(parameter_set(&mut parser, "(...)")),
+            ..FunctionSignature::from(parameter_set(&mut parser, "(...)"))
+        };
+        check_function_signature(
+            &mut parser,
+            "(...) const throw(...)",
+            expected,
+            &["(...) const throw(...)"],
+        );
+        assert!(parser
+            .parse_function_signature("() throw() throw()")
+            .is_err());
+    }
+
+    #[test]
+    fn trailing_specifiers() {
+        let mut parser = EntityParser::new();
+        let mut check_orders = |inputs: &[&str], display| {
+            let expected = unwrap_parse(parser.parse_function_signature(inputs[0]));
+            for input in inputs {
+                assert_eq!(parser.parse_function_signature(input), Ok(("", expected)));
+            }
+            assert_eq!(parser.function_signature(expected).to_string(), display);
+        };
+
+        check_orders(
+            &[
+                "() const noexcept [[nodiscard]] -> int requires C<T>",
+                "() [[nodiscard]] const -> int noexcept requires C<T>",
+                "() requires C<T> noexcept const -> int",
+            ],
+            "() const noexcept -> int requires C<T>",
+        );
+        check_orders(
+            &[
+                "(int) && requires A<T> && B<T>",
+                "(int) requires A<T> && B<T> &&",
+            ],
+            "(int) && requires A<T> && B<T>",
+        );
+        check_orders(
+            &[
+                "() [[gnu::always_inline]] volatile",
+                "() volatile [[gnu::always_inline]]",
+            ],
+            "() volatile",
+        );
+
+        let signature =
+            unwrap_parse(parser.parse_function_signature("() const -> void requires C<T>"));
+        let view = parser.function_signature(signature);
+        assert_eq!(view.cv(), ConstVolatile::CONST);
+        assert_eq!(
+            view.requires().map(|r| r.to_string()).as_deref(),
+            Some("C<T>")
+        );
+        assert_eq!(
+            view.trailing_return().map(|t| t.to_string()).as_deref(),
+            Some("void")
+        );
+
+        // Duplicate specifiers are rejected
+        for input in [
+            "() & &&",
+            "() noexcept noexcept",
+            "() noexcept(false) noexcept",
+            "() noexcept noexcept(false)",
+            "() noexcept(false) noexcept(false)",
+            "() noexcept(true) noexcept(B)",
+            "() -> int -> int",
+            "() requires A requires B",
+        ] {
+            assert!(parser.parse_function_signature(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn function_signature_accessors() {
+        let mut parser = EntityParser::new();
+        let signature = unwrap_parse(parser.parse_function_signature(
+            "[abi:cxx11](int, char, ...) const volatile && noexcept(B) -> void",
+        ));
+        let view = parser.function_signature(signature);
+        assert_eq!(
+            view.abi().map(|abi| abi.to_string()).as_deref(),
+            Some("cxx11")
+        );
+        let parameters = view.parameters();
+        assert_eq!(parameters.len(), 2);
+        assert_eq!(
+            parameters.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+            ["int", "char"]
+        );
+        assert!(view.variadic());
+        assert_eq!(view.cv(), ConstVolatile::CONST | ConstVolatile::VOLATILE);
+        assert_eq!(view.reference(), Reference::RValue);
+        assert_eq!(
+            view.noexcept().map(|o| o.map(|v| v.to_string())),
+            Some(Some("B".to_owned()))
+        );
+        assert_eq!(
+            view.trailing_return().map(|t| t.to_string()).as_deref(),
+            Some("void")
+        );
+        assert_eq!(
+            view.to_string(),
+            "[abi:cxx11](int, char, ...) const volatile && noexcept(B) -> void"
+        );
+    }
+
+    #[test]
+    fn max_list_cols() {
+        let mut parser = EntityParser::new();
+        let signature = unwrap_parse(
+            parser.parse_function_signature("(const std::string&, int, double) const"),
+        );
+        let display = |max_cols| {
+            parser
+                .function_signature(signature)
+                .display(&DisplayState::default().with_max_list_cols(max_cols))
+                .to_string()
+        };
+
+        // Parameter lists are fit within the column budget
+        assert_eq!(display(100), "(const std::string&, int, double) const");
+        assert_eq!(display(30), "(const std::string&, int, doub…) const");
+        assert_eq!(display(28), "(const std::string&, …(+2)) const");
+        assert_eq!(display(12), "(cons…, …(+2)) const");
+        assert_eq!(display(3), "(…) const");
     }
 
     #[test]
@@ -74,7 +74,9 @@ impl EntityParser {
         &self,
         input: &'input str,
     ) -> IResult<'input, IdentifierKey> {
-        let (rest, id) = identifier(input)?;
+        let (rest, id) = identifier
+            .verify(|id| !RESERVED_KEYWORDS.contains(id))
+            .parse(input)?;
         let id_key = self.identifiers.borrow_mut().get_or_intern(id);
         Ok((rest, id_key))
     }
@@ -89,6 +91,13 @@ impl EntityParser {
         ARef::new(self.identifiers.borrow()).map(|identifiers| identifiers.resolve(&key))
     }
 
+    /// Copy a previously parsed identifier into another parser
+    pub(crate) fn copy_identifier(&self, key: IdentifierKey, dst: &Self) -> IdentifierKey {
+        dst.identifiers
+            .borrow_mut()
+            .get_or_intern(&*self.raw_identifier(key))
+    }
+
     /// Tell how many unique identifiers have been parsed so far
     pub fn num_identifiers(&self) -> usize {
         self.identifiers.borrow().len()
@@ -113,6 +122,15 @@ impl<const LEN: usize> KeywordMappings<(), LEN> for [&'static str; LEN] {
     }
 }
 
+/// Keywords that look like identifiers, but must not be parsed as such
+///
+/// Most keywords are already kept away from identifier parsing by the order in
+/// which grammar alternatives are tried, but the coroutine keywords can appear
+/// in names generated by the coroutine transform where an identifier is
+/// otherwise expected.
+///
+const RESERVED_KEYWORDS: [&str; 2] = ["co_yield", "co_return"];
+
 /// Parser recognizing any valid C++ identifier
 fn identifier(s: &str) -> IResult<&str> {
     #[cfg(feature = "unicode-xid")]
@@ -143,7 +161,7 @@ fn identifier(s: &str) -> IResult<&str> {
 
 /// Parser recognizing the end of an identifier, without consuming it
 #[inline]
-fn end_of_identifier(s: &str) -> IResult<()> {
+pub(crate) fn end_of_identifier(s: &str) -> IResult<()> {
     #[cfg(feature = "unicode-xid")]
     {
         use nom::{
@@ -217,6 +235,11 @@ impl<'entities> IdentifierView<'entities> {
             entities,
         }
     }
+
+    /// Copy this identifier into another parser, returning its key there
+    pub(crate) fn copy_into(&self, dst: &EntityParser) -> IdentifierKey {
+        self.entities.copy_identifier(self.key, dst)
+    }
 }
 //
 impl<'entities> AsRef<str> for IdentifierView<'entities> {
@@ -290,5 +313,11 @@ mod tests {
         assert_eq!(entities.parse_identifier(&id_str), Ok(("*", key)));
         assert_eq!(entities.num_identifiers(), 1);
         assert_eq!(&*entities.raw_identifier(key), ID);
+
+        // Coroutine keywords are not identifiers, but may start one
+        assert!(entities.parse_identifier("co_yield").is_err());
+        assert!(entities.parse_identifier("co_return;").is_err());
+        assert!(entities.parse_identifier("co_yield_value").is_ok());
+        assert_eq!(entities.num_identifiers(), 2);
     }
 }
@@ -1,13 +1,17 @@
 //! Handling of hierarchical scopes
 
-use super::unqualified::{UnqualifiedId, UnqualifiedIdView};
+use super::{
+    atoms::IdentifierView,
+    unqualified::{UnqualifiedId, UnqualifiedIdView},
+};
 use crate::{
-    display::{CustomDisplay, DisplayState},
+    display::{AnonymousNamespacePolicy, CustomDisplay, DisplayState},
     interning::{
         recursion::SequenceEntry,
         slice::{SliceItemView, SliceView},
     },
     subparsers::{
+        anonymous::AnonymousEntityView,
         functions::{FunctionSignature, FunctionSignatureView},
         types::qualifiers::{ConstVolatile, Reference},
     },
@@ -84,11 +88,40 @@ impl EntityParser {
         }
     }
 
+    /// Copy a previously parsed id-expression into another parser
+    pub(crate) fn copy_id_expression(&self, id: IdExpression, dst: &Self) -> IdExpression {
+        IdExpression {
+            path: self.copy_nested_name_specifier(id.path, dst),
+            id: self.copy_unqualified_id(id.id, dst),
+        }
+    }
+
     /// Access a previously parsed nested name specifier
     pub fn nested_name_specifier(&self, nns: NestedNameSpecifier) -> NestedNameSpecifierView {
         NestedNameSpecifierView::new(nns, self)
     }
 
+    /// Copy a previously parsed nested name specifier into another parser
+    pub(crate) fn copy_nested_name_specifier(
+        &self,
+        nns: NestedNameSpecifier,
+        dst: &Self,
+    ) -> NestedNameSpecifier {
+        let mut entry = dst.scope_sequences.entry();
+        for scope in self.scope_sequences.get(nns.scopes).iter() {
+            entry.push(Scope {
+                id: self.copy_unqualified_id(scope.id, dst),
+                function_signature: scope
+                    .function_signature
+                    .map(|signature| self.copy_function_signature(signature, dst)),
+            });
+        }
+        NestedNameSpecifier {
+            rooted: nns.rooted,
+            scopes: entry.intern(),
+        }
+    }
+
     /// Parser for a nested name-specifier, optionally followed by an UnqualifiedId,
     /// with an option to backtrack on that last UnqualifiedId if you don't want it.
     ///
@@ -120,10 +153,20 @@ impl EntityParser {
             )
         };
         //
-        while let Ok((rest, scope_or_id)) = self.parse_scope_or_unqualified_id_imut(input) {
+        let mut after_scope = false;
+        while let Ok((rest, scope_or_id)) =
+            self.parse_scope_or_unqualified_id_imut(if after_scope {
+                Self::skip_template_disambiguator(input)
+            } else {
+                input
+            })
+        {
             match scope_or_id {
                 // As long as there are scopes, keep going
-                ScopeOrUnqualifiedId::Scope(scope) => scopes.push(scope),
+                ScopeOrUnqualifiedId::Scope(scope) => {
+                    scopes.push(scope);
+                    after_scope = true;
+                }
 
                 // If a trailing UnqualifiedId is found, we reached the end of the
                 // grammar, return it + input string to allow backtracking
@@ -141,6 +184,21 @@ impl EntityParser {
         Ok((input, make_output(scopes, None)))
     }
 
+    /// Skip the template disambiguator keyword that may precede a dependent
+    /// member template name (as in `Outer::template Inner<T>`), if present
+    ///
+    /// This keyword only tells the compiler how to parse the name that follows,
+    /// and is thus not retained in the parsed output.
+    ///
+    #[inline]
+    pub(crate) fn skip_template_disambiguator(s: &str) -> &str {
+        use nom::character::complete::multispace1;
+        Self::keyword_parser("template")
+            .and(multispace1)
+            .parse(s)
+            .map_or(s, |(rest, _)| rest)
+    }
+
     /// Access a previously parsed sequence of scopes
     pub(crate) fn scope_sequence(&self, key: ScopesKey) -> ScopesView {
         ScopesView::new(key, self.scope_sequences.borrow(), self)
@@ -258,6 +316,23 @@ impl<'entities> IdExpressionView<'entities> {
     pub fn id(&self) -> UnqualifiedIdView {
         self.entities.unqualified_id(self.inner.id)
     }
+
+    /// Flat list of name segments, from the outermost scope to the inner
+    /// unqualified id-expression
+    ///
+    /// For example, `std::chrono::duration<long>` has segments `std`, `chrono`
+    /// and `duration<long>`. Template parameters and anonymous entities are
+    /// kept as part of their segment, but the function signatures of function
+    /// scopes are not reported.
+    pub fn segments(&self) -> Box<[UnqualifiedIdView<'entities>]> {
+        let scopes = self.entities.scope_sequence(self.inner.path.scopes);
+        scopes
+            .iter()
+            .map(|scope| scope.inner.id)
+            .chain(std::iter::once(self.inner.id))
+            .map(|id| self.entities.unqualified_id(id))
+            .collect()
+    }
 }
 //
 impl<'entities> PartialEq for IdExpressionView<'entities> {
@@ -349,12 +424,26 @@ impl<'entities> CustomDisplay for NestedNameSpecifierView<'entities> {
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
         if state.can_recurse() {
+            let scopes = self.scopes();
             if self.is_rooted() {
                 write!(f, "::")?;
+            } else if let Some(first) = scopes.iter().next() {
+                // Strip the leading namespace if the display policy says so
+                if first
+                    .namespace_name()
+                    .is_some_and(|name| state.strips_namespace(&name))
+                {
+                    for scope in scopes.iter().skip(1) {
+                        scope.display_impl(f, state)?;
+                    }
+                    return Ok(());
+                }
             }
-            self.scopes().display_impl(f, state)?;
+            scopes.display_impl(f, state)?;
         } else if !self.scopes().is_empty() {
             write!(f, "…::")?;
+        } else if self.is_rooted() {
+            write!(f, "::")?;
         }
         Ok(())
     }
@@ -418,6 +507,32 @@ impl<'entities> ScopeView<'entities> {
             .function_signature
             .map(|s| self.entities.function_signature(s))
     }
+
+    /// Name of this scope if it could be a namespace, i.e. if it is a plain
+    /// identifier without template parameters or function signature
+    pub fn namespace_name(&self) -> Option<IdentifierView> {
+        if self.inner.function_signature.is_some() {
+            return None;
+        }
+        match self.id() {
+            UnqualifiedIdView::Named {
+                is_destructor: false,
+                id,
+                template_parameters: None,
+            } => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Truth that this scope is an anonymous namespace
+    pub fn is_anonymous_namespace(&self) -> bool {
+        self.inner.function_signature.is_none()
+            && matches!(
+                self.id(),
+                UnqualifiedIdView::Anonymous(AnonymousEntityView::ClangOther(anonymous))
+                    if anonymous.is_namespace()
+            )
+    }
 }
 //
 impl<'entities> PartialEq for ScopeView<'entities> {
@@ -438,6 +553,13 @@ impl<'entities> CustomDisplay for ScopeView<'entities> {
     }
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        if self.is_anonymous_namespace() {
+            match state.anonymous_namespaces() {
+                AnonymousNamespacePolicy::Full => {}
+                AnonymousNamespacePolicy::Compact => return write!(f, "(anon)::"),
+                AnonymousNamespacePolicy::Hide => return Ok(()),
+            }
+        }
         self.id().display_impl(f, state)?;
         self.function_signature().display_impl(f, state)?;
         write!(f, "::")
@@ -461,7 +583,11 @@ impl<'entities> SliceItemView<'entities> for ScopeView<'entities> {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::{display::tests::check_custom_display, tests::unwrap_parse};
+    use crate::{
+        display::tests::check_custom_display, subparsers::anonymous::AnonymousEntity,
+        tests::unwrap_parse,
+    };
+    use assert_matches::assert_matches;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -759,5 +885,213 @@ pub mod tests {
             expected,
             &["…::to_t<…>", "boost::hana::to_t<unsigned long long>"],
         );
+
+        // Rooted at global scope
+        expected = IdExpression {
+            path: unwrap_parse(parser.parse_nested_name_specifier("::std::")),
+            id: unqualified_id(&mut parser, "string"),
+        };
+        check_id_expression(
+            &mut parser,
+            "::std::string",
+            expected,
+            &["…::string", "::std::string"],
+        );
+        expected = IdExpression {
+            path: unwrap_parse(parser.parse_nested_name_specifier("::")),
+            id: unqualified_id(&mut parser, "global_fn"),
+        };
+        check_id_expression(&mut parser, "::global_fn", expected, &["::global_fn"]);
+        assert!(parser.id_expression(expected).path().is_rooted());
+
+        // A lone global scope qualifier is not an id-expression
+        assert!(parser.parse_id_expression("::").is_err());
+        assert!(parser.parse_id_expression(":: ").is_err());
+
+        // Template disambiguators are accepted after a scope, but not kept
+        expected = IdExpression {
+            path: unwrap_parse(parser.parse_nested_name_specifier("Outer::")),
+            id: unqualified_id(&mut parser, "Inner<T>"),
+        };
+        check_id_expression(
+            &mut parser,
+            "Outer::Inner<T>",
+            expected,
+            &["…::Inner<…>", "Outer::Inner<T>"],
+        );
+        check_id_expression(
+            &mut parser,
+            "Outer::template Inner<T>",
+            expected,
+            &["…::Inner<…>", "Outer::Inner<T>"],
+        );
+        expected = IdExpression {
+            path: unwrap_parse(parser.parse_nested_name_specifier("A<T>::B<U>::")),
+            id: unqualified_id(&mut parser, "type"),
+        };
+        check_id_expression(
+            &mut parser,
+            "A<T>::template B<U>::type",
+            expected,
+            &["…::type", "A<…>::B<…>::type", "A<T>::B<U>::type"],
+        );
+
+        // Clang and LLVM names for local entities can appear in the path
+        expected = IdExpression {
+            path: unwrap_parse(parser.parse_nested_name_specifier("(anonymous namespace)::")),
+            id: unqualified_id(&mut parser, "$_3"),
+        };
+        assert_matches!(
+            expected.id,
+            UnqualifiedId::Anonymous(AnonymousEntity::ClangNumbered(_))
+        );
+        check_id_expression(
+            &mut parser,
+            "(anonymous namespace)::$_3",
+            expected,
+            &["…::$_3", "(anonymous namespace)::$_3"],
+        );
+        expected = IdExpression {
+            path: unwrap_parse(parser.parse_nested_name_specifier("f()::'lambda'()::")),
+            id: unqualified_id(&mut parser, "operator()"),
+        };
+        check_id_expression(
+            &mut parser,
+            "f()::'lambda'()::operator()",
+            expected,
+            &["…::operator()", "f()::'lambda'()::operator()"],
+        );
+
+        // Coroutine keywords are not mistaken for promise type members...
+        for input in [
+            "std::coroutine_traits<task>::promise_type::co_yield",
+            "task<int>::promise_type::co_return",
+            "co_yield::promise_type",
+        ] {
+            assert!(
+                !matches!(parser.parse_id_expression(input), Ok(("", _))),
+                "{input}"
+            );
+            assert!(parser.parse_entity(input).is_err(), "{input}");
+        }
+
+        // ...but identifiers that merely start with them are fine
+        let (rest, key) = parser
+            .parse_id_expression("task<int>::promise_type::co_yield_value")
+            .unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            parser.id_expression(key).to_string(),
+            "task<int>::promise_type::co_yield_value"
+        );
+    }
+
+    #[test]
+    fn qualification_policy() {
+        use crate::display::QualificationPolicy;
+        let mut parser = EntityParser::new();
+        let strip_std = QualificationPolicy::StripLeading(vec!["std".into()].into());
+        let mut check_display = |input, full: &str, stripped: &str| {
+            let key = unwrap_parse(parser.parse_id_expression(input));
+            let id = parser.id_expression(key);
+            assert_eq!(id.to_string(), full);
+            let state = DisplayState::default().with_qualification(strip_std.clone());
+            assert_eq!(id.display(&state).to_string(), stripped);
+            assert_eq!(id.bounded_display_with(u16::MAX, &strip_std), stripped);
+        };
+
+        check_display(
+            "std::vector<std::string>",
+            "std::vector<std::string>",
+            "vector<string>",
+        );
+        check_display(
+            "std::chrono::duration<long>",
+            "std::chrono::duration<long>",
+            "chrono::duration<long>",
+        );
+
+        // Only leading namespaces of non-rooted names are stripped
+        check_display("::std::string", "::std::string", "::std::string");
+        check_display(
+            "boost::std::thing",
+            "boost::std::thing",
+            "boost::std::thing",
+        );
+        check_display("std<int>::thing", "std<int>::thing", "std<int>::thing");
+        check_display("stdx::thing", "stdx::thing", "stdx::thing");
+    }
+
+    #[test]
+    fn anonymous_namespace_policy() {
+        let mut parser = EntityParser::new();
+        let mut check_display = |input, full: &str, compact: &str, hidden: &str| {
+            let key = unwrap_parse(parser.parse_id_expression(input));
+            let id = parser.id_expression(key);
+            let display = |policy| {
+                let state = DisplayState::default().with_anonymous_namespaces(policy);
+                id.display(&state).to_string()
+            };
+            assert_eq!(id.to_string(), full);
+            assert_eq!(display(AnonymousNamespacePolicy::Full), full);
+            assert_eq!(display(AnonymousNamespacePolicy::Compact), compact);
+            assert_eq!(display(AnonymousNamespacePolicy::Hide), hidden);
+        };
+
+        check_display(
+            "(anonymous namespace)::foo",
+            "(anonymous namespace)::foo",
+            "(anon)::foo",
+            "foo",
+        );
+        check_display(
+            "ns::(anonymous namespace)::Foo<(anonymous namespace)::Bar>::baz",
+            "ns::(anonymous namespace)::Foo<(anonymous namespace)::Bar>::baz",
+            "ns::(anon)::Foo<(anon)::Bar>::baz",
+            "ns::Foo<Bar>::baz",
+        );
+        check_display(
+            "`anonymous namespace'::foo",
+            "(anonymous namespace)::foo",
+            "(anon)::foo",
+            "foo",
+        );
+
+        // Other anonymous scopes are left alone
+        check_display(
+            "(anonymous struct)::x",
+            "(anonymous struct)::x",
+            "(anonymous struct)::x",
+            "(anonymous struct)::x",
+        );
+    }
+
+    #[test]
+    fn id_expression_segments() {
+        let mut parser = EntityParser::new();
+        let mut check_segments = |input, expected: &[&str]| {
+            let id = unwrap_parse(parser.parse_id_expression(input));
+            let segments = parser
+                .id_expression(id)
+                .segments()
+                .iter()
+                .map(|segment| segment.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(segments, expected);
+        };
+        check_segments("something", &["something"]);
+        check_segments("::std::vector", &["std", "vector"]);
+        check_segments(
+            "std::chrono::duration<long, std::ratio<1,1000>>",
+            &["std", "chrono", "duration<long, std::ratio<1, 1000>>"],
+        );
+        check_segments(
+            "(anonymous namespace)::Foo<int>::bar",
+            &["(anonymous namespace)", "Foo<int>", "bar"],
+        );
+        check_segments(
+            "func(int)::{lambda()#1}::operator()",
+            &["func", "{lambda()#1}", "operator()"],
+        );
     }
 }
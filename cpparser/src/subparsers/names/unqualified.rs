@@ -82,7 +82,8 @@ impl EntityParser {
         // destructor or not. Branches other than _ are ordered by decreasing freq.
         //
         match s.as_bytes().first() {
-            Some(b'{') | Some(b'(') => anonymous.parse(s),
+            Some(b'{') | Some(b'(') | Some(b'`') | Some(b'<') | Some(b'\'') => anonymous.parse(s),
+            Some(b'$') => anonymous.or(named(false)).parse(s),
             Some(b'd') => decltype.or(named(false)).parse(s),
             Some(b'o') => operator.or(named(false)).parse(s),
             Some(b'~') => named(true).parse(&s[1..]),
@@ -94,6 +95,36 @@ impl EntityParser {
     pub fn unqualified_id(&self, id: UnqualifiedId) -> UnqualifiedIdView {
         UnqualifiedIdView::new(id, self)
     }
+
+    /// Copy a previously parsed unqualified id into another parser
+    pub(crate) fn copy_unqualified_id(&self, id: UnqualifiedId, dst: &Self) -> UnqualifiedId {
+        match id {
+            UnqualifiedId::Named {
+                is_destructor,
+                id,
+                template_parameters,
+            } => UnqualifiedId::Named {
+                is_destructor,
+                id: self.copy_identifier(id, dst),
+                template_parameters: template_parameters
+                    .map(|params| self.copy_template_parameters(params, dst)),
+            },
+            UnqualifiedId::Operator {
+                operator,
+                template_parameters,
+            } => UnqualifiedId::Operator {
+                operator: self.copy_operator(operator, dst),
+                template_parameters: template_parameters
+                    .map(|params| self.copy_template_parameters(params, dst)),
+            },
+            UnqualifiedId::Decltype(value) => {
+                UnqualifiedId::Decltype(self.copy_value_like(value, dst))
+            }
+            UnqualifiedId::Anonymous(anonymous) => {
+                UnqualifiedId::Anonymous(self.copy_anonymous(anonymous, dst))
+            }
+        }
+    }
 }
 
 /// Unqualified id-expression
@@ -20,6 +20,15 @@ impl EntityParser {
     pub fn operator(&self, op: Operator) -> OperatorView {
         OperatorView::new(op, self)
     }
+
+    /// Copy a previously parsed operator into another parser
+    pub(crate) fn copy_operator(&self, op: Operator, dst: &Self) -> Operator {
+        match op {
+            Operator::CustomLiteral(id) => Operator::CustomLiteral(self.copy_identifier(id, dst)),
+            Operator::Conversion(ty) => Operator::Conversion(self.copy_type_like(ty, dst)),
+            other => other,
+        }
+    }
 }
 
 /// C++ operators that can be overloaded
@@ -72,6 +81,60 @@ pub enum Operator {
     Conversion(TypeKey),
 }
 //
+impl Operator {
+    /// Broad category that this operator belongs to
+    ///
+    /// Symbols that are shared by several operators are classified according
+    /// to their binary usage, e.g. `*` is arithmetic and `&` is bitwise.
+    ///
+    pub fn category(&self) -> OperatorCategory {
+        use OperatorCategory::*;
+        match *self {
+            Self::Basic {
+                symbol,
+                twice,
+                equal,
+            } => match (symbol, twice, equal) {
+                // Plain assignment and equality comparison
+                (Symbol::AssignEq, false, false) => Assignment,
+                (Symbol::AssignEq, _, _) => Comparison,
+
+                // Comparisons, shifts and shift-assignments
+                (Symbol::Less | Symbol::Greater, false, _) => Comparison,
+                (Symbol::Less | Symbol::Greater, true, false) => Bitwise,
+
+                // Inequality and logical negation
+                (Symbol::Not, _, true) => Comparison,
+                (Symbol::Not, _, false) => Logical,
+
+                // Sequencing
+                (Symbol::Comma, _, _) => Special,
+
+                // Compound assignment
+                (_, _, true) => Assignment,
+
+                // Short-circuiting logical operators
+                (Symbol::AndRef | Symbol::Or, true, false) => Logical,
+
+                // Everything else
+                (
+                    Symbol::AddPlus | Symbol::SubNeg | Symbol::MulDeref | Symbol::Div | Symbol::Mod,
+                    _,
+                    false,
+                ) => Arithmetic,
+                (Symbol::Xor | Symbol::AndRef | Symbol::Or | Symbol::BitNot, _, false) => Bitwise,
+            },
+            Self::Spaceship => Comparison,
+            Self::Deref { .. } | Self::CallIndex { is_index: true } => Access,
+            Self::CallIndex { is_index: false }
+            | Self::CustomLiteral(_)
+            | Self::NewDelete { .. }
+            | Self::CoAwait
+            | Self::Conversion(_) => Special,
+        }
+    }
+}
+//
 impl From<Symbol> for Operator {
     fn from(symbol: Symbol) -> Self {
         Self::Basic {
@@ -88,6 +151,31 @@ impl From<TypeKey> for Operator {
     }
 }
 
+/// Broad categories of C++ operators
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OperatorCategory {
+    /// Arithmetic operators (`+`, `*`, `++`...)
+    Arithmetic,
+
+    /// Comparison operators (`==`, `<`, `<=>`...)
+    Comparison,
+
+    /// Logical operators (`!`, `&&`, `||`)
+    Logical,
+
+    /// Bitwise operators (`&`, `~`, `<<`...)
+    Bitwise,
+
+    /// Assignment operators (`=`, `+=`, `>>=`...)
+    Assignment,
+
+    /// Member and element access operators (`->`, `->*`, `[]`)
+    Access,
+
+    /// Other operators (`()`, `,`, `new`, conversions...)
+    Special,
+}
+
 /// View of a C++ operator
 #[derive(PartialEq)]
 pub enum OperatorView<'entities> {
@@ -468,6 +556,7 @@ impl Display for Symbol {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::unwrap_parse;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -617,4 +706,77 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn category() {
+        let mut parser = EntityParser::new();
+        let mut check_category = |s, expected| {
+            let (rest, (operator, _)) = parser.parse_operator_overload(s).unwrap();
+            assert_eq!(rest, "");
+            assert_eq!(operator.category(), expected, "category of {s}");
+        };
+        check_category("operator+", OperatorCategory::Arithmetic);
+        check_category("operator++", OperatorCategory::Arithmetic);
+        check_category("operator<=>", OperatorCategory::Comparison);
+        check_category("operator==", OperatorCategory::Comparison);
+        check_category("operator!=", OperatorCategory::Comparison);
+        check_category("operator>=", OperatorCategory::Comparison);
+        check_category("operator&&", OperatorCategory::Logical);
+        check_category("operator!", OperatorCategory::Logical);
+        check_category("operator&", OperatorCategory::Bitwise);
+        check_category("operator<<", OperatorCategory::Bitwise);
+        check_category("operator|=", OperatorCategory::Assignment);
+        check_category("operator=", OperatorCategory::Assignment);
+        check_category("operator>>=", OperatorCategory::Assignment);
+        check_category("operator->", OperatorCategory::Access);
+        check_category("operator[]", OperatorCategory::Access);
+        check_category("operator()", OperatorCategory::Special);
+        check_category("operator,", OperatorCategory::Special);
+        check_category("operator new[]", OperatorCategory::Special);
+    }
+
+    #[test]
+    fn compound_assignment_round_trip() {
+        let mut parser = EntityParser::new();
+        for (op, symbol, twice) in [
+            ("+=", Symbol::AddPlus, false),
+            ("-=", Symbol::SubNeg, false),
+            ("*=", Symbol::MulDeref, false),
+            ("/=", Symbol::Div, false),
+            ("%=", Symbol::Mod, false),
+            ("^=", Symbol::Xor, false),
+            ("&=", Symbol::AndRef, false),
+            ("|=", Symbol::Or, false),
+            ("<<=", Symbol::Less, true),
+            (">>=", Symbol::Greater, true),
+        ] {
+            // The operator model captures all compound assignments...
+            let expected = Operator::Basic {
+                symbol,
+                twice,
+                equal: true,
+            };
+            assert_eq!(expected.category(), OperatorCategory::Assignment);
+
+            // ...which survive a round trip through declaration syntax...
+            for declaration in [format!("operator{op}"), format!("operator{op}<int>")] {
+                let (rest, (operator, _)) = parser.parse_operator_overload(&declaration).unwrap();
+                assert_eq!((rest, operator), ("", expected));
+                let id = unwrap_parse(parser.parse_unqualified_id(&declaration));
+                let display = parser.unqualified_id(id).to_string();
+                assert_eq!(display, declaration);
+                assert_eq!(parser.parse_unqualified_id(&display), Ok(("", id)));
+            }
+
+            // ...and through binary usage syntax
+            let usage = format!("a {op} b");
+            let value = unwrap_parse(parser.parse_value_like(&usage, true, true));
+            let display = parser.value_like(value).to_string();
+            assert_eq!(display, usage);
+            assert_eq!(
+                parser.parse_value_like(&display, true, true),
+                Ok(("", value))
+            );
+        }
+    }
 }
@@ -43,7 +43,7 @@ impl EntityParser {
             new.or(super::delete)
                 .or(super::co_await)
                 // Must come last as it matches keywords
-                .or((|s| self.parse_type_like_imut(s)).map(Operator::Conversion)),
+                .or((|s| self.parse_conversion_type_imut(s)).map(Operator::Conversion)),
         ))
         .and(preceded(
             multispace0,
@@ -198,6 +198,19 @@ mod tests {
             parser.parse_operator_overload("operator[]"),
             Ok(("", (Operator::CallIndex { is_index: true }, None)))
         );
+        // The call operator's name is not confused with the following signature
+        assert_eq!(
+            parser.parse_operator_overload("operator()()"),
+            Ok(("()", (Operator::CallIndex { is_index: false }, None)))
+        );
+        assert_eq!(
+            parser.parse_operator_overload("operator()(int)"),
+            Ok(("(int)", (Operator::CallIndex { is_index: false }, None)))
+        );
+        assert_eq!(
+            parser.parse_operator_overload("operator[](size_t)"),
+            Ok(("(size_t)", (Operator::CallIndex { is_index: true }, None)))
+        );
         assert_eq!(
             parser.parse_operator_overload("operator\"\" _stuff"),
             Ok((
@@ -223,6 +236,44 @@ mod tests {
                 )
             ))
         );
+        // The `[]` of `delete[]` is not confused with an array declarator, and
+        // sized/aligned deallocation signatures are left alone
+        for (input, array, signature) in [
+            (
+                "operator delete(void*, unsigned long)",
+                false,
+                "(void*, unsigned long)",
+            ),
+            (
+                "operator delete[](void*, std::size_t)",
+                true,
+                "(void*, std::size_t)",
+            ),
+            (
+                "operator delete(void*, std::align_val_t)",
+                false,
+                "(void*, std::align_val_t)",
+            ),
+            (
+                "operator delete[](void*, unsigned long, std::align_val_t)",
+                true,
+                "(void*, unsigned long, std::align_val_t)",
+            ),
+        ] {
+            assert_eq!(
+                parser.parse_operator_overload(input),
+                Ok((
+                    signature,
+                    (
+                        Operator::NewDelete {
+                            is_delete: true,
+                            array
+                        },
+                        None
+                    )
+                ))
+            );
+        }
         assert_eq!(
             parser.parse_operator_overload("operator\tco_await"),
             Ok(("", (Operator::CoAwait, None)))
@@ -238,6 +289,64 @@ mod tests {
             ))
         );
 
+        // ...and leaves trailing function signatures alone
+        for (input, target, signature) in [
+            ("operator bool() const", "bool", "() const"),
+            ("operator int() &&", "int", "() &&"),
+            (
+                "operator const char*() noexcept",
+                "const char*",
+                "() noexcept",
+            ),
+        ] {
+            assert_eq!(
+                parser.parse_operator_overload(input),
+                Ok((signature, (type_like(&mut parser, target).into(), None)))
+            );
+        }
+
+        // Conversions to template-ids keep their own template arguments...
+        for (input, target, signature) in [
+            ("operator Foo<int>()", "Foo<int>", "()"),
+            (
+                "operator std::optional<T>() const",
+                "std::optional<T>",
+                "() const",
+            ),
+            (
+                "operator std::map<K, std::vector<V>>&() &",
+                "std::map<K, std::vector<V>>&",
+                "() &",
+            ),
+        ] {
+            assert_eq!(
+                parser.parse_operator_overload(input),
+                Ok((signature, (type_like(&mut parser, target).into(), None)))
+            );
+        }
+
+        // ...which are not confused with the operator's own template arguments
+        assert_eq!(
+            parser.parse_operator_overload("operator Foo<int><char>()"),
+            Ok((
+                "()",
+                (
+                    type_like(&mut parser, "Foo<int>").into(),
+                    Some(unwrap_parse(parser.parse_template_parameters("<char>")))
+                )
+            ))
+        );
+
+        // ...including within complete entities
+        for input in [
+            "Wrapper<T>::operator Foo<int>()",
+            "Wrapper<T>::operator std::optional<T>() const",
+            "Wrapper<T>::operator Foo<int><char>() &&",
+        ] {
+            let entity = parser.parse_entity(input).unwrap();
+            assert_eq!(parser.entity(entity).to_string(), input);
+        }
+
         // Ambiguities between template and operator syntax are handled well
         let template_parameters =
             |parser: &mut EntityParser, s| unwrap_parse(parser.parse_template_parameters(s));
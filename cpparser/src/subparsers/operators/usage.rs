@@ -167,6 +167,20 @@ impl EntityParser {
     pub fn new_expression(&self, ne: NewExpression) -> NewExpressionView {
         NewExpressionView::new(ne, self)
     }
+
+    /// Copy a previously parsed new-expression into another parser
+    pub(crate) fn copy_new_expression(&self, ne: NewExpression, dst: &Self) -> NewExpression {
+        NewExpression {
+            rooted: ne.rooted,
+            placement: ne
+                .placement
+                .map(|args| self.copy_function_arguments(args, dst)),
+            ty: self.copy_type_like(ne.ty, dst),
+            constructor: ne
+                .constructor
+                .map(|args| self.copy_function_arguments(args, dst)),
+        }
+    }
 }
 
 /// New-expression, i.e. usage of the new operator
@@ -81,6 +81,31 @@ impl EntityParser {
         TemplateParametersView::new(tp, self)
     }
 
+    /// Copy a previously interned template parameter set into another parser
+    pub(crate) fn copy_template_parameters(
+        &self,
+        tp: TemplateParameters,
+        dst: &Self,
+    ) -> TemplateParameters {
+        let key = tp?;
+        let mut entry = dst.template_parameter_lists.entry();
+        for &param in self.template_parameter_lists.get(key).iter() {
+            entry.push(match param {
+                TemplateParameter::TypeLike(ty) => {
+                    TemplateParameter::TypeLike(self.copy_type_like(ty, dst))
+                }
+                TemplateParameter::ValueLike(value) => {
+                    TemplateParameter::ValueLike(self.copy_value_like(value, dst))
+                }
+                TemplateParameter::Defaulted(ty, value) => TemplateParameter::Defaulted(
+                    self.copy_type_like(ty, dst),
+                    self.copy_value_like(value, dst),
+                ),
+            });
+        }
+        Some(entry.intern())
+    }
+
     /// Retrieve a previously interned template parameter set
     #[cfg(test)]
     pub(crate) fn raw_template_parameters(
@@ -113,21 +138,39 @@ impl EntityParser {
     /// Must look ahead to the next template parameter separator (, or `>`) in
     /// order to resolve the type vs value ambiguity properly.
     ///
+    /// A lone template-id like `is_integral_v<T>` could either be a class
+    /// template specialization or a variable template specialization, and is
+    /// parsed as a type. Any expression built on top of it, such as
+    /// `is_integral_v<T> && B`, will be parsed as a value.
+    ///
+    /// A `>` that is directly followed by `=` is a comparison operator, as in
+    /// `tuple_size<T>::value >= 2`, rather than the end of the parameter list.
+    ///
     fn parse_template_parameter_imut<'source>(
         &self,
         s: &'source str,
     ) -> IResult<'source, TemplateParameter> {
         use nom::{
             character::complete::{char, multispace0},
-            combinator::peek,
+            combinator::{not, peek},
+            sequence::separated_pair,
         };
+        let end_of_parameter =
+            || multispace0.and(peek(char(',').or(char('>').terminated(not(char('='))))));
         let type_like = (|s| self.parse_type_like_imut(s))
             .map(TemplateParameter::TypeLike)
-            .terminated(multispace0.and(peek(char(',').or(char('>')))));
+            .terminated(end_of_parameter());
         let value_like = (|s| self.parse_value_like_imut(s, false, false))
             .map(TemplateParameter::ValueLike)
-            .terminated(multispace0.and(peek(char(',').or(char('>')))));
-        type_like.or(value_like).parse(s)
+            .terminated(end_of_parameter());
+        let defaulted = separated_pair(
+            |s| self.parse_type_like_imut(s),
+            multispace0.and(char('=')).and(multispace0),
+            |s| self.parse_value_like_imut(s, false, false),
+        )
+        .map(|(ty, default)| TemplateParameter::Defaulted(ty, default))
+        .terminated(end_of_parameter());
+        type_like.or(value_like).or(defaulted).parse(s)
     }
 
     /// Retrieve a previously interned template parameter
@@ -176,7 +219,12 @@ impl<'entities> CustomDisplay for TemplateParametersView<'entities> {
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
         if let Some(list) = &self.0 {
-            list.display_impl(f, state)
+            list.display_impl_with_limits(
+                f,
+                state,
+                state.max_template_args(),
+                state.max_list_cols(),
+            )
         } else if let Ok(_guard) = state.recurse() {
             write!(f, "<, void>")
         } else {
@@ -201,6 +249,10 @@ pub enum TemplateParameter {
 
     /// Value
     ValueLike(ValueKey),
+
+    /// Type followed by a default value, as in the unnamed non-type template
+    /// parameter `std::enable_if_t<C>* = nullptr`
+    Defaulted(TypeKey, ValueKey),
 }
 
 /// View of a template parameter
@@ -211,6 +263,10 @@ pub enum TemplateParameterView<'entities> {
 
     /// Value
     ValueLike(ValueView<'entities>),
+
+    /// Type followed by a default value, as in the unnamed non-type template
+    /// parameter `std::enable_if_t<C>* = nullptr`
+    Defaulted(TypeView<'entities>, ValueView<'entities>),
 }
 //
 impl<'entities> TemplateParameterView<'entities> {
@@ -219,6 +275,9 @@ impl<'entities> TemplateParameterView<'entities> {
         match inner {
             TemplateParameter::TypeLike(t) => Self::TypeLike(entities.type_like(t)),
             TemplateParameter::ValueLike(v) => Self::ValueLike(entities.value_like(v)),
+            TemplateParameter::Defaulted(t, v) => {
+                Self::Defaulted(entities.type_like(t), entities.value_like(v))
+            }
         }
     }
 }
@@ -234,6 +293,7 @@ impl<'entities> CustomDisplay for TemplateParameterView<'entities> {
         match self {
             Self::TypeLike(t) => t.recursion_depth(),
             Self::ValueLike(v) => v.recursion_depth(),
+            Self::Defaulted(t, v) => t.recursion_depth().max(v.recursion_depth()),
         }
     }
 
@@ -241,6 +301,11 @@ impl<'entities> CustomDisplay for TemplateParameterView<'entities> {
         match self {
             Self::TypeLike(t) => t.display_impl(f, state),
             Self::ValueLike(v) => v.display_impl(f, state),
+            Self::Defaulted(t, v) => {
+                t.display_impl(f, state)?;
+                write!(f, " = ")?;
+                v.display_impl(f, state)
+            }
         }
     }
 }
@@ -262,7 +327,10 @@ impl<'entities> SliceItemView<'entities> for TemplateParameterView<'entities> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{display::tests::check_custom_display, tests::unwrap_parse};
+    use crate::{
+        display::{tests::check_custom_display, DisplayState},
+        tests::unwrap_parse,
+    };
     use assert_matches::assert_matches;
     use pretty_assertions::assert_eq;
 
@@ -311,6 +379,7 @@ mod tests {
             "charamel<lol>&",
             &["charamel<…>…", "charamel<lol>&"],
         );
+        test_type_parameter(&mut parser, "Bases...", &["Bases…", "Bases..."]);
     }
 
     #[test]
@@ -341,4 +410,293 @@ mod tests {
         );
         test_case("<, void>", None, &["<…>", "<, void>"]);
     }
+
+    #[test]
+    fn qualified_template_arguments() {
+        use crate::subparsers::types::qualifiers::ConstVolatile;
+        let mut parser = EntityParser::new();
+        let mut check_arguments = |input: &str, expected: &[(ConstVolatile, &str, &str)]| {
+            let key = unwrap_parse(parser.parse_template_parameters(input))
+                .expect("Should not be clang's <, void> edge case");
+            let list = parser.template_parameter_list(key);
+            assert_eq!(list.len(), expected.len());
+            for (param, &(cv, declarator, display)) in list.iter().zip(expected) {
+                let TemplateParameterView::TypeLike(ty) = &param else {
+                    panic!("{display} should be parsed as a type");
+                };
+                assert_eq!(ty.type_specifier().cv(), cv, "cv of {display}");
+                assert_eq!(
+                    ty.declarator().to_string(),
+                    declarator,
+                    "declarator of {display}"
+                );
+                assert_eq!(param.to_string(), display);
+            }
+            assert_eq!(list.to_string(), input);
+        };
+
+        // Qualifiers stay attached to the argument they belong to
+        check_arguments(
+            "<const int, int>",
+            &[
+                (ConstVolatile::CONST, "", "const int"),
+                (ConstVolatile::default(), "", "int"),
+            ],
+        );
+        check_arguments(
+            "<int, const int>",
+            &[
+                (ConstVolatile::default(), "", "int"),
+                (ConstVolatile::CONST, "", "const int"),
+            ],
+        );
+
+        // Specifier and declarator qualifiers are kept apart
+        check_arguments(
+            "<const T&, volatile int*, int* const, const volatile U&&>",
+            &[
+                (ConstVolatile::CONST, "&", "const T&"),
+                (ConstVolatile::VOLATILE, "*", "volatile int*"),
+                (ConstVolatile::default(), "* const", "int* const"),
+                (
+                    ConstVolatile::CONST | ConstVolatile::VOLATILE,
+                    "&&",
+                    "const volatile U&&",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn max_template_args() {
+        let mut parser = EntityParser::new();
+        let ty = unwrap_parse(parser.parse_type_like("std::tuple<A, B, C, D, E, F, G, H, I, J>"));
+        let display = |state: DisplayState| parser.type_like(ty).display(&state).to_string();
+
+        // Long template parameter lists are elided beyond the cap...
+        assert_eq!(
+            display(DisplayState::default().with_max_template_args(3)),
+            "std::tuple<A, B, C, …(+7)>"
+        );
+        assert_eq!(
+            display(DisplayState::default().with_max_template_args(0)),
+            "std::tuple<…(+10)>"
+        );
+
+        // ...but shorter ones are left alone
+        assert_eq!(
+            display(DisplayState::default().with_max_template_args(10)),
+            "std::tuple<A, B, C, D, E, F, G, H, I, J>"
+        );
+        assert_eq!(
+            display(DisplayState::default()),
+            "std::tuple<A, B, C, D, E, F, G, H, I, J>"
+        );
+
+        // The cap combines with recursion limits and applies at every level
+        assert_eq!(
+            display(DisplayState::new(0).with_max_template_args(3)),
+            "…::tuple<…>"
+        );
+        let nested = unwrap_parse(parser.parse_type_like("V<W<A, B, C>, X, Y>"));
+        assert_eq!(
+            parser
+                .type_like(nested)
+                .display(&DisplayState::default().with_max_template_args(2))
+                .to_string(),
+            "V<W<A, B, …(+1)>, X, …(+1)>"
+        );
+    }
+
+    #[test]
+    fn max_list_cols() {
+        let mut parser = EntityParser::new();
+        let ty = unwrap_parse(parser.parse_type_like("std::map<std::string, std::vector<int>>"));
+        let display = |state: DisplayState| parser.type_like(ty).display(&state).to_string();
+
+        // Template argument lists are fit within the column budget...
+        assert_eq!(
+            display(DisplayState::default().with_max_list_cols(100)),
+            "std::map<std::string, std::vector<int>>"
+        );
+        assert_eq!(
+            display(DisplayState::default().with_max_list_cols(29)),
+            "std::map<std::string, std::vector<int>>"
+        );
+        assert_eq!(
+            display(DisplayState::default().with_max_list_cols(25)),
+            "std::map<std::string, std::vector…>"
+        );
+        assert_eq!(
+            display(DisplayState::default().with_max_list_cols(5)),
+            "std::map<…(+2)>"
+        );
+
+        // ...and combine with the template argument count cap
+        assert_eq!(
+            display(
+                DisplayState::default()
+                    .with_max_template_args(1)
+                    .with_max_list_cols(100)
+            ),
+            "std::map<std::string, …(+1)>"
+        );
+    }
+
+    #[test]
+    fn variable_template_arguments() {
+        let mut parser = EntityParser::new();
+        let mut check_value_parameters = |input: &str, expected_values: &[&str]| {
+            assert_matches!(parser.parse_template_parameters(input), Ok(("", Some(key))) => {
+                let parameters = parser.raw_template_parameters(key).to_owned();
+                assert_eq!(parameters.len(), expected_values.len());
+                for (expected, actual) in expected_values.iter().zip(parameters.to_vec()) {
+                    let expected = TemplateParameter::ValueLike(unwrap_parse(
+                        parser.parse_value_like(expected, false, false),
+                    ));
+                    assert_eq!(expected, actual);
+                }
+                assert_eq!(parser.template_parameters(Some(key)).to_string(), input);
+            })
+        };
+
+        // Variable template specializations in value expressions
+        check_value_parameters(
+            "<std::is_integral_v<T> && B>",
+            &["std::is_integral_v<T> && B"],
+        );
+        check_value_parameters(
+            "<std::is_integral_v<T> || sizeof(T) == 4>",
+            &["std::is_integral_v<T> || sizeof(T) == 4"],
+        );
+        check_value_parameters(
+            "<(std::is_integral_v<int>)>",
+            &["(std::is_integral_v<int>)"],
+        );
+        check_value_parameters("<!is_integral_v<T>>", &["!is_integral_v<T>"]);
+
+        // Variable template specializations nested in a type
+        let entity = "std::enable_if<std::is_integral_v<int>, void>";
+        let ty = unwrap_parse(parser.parse_type_like(entity));
+        assert_eq!(parser.type_like(ty).to_string(), entity);
+        let entity = "std::enable_if_t<std::is_integral_v<T> && std::is_signed_v<T>, int>";
+        let ty = unwrap_parse(parser.parse_type_like(entity));
+        assert_eq!(parser.type_like(ty).to_string(), entity);
+    }
+
+    #[test]
+    fn function_type_arguments() {
+        use crate::subparsers::{
+            names::unqualified::UnqualifiedIdView,
+            types::{declarators::DeclOperatorView, specifiers::SimpleTypeView},
+        };
+        let mut parser = EntityParser::new();
+        let mut check_argument = |input: &str, display: &str, specifier: &str, num_ops: usize| {
+            let ty = unwrap_parse(parser.parse_type_like(input));
+            let ty = parser.type_like(ty);
+            assert_eq!(ty.to_string(), display);
+
+            // The argument is an abstract function type, not a value
+            let type_specifier = ty.type_specifier();
+            let SimpleTypeView::IdExpression(id) = type_specifier.simple_type() else {
+                panic!("{input} should be a template specialization");
+            };
+            let UnqualifiedIdView::Named {
+                template_parameters: Some(TemplateParametersView(Some(args))),
+                ..
+            } = id.id()
+            else {
+                panic!("{input} should have template arguments");
+            };
+            assert_eq!(args.len(), 1);
+            let TemplateParameterView::TypeLike(arg) = args.iter().next().unwrap() else {
+                panic!("The argument of {input} should be parsed as a type");
+            };
+            assert_eq!(arg.type_specifier().to_string(), specifier);
+            let declarator = arg.declarator();
+            assert_eq!(declarator.len(), num_ops);
+            assert!(matches!(
+                declarator.iter().last(),
+                Some(DeclOperatorView::Function(_))
+            ));
+        };
+
+        check_argument(
+            "std::function<void(int)>",
+            "std::function<void(int)>",
+            "void",
+            1,
+        );
+        check_argument(
+            "std::function<int(*)(double)>",
+            "std::function<int (*)(double)>",
+            "int",
+            2,
+        );
+        check_argument(
+            "member_traits<void() const>",
+            "member_traits<void() const>",
+            "void",
+            1,
+        );
+        check_argument(
+            "std::move_only_function<void(int, double) const & noexcept>",
+            "std::move_only_function<void(int, double) const & noexcept>",
+            "void",
+            1,
+        );
+    }
+
+    #[test]
+    fn sfinae_template_arguments() {
+        let mut parser = EntityParser::new();
+        let mut check_arguments = |input: &str, kinds: &[&str]| {
+            let key = unwrap_parse(parser.parse_template_parameters(input))
+                .expect("Should not be clang's <, void> edge case");
+            let list = parser.template_parameter_list(key);
+            assert_eq!(list.len(), kinds.len());
+            for (param, &kind) in list.iter().zip(kinds) {
+                let actual = match param {
+                    TemplateParameterView::TypeLike(_) => "type",
+                    TemplateParameterView::ValueLike(_) => "value",
+                    TemplateParameterView::Defaulted(_, _) => "defaulted",
+                };
+                assert_eq!(actual, kind, "kind of {param}");
+            }
+            assert_eq!(list.to_string(), input);
+        };
+
+        // Member access on a trait specialization inside enable_if
+        check_arguments(
+            "<std::enable_if<std::is_integral<T>::value, int>::type>",
+            &["type"],
+        );
+        check_arguments(
+            "<typename std::enable_if<!std::is_same<T, U>::value>::type>",
+            &["type"],
+        );
+
+        // Comparisons whose operator starts with '>' do not end the list
+        check_arguments("<N >= 2>", &["value"]);
+        check_arguments("<std::tuple_size<T>::value >= 2, int>", &["value", "type"]);
+        check_arguments(
+            "<std::enable_if<std::tuple_size<T>::value >= 2, int>>",
+            &["type"],
+        );
+
+        // Defaulted unnamed non-type template parameters
+        check_arguments(
+            "<T, std::enable_if_t<std::is_integral<T>::value, int>* = nullptr>",
+            &["type", "defaulted"],
+        );
+        check_arguments(
+            "<T, typename std::enable_if<std::is_integral<T>::value, int>::type = 0>",
+            &["type", "defaulted"],
+        );
+
+        // Nested closing brackets still close every level
+        let entity = "foo<std::enable_if_t<std::is_integral_v<T>>* = nullptr>";
+        let ty = unwrap_parse(parser.parse_type_like(entity));
+        assert_eq!(parser.type_like(ty).to_string(), entity);
+    }
 }
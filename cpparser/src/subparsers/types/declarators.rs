@@ -5,10 +5,16 @@
 use super::qualifiers::{ConstVolatile, Reference};
 use crate::{
     display::{CustomDisplay, DisplayState},
-    interning::slice::{SliceItemView, SliceView},
+    interning::{
+        recursion::SequenceEntry,
+        slice::{SliceItemView, SliceView},
+    },
     subparsers::{
         functions::{FunctionSignature, FunctionSignatureView},
-        names::scopes::{NestedNameSpecifier, NestedNameSpecifierView},
+        names::{
+            atoms::{IdentifierKey, IdentifierView},
+            scopes::{NestedNameSpecifier, NestedNameSpecifierView},
+        },
         values::{ValueKey, ValueView},
     },
     EntityParser, IResult,
@@ -50,13 +56,37 @@ impl EntityParser {
         use nom::{character::complete::multispace0, multi::fold_many0};
         fold_many0(
             (|s| self.parse_decl_operator_imut(s)).terminated(multispace0),
-            || self.declarators.entry(),
-            |mut acc, item| {
-                acc.push(item);
-                acc
-            },
+            || (self.declarators.entry(), Reference::None),
+            push_decl_operator,
         )
-        .map(|entry| entry.intern())
+        .map(intern_declarator)
+        .parse(s)
+    }
+
+    /// Parser for the declarators allowed in a conversion operator's target
+    /// type, which excludes function, array and pack expansion declarators
+    pub(crate) fn parse_conversion_declarator_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, DeclaratorKey> {
+        use nom::{character::complete::multispace0, multi::fold_many0};
+        fold_many0(
+            (|s| self.parse_decl_operator_imut(s))
+                .verify(|op| {
+                    !matches!(
+                        op,
+                        DeclOperator::Function(_)
+                            | DeclOperator::Array(_)
+                            | DeclOperator::Parenthesized(_)
+                            | DeclOperator::PackExpansion
+                            | DeclOperator::PackIndex(_)
+                    )
+                })
+                .terminated(multispace0),
+            || (self.declarators.entry(), Reference::None),
+            push_decl_operator,
+        )
+        .map(intern_declarator)
         .parse(s)
     }
 
@@ -65,6 +95,39 @@ impl EntityParser {
         DeclaratorView::new(d, self.declarators.borrow(), self)
     }
 
+    /// Copy a previously parsed declarator into another parser
+    pub(crate) fn copy_declarator(&self, d: DeclaratorKey, dst: &Self) -> DeclaratorKey {
+        let mut entry = dst.declarators.entry();
+        for &op in self.declarators.get(d).iter() {
+            entry.push(match op {
+                DeclOperator::Pointer { path, cv, restrict } => DeclOperator::Pointer {
+                    path: self.copy_nested_name_specifier(path, dst),
+                    cv,
+                    restrict,
+                },
+                DeclOperator::Array(size) => {
+                    DeclOperator::Array(size.map(|size| self.copy_value_like(size, dst)))
+                }
+                DeclOperator::Function(signature) => {
+                    DeclOperator::Function(self.copy_function_signature(signature, dst))
+                }
+                DeclOperator::Parenthesized(inner) => {
+                    DeclOperator::Parenthesized(self.copy_declarator(inner, dst))
+                }
+                DeclOperator::VectorSize(syntax, size) => {
+                    DeclOperator::VectorSize(syntax, self.copy_value_like(size, dst))
+                }
+                DeclOperator::PackIndex(index) => {
+                    DeclOperator::PackIndex(self.copy_value_like(index, dst))
+                }
+                other @ (DeclOperator::ConstVolatile(_)
+                | DeclOperator::Reference(_)
+                | DeclOperator::PackExpansion) => other,
+            });
+        }
+        entry.intern()
+    }
+
     /// Retrieve a previously interned declarator
     #[cfg(test)]
     pub(crate) fn raw_declarator(&self, key: DeclaratorKey) -> ARef<[DeclOperator]> {
@@ -76,6 +139,73 @@ impl EntityParser {
         self.declarators.borrow().num_items()
     }
 
+    /// Parser for the declarator of a class member, which follows the member's
+    /// type and is made of an optional name and an optional bitfield width,
+    /// as in `x : 3` or `: 0`
+    pub fn parse_member_declarator<'source>(
+        &mut self,
+        s: &'source str,
+    ) -> IResult<'source, MemberDeclarator> {
+        self.parse_member_declarator_imut(s)
+    }
+
+    /// Implementation of parse_member_declarator using internal mutability
+    pub(crate) fn parse_member_declarator_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, MemberDeclarator> {
+        use nom::{
+            character::complete::{char, multispace0},
+            combinator::opt,
+            sequence::{pair, preceded},
+        };
+        pair(
+            opt(|s| self.parse_identifier_imut(s)),
+            opt(preceded(multispace0.and(char(':')).and(multispace0), |s| {
+                self.parse_value_like_imut(s, false, true)
+            })),
+        )
+        .map(|(name, bit_width)| MemberDeclarator { name, bit_width })
+        .parse(s)
+    }
+
+    /// Access a previously parsed member declarator
+    pub fn member_declarator(&self, d: MemberDeclarator) -> MemberDeclaratorView {
+        MemberDeclaratorView::new(d, self)
+    }
+
+    /// Parser for vector extension attributes, as in
+    /// `__attribute__((vector_size(16)))`
+    ///
+    /// These may appear both before and after the element type. In the
+    /// former case, they are moved to the declarator by parse_type_like.
+    ///
+    pub(crate) fn parse_vector_attribute_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, DeclOperator> {
+        use nom::{
+            character::complete::{char, multispace0},
+            sequence::{delimited, pair},
+        };
+        use nom_supreme::tag::complete::tag;
+        let syntax = Self::keywords_parser([
+            ("vector_size", VectorSyntax::GnuVectorSize),
+            ("__vector_size__", VectorSyntax::GnuVectorSize),
+            ("ext_vector_type", VectorSyntax::ClangExtVector),
+            ("__ext_vector_type__", VectorSyntax::ClangExtVector),
+        ]);
+        delimited(
+            tag("__attribute__((").and(multispace0),
+            pair(syntax.terminated(multispace0.and(char('('))), |s| {
+                self.parse_value_like_imut(s, false, true)
+            }),
+            multispace0.and(tag(")))")),
+        )
+        .map(|(syntax, size)| DeclOperator::VectorSize(syntax, size))
+        .parse(s)
+    }
+
     /// Maximal number of template parameters
     pub fn max_declarator_len(&self) -> Option<usize> {
         self.declarators.borrow().max_sequence_len()
@@ -149,7 +279,22 @@ impl EntityParser {
             |s| self.parse_value_like_imut(s, false, true),
             char(')'),
         )
-        .map(DeclOperator::VectorSize);
+        .map(|size| DeclOperator::VectorSize(VectorSyntax::Libiberty, size))
+        .or(|s| self.parse_vector_attribute_imut(s));
+
+        // Pack indexing, as in `Ts...[0]` (C++26)
+        let pack_index = preceded(
+            tag("...").and(multispace0),
+            delimited(
+                char('[').and(multispace0),
+                |s| self.parse_value_like_imut(s, false, true),
+                multispace0.and(char(']')),
+            ),
+        )
+        .map(DeclOperator::PackIndex);
+
+        // Pack expansion, as in `Bases...` or `Bases::operator()...`
+        let pack_expansion = tag("...").value(DeclOperator::PackExpansion);
 
         // Putting it all together...
         //
@@ -164,9 +309,10 @@ impl EntityParser {
             Some(b'*') => basic_pointer.parse(s),
             Some(b'(') => function.or(parenthesized).or(member_pointer).parse(s),
             Some(b'[') => function.or(array).parse(s),
-            Some(b'_') => vector_size.or(member_pointer).parse(s),
+            Some(b'_') => vector_size.or(function).or(member_pointer).parse(s),
             Some(b'c') => cv.or(member_pointer).parse(s),
             Some(b'v') => cv.or(member_pointer).parse(s),
+            Some(b'.') => pack_index.or(pack_expansion).parse(s),
             _ => member_pointer.parse(s),
         }
     }
@@ -178,6 +324,40 @@ impl EntityParser {
     }
 }
 
+/// Declarator being built, along with a trailing reference that has not been
+/// pushed yet because it may need to be collapsed with the next operator
+type DeclaratorAccumulator<'entities> = (
+    SequenceEntry<'entities, DeclOperator, DeclaratorKey>,
+    Reference,
+);
+
+/// Add an operator to a declarator that is being built
+///
+/// Consecutive references (as in `T& &&`) are collapsed into a single one,
+/// which ensures that declarators display as canonical, valid C++.
+///
+fn push_decl_operator(
+    (mut entry, reference): DeclaratorAccumulator,
+    item: DeclOperator,
+) -> DeclaratorAccumulator {
+    if let DeclOperator::Reference(outer) = item {
+        return (entry, reference.collapse(outer));
+    }
+    if reference != Reference::None {
+        entry.push(DeclOperator::Reference(reference));
+    }
+    entry.push(item);
+    (entry, Reference::None)
+}
+
+/// Intern a declarator that was built using `push_decl_operator()`
+fn intern_declarator((mut entry, reference): DeclaratorAccumulator) -> DeclaratorKey {
+    if reference != Reference::None {
+        entry.push(DeclOperator::Reference(reference));
+    }
+    entry.intern()
+}
+
 /// View of a declarator
 pub type DeclaratorView<'entities> =
     SliceView<'entities, DeclOperator, DeclOperatorView<'entities>, DeclaratorKey>;
@@ -213,7 +393,13 @@ pub enum DeclOperator {
     Parenthesized(DeclaratorKey),
 
     /// Vector size, as in `__vector(2)`
-    VectorSize(ValueKey),
+    VectorSize(VectorSyntax, ValueKey),
+
+    /// Pack expansion, as in `Bases...`
+    PackExpansion,
+
+    /// Pack indexing, as in `Ts...[0]`
+    PackIndex(ValueKey),
 }
 //
 impl From<Reference> for DeclOperator {
@@ -234,6 +420,103 @@ impl From<DeclaratorKey> for DeclOperator {
     }
 }
 
+/// Declarator of a class member, as in `x : 3`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MemberDeclarator {
+    /// Name of the member, if any (bitfields can be anonymous)
+    name: Option<IdentifierKey>,
+
+    /// Width of the member in bits, if it is a bitfield
+    bit_width: Option<ValueKey>,
+}
+//
+impl MemberDeclarator {
+    /// Truth that this declarator has no name and no bitfield width
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.bit_width.is_none()
+    }
+
+    /// Truth that this declarator specifies a bitfield width
+    pub fn is_bitfield(&self) -> bool {
+        self.bit_width.is_some()
+    }
+}
+
+/// View of a class member declarator
+#[derive(PartialEq)]
+pub struct MemberDeclaratorView<'entities> {
+    /// Name of the member, if any
+    name: Option<IdentifierView<'entities>>,
+
+    /// Width of the member in bits, if it is a bitfield
+    bit_width: Option<ValueView<'entities>>,
+}
+//
+impl<'entities> MemberDeclaratorView<'entities> {
+    /// Build a member declarator view
+    pub fn new(inner: MemberDeclarator, entities: &'entities EntityParser) -> Self {
+        Self {
+            name: inner.name.map(|name| entities.identifier(name)),
+            bit_width: inner.bit_width.map(|width| entities.value_like(width)),
+        }
+    }
+
+    /// Name of the member, if any (bitfields can be anonymous)
+    pub fn name(&self) -> Option<&IdentifierView<'entities>> {
+        self.name.as_ref()
+    }
+
+    /// Width of the member in bits, if it is a bitfield
+    pub fn bit_width(&self) -> Option<&ValueView<'entities>> {
+        self.bit_width.as_ref()
+    }
+
+    /// Copy this member declarator into another parser
+    pub(crate) fn copy_into(&self, dst: &EntityParser) -> MemberDeclarator {
+        MemberDeclarator {
+            name: self.name.as_ref().map(|name| name.copy_into(dst)),
+            bit_width: self.bit_width.as_ref().map(|width| width.copy_into(dst)),
+        }
+    }
+}
+//
+impl<'entities> Display for MemberDeclaratorView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for MemberDeclaratorView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        self.bit_width.recursion_depth()
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+        if let Some(width) = &self.bit_width {
+            write!(f, " : ")?;
+            width.display_impl(f, state)?;
+        }
+        Ok(())
+    }
+}
+
+/// Syntax that was used to declare a vector extension type
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VectorSyntax {
+    /// Libiberty's `__vector(N)`, where N is a number of elements
+    Libiberty,
+
+    /// GCC's `__attribute__((vector_size(N)))`, where N is a number of bytes
+    GnuVectorSize,
+
+    /// Clang's `__attribute__((ext_vector_type(N)))`, where N is a number of
+    /// elements
+    ClangExtVector,
+}
+
 /// View of a type declarator component (operator)
 #[derive(PartialEq)]
 pub enum DeclOperatorView<'entities> {
@@ -265,7 +548,13 @@ pub enum DeclOperatorView<'entities> {
     Parenthesized(DeclaratorView<'entities>),
 
     /// Vector size, as in `__vector(2)`
-    VectorSize(ValueView<'entities>),
+    VectorSize(VectorSyntax, ValueView<'entities>),
+
+    /// Pack expansion, as in `Bases...`
+    PackExpansion,
+
+    /// Pack indexing, as in `Ts...[0]`
+    PackIndex(ValueView<'entities>),
 }
 //
 impl<'entities> DeclOperatorView<'entities> {
@@ -282,7 +571,9 @@ impl<'entities> DeclOperatorView<'entities> {
             DeclOperator::Array(v) => Self::Array(v.map(|v| entities.value_like(v))),
             DeclOperator::Function(f) => Self::Function(entities.function_signature(f)),
             DeclOperator::Parenthesized(d) => Self::Parenthesized(entities.declarator(d)),
-            DeclOperator::VectorSize(v) => Self::VectorSize(entities.value_like(v)),
+            DeclOperator::VectorSize(syntax, v) => Self::VectorSize(syntax, entities.value_like(v)),
+            DeclOperator::PackExpansion => Self::PackExpansion,
+            DeclOperator::PackIndex(i) => Self::PackIndex(entities.value_like(i)),
         }
     }
 }
@@ -302,7 +593,9 @@ impl<'entities> CustomDisplay for DeclOperatorView<'entities> {
             Self::Array(a) => a.recursion_depth(),
             Self::Function(func) => func.recursion_depth(),
             Self::Parenthesized(d) => d.recursion_depth(),
-            Self::VectorSize(s) => s.recursion_depth(),
+            Self::VectorSize(_, s) => s.recursion_depth(),
+            Self::PackExpansion => 0,
+            Self::PackIndex(i) => i.recursion_depth(),
         }
     }
 
@@ -329,7 +622,12 @@ impl<'entities> CustomDisplay for DeclOperatorView<'entities> {
                 a.display_impl(f, state)?;
                 write!(f, "]")?;
             }
-            Self::Function(func) => func.display_impl(f, state)?,
+            Self::Function(func) => {
+                if func.calling_convention().is_some() {
+                    write!(f, " ")?;
+                }
+                func.display_impl(f, state)?
+            }
             // FIXME: Add recursion bound based on () sign
             Self::Parenthesized(d) => {
                 write!(f, " (")?;
@@ -337,10 +635,22 @@ impl<'entities> CustomDisplay for DeclOperatorView<'entities> {
                 write!(f, ")")?;
             }
             // FIXME: Add recursion bound based on () sign
-            Self::VectorSize(s) => {
-                write!(f, " __vector(")?;
+            Self::VectorSize(syntax, s) => {
+                let (header, trailer) = match syntax {
+                    VectorSyntax::Libiberty => (" __vector(", ")"),
+                    VectorSyntax::GnuVectorSize => (" __attribute__((vector_size(", ")))"),
+                    VectorSyntax::ClangExtVector => (" __attribute__((ext_vector_type(", ")))"),
+                };
+                write!(f, "{header}")?;
                 s.display_impl(f, state)?;
-                write!(f, ")")?;
+                write!(f, "{trailer}")?;
+            }
+            Self::PackExpansion => write!(f, "...")?,
+            // FIXME: Add recursion bound based on [] sign
+            Self::PackIndex(i) => {
+                write!(f, "...[")?;
+                i.display_impl(f, state)?;
+                write!(f, "]")?;
             }
         }
         Ok(())
@@ -532,9 +842,54 @@ mod tests {
         check_decl_operator(&mut parser, "(&&)", expected, &[" (…)", " (&&)"]);
 
         // Vector size
-        expected =
-            DeclOperator::VectorSize(unwrap_parse(parser.parse_value_like("2", false, true)));
+        let two = unwrap_parse(parser.parse_value_like("2", false, true));
+        expected = DeclOperator::VectorSize(VectorSyntax::Libiberty, two);
         check_decl_operator(&mut parser, "__vector(2)", expected, &[" __vector(2)"]);
+        expected = DeclOperator::VectorSize(VectorSyntax::GnuVectorSize, two);
+        check_decl_operator(
+            &mut parser,
+            "__attribute__((vector_size(2)))",
+            expected,
+            &[" __attribute__((vector_size(2)))"],
+        );
+        let four_floats = unwrap_parse(parser.parse_value_like("4 * sizeof(float)", false, true));
+        expected = DeclOperator::VectorSize(VectorSyntax::GnuVectorSize, four_floats);
+        check_decl_operator(
+            &mut parser,
+            "__attribute__((__vector_size__(4 * sizeof(float))))",
+            expected,
+            &[
+                " __attribute__((vector_size(4…)))",
+                " __attribute__((vector_size(4 * sizeof(float))))",
+            ],
+        );
+        expected = DeclOperator::VectorSize(VectorSyntax::ClangExtVector, two);
+        check_decl_operator(
+            &mut parser,
+            "__attribute__((ext_vector_type(2)))",
+            expected,
+            &[" __attribute__((ext_vector_type(2)))"],
+        );
+
+        // Pack expansion
+        check_decl_operator(&mut parser, "...", DeclOperator::PackExpansion, &["..."]);
+
+        // Pack indexing
+        let value_like =
+            |parser: &mut EntityParser, s| unwrap_parse(parser.parse_value_like(s, true, true));
+        expected = DeclOperator::PackIndex(value_like(&mut parser, "0"));
+        check_decl_operator(&mut parser, "...[0]", expected, &["...[0]"]);
+        expected = DeclOperator::PackIndex(value_like(&mut parser, "sizeof...(Ts) - 1"));
+        check_decl_operator(
+            &mut parser,
+            "... [ sizeof...(Ts) - 1 ]",
+            expected,
+            &[
+                "...[sizeof…]",
+                "...[sizeof...(…) - 1]",
+                "...[sizeof...(Ts) - 1]",
+            ],
+        );
     }
 
     #[test]
@@ -560,5 +915,86 @@ mod tests {
 
         // Multiple operators
         test_case("&&*const()", &["&&", "*const", "()"]);
+
+        // Pack expansion
+        test_case("&&...", &["&&", "..."]);
+
+        // Pack indexing
+        test_case("...[0]", &["...[0]"]);
+        test_case("...[0]*", &["...[0]", "*"]);
+
+        // Reference collapsing
+        test_case("& &&", &["&"]);
+        test_case("&& &&", &["&&"]);
+        test_case("&& &", &["&"]);
+        test_case("*& &&", &["*", "&"]);
+        test_case("& &&...", &["&", "..."]);
+        test_case("&const&", &["&", "const", "&"]);
+    }
+
+    #[test]
+    fn member_declaration() {
+        let mut parser = EntityParser::new();
+        let mut check_member =
+            |input, ty: &str, name: Option<&str>, width: Option<&str>, display| {
+                let (ty_key, member) = unwrap_parse(parser.parse_member_declaration(input));
+                assert_eq!(parser.type_like(ty_key).to_string(), ty);
+                let member = parser.member_declarator(member);
+                assert_eq!(member.name().map(|name| name.to_string()).as_deref(), name);
+                assert_eq!(
+                    member.bit_width().map(|width| width.to_string()).as_deref(),
+                    width
+                );
+                assert_eq!(format!("{}{member}", parser.type_like(ty_key)), display);
+            };
+
+        // Named bitfield
+        check_member("int x : 3", "int", Some("x"), Some("3"), "int x : 3");
+        check_member(
+            "unsigned int flags:N + 1",
+            "unsigned int",
+            Some("flags"),
+            Some("N + 1"),
+            "unsigned int flags : N + 1",
+        );
+
+        // Anonymous bitfield
+        check_member("int : 0", "int", None, Some("0"), "int : 0");
+
+        // Regular member
+        check_member(
+            "const char* name",
+            "const char*",
+            Some("name"),
+            None,
+            "const char* name",
+        );
+
+        // Bitfield widths have their own display
+        let member = unwrap_parse(parser.parse_member_declarator("x : 3"));
+        check_custom_display(parser.member_declarator(member), &[" x : 3"]);
+    }
+
+    #[test]
+    fn reference_display() {
+        let mut parser = EntityParser::new();
+        for (input, expected) in [
+            ("T&&", "T&&"),
+            ("T &&", "T&&"),
+            ("const T&", "const T&"),
+            ("T const&", "T const&"),
+            ("T& &&", "T&"),
+            ("T&& &&", "T&&"),
+            ("T&& &", "T&"),
+            ("T*&&", "T*&&"),
+            ("std::vector<T&&>", "std::vector<T&&>"),
+        ] {
+            let key = unwrap_parse(parser.parse_type_like(input));
+            let display = parser.type_like(key).to_string();
+            assert_eq!(display, expected, "{input}");
+
+            // The canonical display must parse back into the same type
+            assert_eq!(parser.parse_type_like(&display), Ok(("", key)), "{input}");
+        }
     }
 }
@@ -5,13 +5,16 @@ pub mod qualifiers;
 pub mod specifiers;
 
 use self::{
-    declarators::{DeclaratorKey, DeclaratorView},
+    declarators::{DeclaratorKey, DeclaratorView, MemberDeclarator},
     specifiers::{TypeSpecifier, TypeSpecifierView},
 };
 use crate::{
     display::{CustomDisplay, DisplayState},
     interning::slice::SliceItemView,
-    subparsers::functions::{FunctionArgumentsKey, FunctionArgumentsView},
+    subparsers::{
+        functions::{FunctionArgumentsKey, FunctionArgumentsView},
+        names::atoms::{IdentifierKey, IdentifierView},
+    },
     EntityParser, IResult,
 };
 use asylum::lasso::Spur;
@@ -41,6 +44,29 @@ impl EntityParser {
     pub(crate) fn parse_type_like_imut<'source>(
         &self,
         s: &'source str,
+    ) -> IResult<'source, TypeKey> {
+        self.parse_type_like_impl(s, |s| self.parse_declarator_imut(s))
+    }
+
+    /// Parser recognizing the target type of a conversion operator
+    ///
+    /// Unlike parse_type_like, this stops before function and array
+    /// declarators, so that in `operator int() &&` the target type is `int`
+    /// and `() &&` is left for the function signature parser.
+    ///
+    pub(crate) fn parse_conversion_type_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, TypeKey> {
+        self.parse_type_like_impl(s, |s| self.parse_conversion_declarator_imut(s))
+    }
+
+    /// Common implementation of parse_type_like and parse_conversion_type
+    #[inline]
+    fn parse_type_like_impl<'source>(
+        &self,
+        s: &'source str,
+        declarator: impl FnMut(&'source str) -> IResult<'source, DeclaratorKey>,
     ) -> IResult<'source, TypeKey> {
         use nom::{
             character::complete::{char, multispace0},
@@ -49,7 +75,12 @@ impl EntityParser {
         };
         use nom_supreme::tag::complete::tag;
 
-        // GNU-style type attributes come first
+        // Vector extension attributes may come first, but they are moved to the
+        // declarator so that all vector types are displayed consistently...
+        let vector_attribute =
+            opt((|s| self.parse_vector_attribute_imut(s)).terminated(multispace0));
+
+        // ...then come GNU-style type attributes
         let attributes = opt(delimited(
             tag("__attribute__("),
             |s| self.parse_function_call_imut(s),
@@ -59,20 +90,91 @@ impl EntityParser {
 
         // Then come the type specifier and declarator
         tuple((
+            vector_attribute,
             attributes.terminated(multispace0),
             (|s| self.parse_type_specifier_imut(s)).terminated(multispace0),
-            |s| self.parse_declarator_imut(s),
+            declarator,
         ))
-        .map(|(attributes, type_specifier, declarator)| {
-            self.types.borrow_mut().intern(TypeLike {
-                attributes,
-                type_specifier,
-                declarator,
-            })
-        })
+        .map(
+            |(vector_attribute, attributes, type_specifier, mut declarator)| {
+                if let Some(vector_attribute) = vector_attribute {
+                    let operators = self.declarators.get(declarator).to_vec();
+                    let mut entry = self.declarators.entry();
+                    entry.push(vector_attribute);
+                    for operator in operators {
+                        entry.push(operator);
+                    }
+                    declarator = entry.intern();
+                }
+                self.types.borrow_mut().intern(TypeLike {
+                    attributes,
+                    type_specifier,
+                    declarator,
+                })
+            },
+        )
         .parse(s)
     }
 
+    /// Parser recognizing class member declarations without the trailing
+    /// semicolon, such as `int x`, `unsigned flags : 3` or `int : 0`
+    pub fn parse_member_declaration<'source>(
+        &mut self,
+        s: &'source str,
+    ) -> IResult<'source, (TypeKey, MemberDeclarator)> {
+        self.parse_member_declaration_imut(s)
+    }
+
+    /// Implementation of parse_member_declaration using internal mutability
+    pub(crate) fn parse_member_declaration_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, (TypeKey, MemberDeclarator)> {
+        use nom::character::complete::multispace0;
+        (|s| self.parse_type_like_imut(s))
+            .terminated(multispace0)
+            .and(|s| self.parse_member_declarator_imut(s))
+            .parse(s)
+    }
+
+    /// Parser recognizing alias declarations, such as `using X = std::vector<T>`
+    ///
+    /// References to type aliases and alias templates do not need any special
+    /// treatment, as they look like other type names and template-ids.
+    ///
+    pub fn parse_alias_declaration<'source>(
+        &mut self,
+        s: &'source str,
+    ) -> IResult<'source, AliasDeclaration> {
+        self.parse_alias_declaration_imut(s)
+    }
+
+    /// Implementation of parse_alias_declaration using internal mutability
+    pub(crate) fn parse_alias_declaration_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, AliasDeclaration> {
+        use nom::{
+            character::complete::{char, multispace0, multispace1},
+            sequence::{preceded, separated_pair},
+        };
+        preceded(
+            Self::keyword_parser("using").and(multispace1),
+            separated_pair(
+                |s| self.parse_identifier_imut(s),
+                multispace0.and(char('=')).and(multispace0),
+                |s| self.parse_type_like_imut(s),
+            ),
+        )
+        .map(|(name, aliased)| AliasDeclaration { name, aliased })
+        .parse(s)
+    }
+
+    /// Access a previously parsed alias declaration
+    pub fn alias_declaration(&self, a: AliasDeclaration) -> AliasDeclarationView {
+        AliasDeclarationView::new(a, self)
+    }
+
     /// Access a previously parsed type
     pub fn type_like(&self, t: TypeKey) -> TypeView {
         TypeView::new(t, self)
@@ -83,6 +185,17 @@ impl EntityParser {
         ARef::new(self.types.borrow()).map(|types| types.get(key))
     }
 
+    /// Copy a previously parsed type into another parser
+    pub(crate) fn copy_type_like(&self, key: TypeKey, dst: &Self) -> TypeKey {
+        let ty = *self.raw_type_like(key);
+        let copy = TypeLike {
+            attributes: self.copy_function_arguments(ty.attributes, dst),
+            type_specifier: self.copy_type_specifier(ty.type_specifier, dst),
+            declarator: self.copy_declarator(ty.declarator, dst),
+        };
+        dst.types.borrow_mut().intern(copy)
+    }
+
     /// Tell how many unique types have been parsed so far
     pub fn num_types(&self) -> usize {
         self.types.borrow().len()
@@ -138,6 +251,11 @@ impl<'entities> TypeView<'entities> {
     pub fn declarator(&self) -> DeclaratorView {
         self.entities.declarator(self.inner.declarator)
     }
+
+    /// Copy this type into another parser, returning its key there
+    pub(crate) fn copy_into(&self, dst: &EntityParser) -> TypeKey {
+        self.entities.copy_type_like(self.key, dst)
+    }
 }
 //
 impl<'entities> PartialEq for TypeView<'entities> {
@@ -190,6 +308,71 @@ impl<'entities> SliceItemView<'entities> for TypeView<'entities> {
     const DISPLAY_TRAILER: &'static str = "";
 }
 
+/// Alias declaration, as in `using X = std::vector<T>`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AliasDeclaration {
+    /// Name of the type alias
+    name: IdentifierKey,
+
+    /// Type that is being aliased
+    aliased: TypeKey,
+}
+
+/// View of an alias declaration
+#[derive(PartialEq)]
+pub struct AliasDeclarationView<'entities> {
+    /// Name of the type alias
+    name: IdentifierView<'entities>,
+
+    /// Type that is being aliased
+    aliased: TypeView<'entities>,
+}
+//
+impl<'entities> AliasDeclarationView<'entities> {
+    /// Build an alias declaration view
+    pub fn new(inner: AliasDeclaration, entities: &'entities EntityParser) -> Self {
+        Self {
+            name: entities.identifier(inner.name),
+            aliased: entities.type_like(inner.aliased),
+        }
+    }
+
+    /// Name of the type alias
+    pub fn name(&self) -> &IdentifierView<'entities> {
+        &self.name
+    }
+
+    /// Type that is being aliased
+    pub fn aliased(&self) -> &TypeView<'entities> {
+        &self.aliased
+    }
+
+    /// Copy this alias declaration into another parser
+    pub(crate) fn copy_into(&self, dst: &EntityParser) -> AliasDeclaration {
+        AliasDeclaration {
+            name: self.name.copy_into(dst),
+            aliased: self.aliased.copy_into(dst),
+        }
+    }
+}
+//
+impl<'entities> Display for AliasDeclarationView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for AliasDeclarationView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        self.aliased.recursion_depth()
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        write!(f, "using {} = ", self.name)?;
+        self.aliased.display_impl(f, state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,6 +419,76 @@ mod tests {
             ],
         );
 
+        // Vector extension types, whose attributes go to the declarator...
+        expected = TypeLike {
+            attributes: attributes(&mut parser, "()"),
+            type_specifier: type_specifier(&mut parser, "float"),
+            declarator: declarator(&mut parser, "__attribute__((vector_size(16)))"),
+        };
+        check_type_like(
+            &mut parser,
+            "__attribute__((vector_size(16))) float",
+            expected,
+            &["float…", "float __attribute__((vector_size(16)))"],
+        );
+        check_type_like(
+            &mut parser,
+            "float __attribute__((vector_size(16)))",
+            expected,
+            &["float…", "float __attribute__((vector_size(16)))"],
+        );
+        expected = TypeLike {
+            attributes: attributes(&mut parser, "()"),
+            type_specifier: type_specifier(&mut parser, "float"),
+            declarator: declarator(&mut parser, "__attribute__((ext_vector_type(4)))"),
+        };
+        check_type_like(
+            &mut parser,
+            "float __attribute__((ext_vector_type(4)))",
+            expected,
+            &["float…", "float __attribute__((ext_vector_type(4)))"],
+        );
+        expected = TypeLike {
+            attributes: attributes(&mut parser, "()"),
+            type_specifier: type_specifier(&mut parser, "float"),
+            declarator: declarator(&mut parser, "__attribute__((vector_size(16)))*"),
+        };
+        check_type_like(
+            &mut parser,
+            "__attribute__((vector_size(16))) float*",
+            expected,
+            &["float…", "float __attribute__((vector_size(16)))*"],
+        );
+
+        // ...without affecting the parsing of their element type
+        expected = TypeLike {
+            attributes: attributes(&mut parser, "()"),
+            type_specifier: type_specifier(&mut parser, "float"),
+            declarator: declarator(&mut parser, ""),
+        };
+        check_type_like(&mut parser, "float", expected, &["float"]);
+
+        // OpenCL-style vector types are just regular type names
+        expected = TypeLike {
+            attributes: attributes(&mut parser, "()"),
+            type_specifier: type_specifier(&mut parser, "float4"),
+            declarator: declarator(&mut parser, ""),
+        };
+        check_type_like(&mut parser, "float4", expected, &["float4"]);
+
+        // Alignment specifiers belong to the type specifier
+        expected = TypeLike {
+            attributes: attributes(&mut parser, "()"),
+            type_specifier: type_specifier(&mut parser, "alignas(double) char"),
+            declarator: declarator(&mut parser, "[8]"),
+        };
+        check_type_like(
+            &mut parser,
+            "alignas(double) char[8]",
+            expected,
+            &["alignas(double) char…", "alignas(double) char[8]"],
+        );
+
         // Basic function pointer
         expected = TypeLike {
             attributes: attributes(&mut parser, "()"),
@@ -261,5 +514,171 @@ mod tests {
             expected,
             &["T<…>…", "T<1>(…)", "T<1>(U)"],
         );
+
+        // Types whose declarator is just a signature or pack suffix
+        for (input, specifier, suffix, displays) in [
+            // MSVC calling conventions
+            (
+                "int __cdecl(void)",
+                "int",
+                "__cdecl(void)",
+                &["int…", "int __cdecl(…)", "int __cdecl(void)"][..],
+            ),
+            (
+                "void __thiscall(int)",
+                "void",
+                "__thiscall(int)",
+                &["void…", "void __thiscall(…)", "void __thiscall(int)"][..],
+            ),
+            // Call and index operators are not confused with their signature
+            (
+                "operator()()",
+                "operator()",
+                "()",
+                &["operator()…", "operator()()"][..],
+            ),
+            (
+                "operator()(int)",
+                "operator()",
+                "(int)",
+                &["operator()…", "operator()(…)", "operator()(int)"][..],
+            ),
+            (
+                "operator[](size_t)",
+                "operator[]",
+                "(size_t)",
+                &["operator[]…", "operator[](…)", "operator[](size_t)"][..],
+            ),
+            (
+                "operator delete(void*, unsigned long)",
+                "operator delete",
+                "(void*, unsigned long)",
+                &[
+                    "operator delete…",
+                    "operator delete(…)",
+                    "operator delete(void…, unsigned long)",
+                    "operator delete(void*, unsigned long)",
+                ][..],
+            ),
+            (
+                "operator delete[](void*, std::size_t)",
+                "operator delete[]",
+                "(void*, std::size_t)",
+                &[
+                    "operator delete[]…",
+                    "operator delete[](…)",
+                    "operator delete[](void…, …::size_t)",
+                    "operator delete[](void*, std::size_t)",
+                ][..],
+            ),
+            (
+                "operator delete(void*, std::align_val_t)",
+                "operator delete",
+                "(void*, std::align_val_t)",
+                &[
+                    "operator delete…",
+                    "operator delete(…)",
+                    "operator delete(void…, …::align_val_t)",
+                    "operator delete(void*, std::align_val_t)",
+                ][..],
+            ),
+            (
+                "operator delete[](void*, unsigned long, std::align_val_t)",
+                "operator delete[]",
+                "(void*, unsigned long, std::align_val_t)",
+                &[
+                    "operator delete[]…",
+                    "operator delete[](…)",
+                    "operator delete[](void…, unsigned long, …::align_val_t)",
+                    "operator delete[](void*, unsigned long, std::align_val_t)",
+                ][..],
+            ),
+            // Pack indexing (C++26)
+            ("Ts...[0]", "Ts", "...[0]", &["Ts…", "Ts...[0]"][..]),
+            (
+                "Ts...[N + 1]*",
+                "Ts",
+                "...[N + 1]*",
+                &["Ts…", "Ts...[N…]*", "Ts...[N + 1]*"][..],
+            ),
+            // Pack expansions in template arguments and using-declarator targets
+            (
+                "Derived<Bases...>",
+                "Derived<Bases...>",
+                "",
+                &["Derived<…>", "Derived<Bases…>", "Derived<Bases...>"][..],
+            ),
+            (
+                "Bases::operator()...",
+                "Bases::operator()",
+                "...",
+                &["…::operator()…", "Bases::operator()..."][..],
+            ),
+        ] {
+            expected = TypeLike {
+                attributes: attributes(&mut parser, "()"),
+                type_specifier: type_specifier(&mut parser, specifier),
+                declarator: declarator(&mut parser, suffix),
+            };
+            check_type_like(&mut parser, input, expected, displays);
+        }
+    }
+
+    #[test]
+    fn type_aliases() {
+        use crate::subparsers::{
+            names::unqualified::UnqualifiedIdView, types::specifiers::SimpleTypeView,
+        };
+        let mut parser = EntityParser::new();
+
+        // References to alias templates are parsed like other template-ids
+        for (input, alias) in [
+            ("std::remove_cvref_t<T>", "remove_cvref_t<T>"),
+            (
+                "typename std::allocator_traits<A>::rebind_alloc<U>",
+                "rebind_alloc<U>",
+            ),
+            ("alias<int>::nested_alias<T>*", "nested_alias<T>"),
+        ] {
+            let key = unwrap_parse(parser.parse_type_like(input));
+            let ty = parser.type_like(key);
+            assert_eq!(ty.to_string(), input);
+            let type_specifier = ty.type_specifier();
+            let (SimpleTypeView::IdExpression(id) | SimpleTypeView::DependentName(id)) =
+                type_specifier.simple_type()
+            else {
+                panic!("{input} should be named by an id-expression");
+            };
+            let id = id.id();
+            assert!(matches!(
+                id,
+                UnqualifiedIdView::Named {
+                    template_parameters: Some(_),
+                    ..
+                }
+            ));
+            assert_eq!(id.to_string(), alias);
+        }
+
+        // Alias declarations
+        let mut check_alias_declaration = |input, name: &str, aliased: &str, displays| {
+            let alias = unwrap_parse(parser.parse_alias_declaration(input));
+            let alias = parser.alias_declaration(alias);
+            assert_eq!(&**alias.name(), name);
+            assert_eq!(alias.aliased().to_string(), aliased);
+            check_custom_display(alias, displays);
+        };
+        check_alias_declaration("using X = int", "X", "int", &["using X = int"]);
+        check_alias_declaration(
+            "using value_type=std::vector<T>",
+            "value_type",
+            "std::vector<T>",
+            &[
+                "using value_type = …::vector<…>",
+                "using value_type = std::vector<T>",
+            ],
+        );
+        assert!(parser.parse_alias_declaration("usingX = int").is_err());
+        assert!(parser.parse_alias_declaration("using = int").is_err());
     }
 }
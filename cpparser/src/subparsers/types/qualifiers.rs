@@ -99,6 +99,23 @@ pub enum Reference {
     RValue,
 }
 //
+impl Reference {
+    /// Apply reference collapsing to a reference to `self` reference
+    ///
+    /// References to references, which clang may emit after template argument
+    /// substitution (as in `T& &&` with `T = U&`), collapse into an rvalue
+    /// reference if both references are rvalue references and into an lvalue
+    /// reference otherwise.
+    ///
+    pub fn collapse(self, outer: Reference) -> Reference {
+        match (self, outer) {
+            (Self::None, other) | (other, Self::None) => other,
+            (Self::RValue, Self::RValue) => Self::RValue,
+            _ => Self::LValue,
+        }
+    }
+}
+//
 impl Default for Reference {
     fn default() -> Self {
         Self::None
@@ -163,5 +180,23 @@ mod tests {
             EntityParser::parse_reference("&&"),
             Ok(("", Reference::RValue))
         );
+        assert_eq!(Reference::None.to_string(), "");
+        assert_eq!(Reference::LValue.to_string(), "&");
+        assert_eq!(Reference::RValue.to_string(), "&&");
+    }
+
+    #[test]
+    fn reference_collapsing() {
+        for (inner, outer, expected) in [
+            (Reference::None, Reference::None, Reference::None),
+            (Reference::None, Reference::LValue, Reference::LValue),
+            (Reference::RValue, Reference::None, Reference::RValue),
+            (Reference::LValue, Reference::LValue, Reference::LValue),
+            (Reference::LValue, Reference::RValue, Reference::LValue),
+            (Reference::RValue, Reference::LValue, Reference::LValue),
+            (Reference::RValue, Reference::RValue, Reference::RValue),
+        ] {
+            assert_eq!(inner.collapse(outer), expected);
+        }
     }
 }
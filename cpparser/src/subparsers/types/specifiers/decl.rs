@@ -0,0 +1,175 @@
+//! Declaration specifiers that are not part of the type proper
+//!
+//! See <https://en.cppreference.com/w/cpp/language/declarations> for context.
+
+use crate::{EntityParser, IResult};
+use nom::Parser;
+use nom_supreme::ParserExt;
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::BitOr,
+};
+
+impl EntityParser {
+    /// Parser recognizing a single declaration specifier keyword
+    #[inline]
+    pub fn parse_decl_specifier(s: &str) -> IResult<DeclSpecifiers> {
+        Self::keywords_parser(DeclSpecifiers::KEYWORDS)(s)
+    }
+
+    /// Parser recognizing an MSVC `__declspec(...)` specifier
+    ///
+    /// Like `[[...]]` attributes, these specifiers do not affect how entities
+    /// are displayed, so their contents are discarded.
+    ///
+    pub fn parse_declspec(s: &str) -> IResult<()> {
+        use nom::character::complete::multispace0;
+        Self::keyword_parser("__declspec")
+            .and(multispace0)
+            .and(balanced_parentheses)
+            .value(())
+            .parse(s)
+    }
+}
+
+/// Parser recognizing a parenthesized group, which may contain nested
+/// parentheses, and returning what lies inside
+fn balanced_parentheses(s: &str) -> IResult<&str> {
+    use nom::{
+        character::complete::char,
+        error::{Error, ErrorKind},
+        Err,
+    };
+    let (rest, _) = char('(')(s)?;
+    let mut depth = 1usize;
+    for (idx, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((&rest[idx + 1..], &rest[..idx]));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(Err::Error(Error::new(s, ErrorKind::Char)))
+}
+
+/// Set of declaration specifiers (constexpr, static, inline...)
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DeclSpecifiers(u8);
+//
+impl DeclSpecifiers {
+    /// Lone friend specifier
+    pub const FRIEND: DeclSpecifiers = DeclSpecifiers(1 << 0);
+
+    /// Lone inline specifier
+    pub const INLINE: DeclSpecifiers = DeclSpecifiers(1 << 1);
+
+    /// Lone static specifier
+    pub const STATIC: DeclSpecifiers = DeclSpecifiers(1 << 2);
+
+    /// Lone virtual specifier
+    pub const VIRTUAL: DeclSpecifiers = DeclSpecifiers(1 << 3);
+
+    /// Lone explicit specifier
+    pub const EXPLICIT: DeclSpecifiers = DeclSpecifiers(1 << 4);
+
+    /// Lone constexpr specifier
+    pub const CONSTEXPR: DeclSpecifiers = DeclSpecifiers(1 << 5);
+
+    /// Lone consteval specifier
+    pub const CONSTEVAL: DeclSpecifiers = DeclSpecifiers(1 << 6);
+
+    /// Lone constinit specifier
+    pub const CONSTINIT: DeclSpecifiers = DeclSpecifiers(1 << 7);
+
+    /// Keywords associated with each specifier, in display order
+    const KEYWORDS: [(&'static str, DeclSpecifiers); 8] = [
+        ("friend", Self::FRIEND),
+        ("inline", Self::INLINE),
+        ("static", Self::STATIC),
+        ("virtual", Self::VIRTUAL),
+        ("explicit", Self::EXPLICIT),
+        ("constexpr", Self::CONSTEXPR),
+        ("consteval", Self::CONSTEVAL),
+        ("constinit", Self::CONSTINIT),
+    ];
+
+    /// Truth that no specifier is present
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Truth that all specifiers from `other` are present in `self`
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+//
+impl BitOr for DeclSpecifiers {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+//
+impl Display for DeclSpecifiers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut first = true;
+        for (keyword, specifier) in Self::KEYWORDS {
+            if self.contains(specifier) {
+                if !first {
+                    write!(f, " ")?;
+                }
+                write!(f, "{keyword}")?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn decl_specifier() {
+        for (keyword, specifier) in DeclSpecifiers::KEYWORDS {
+            assert_eq!(
+                EntityParser::parse_decl_specifier(keyword),
+                Ok(("", specifier))
+            );
+            assert_eq!(specifier.to_string(), keyword);
+        }
+        assert!(EntityParser::parse_decl_specifier("constexpression").is_err());
+        assert!(DeclSpecifiers::default().is_empty());
+
+        let combined = DeclSpecifiers::STATIC | DeclSpecifiers::INLINE;
+        assert!(combined.contains(DeclSpecifiers::INLINE));
+        assert!(!combined.contains(DeclSpecifiers::CONSTEXPR));
+        assert_eq!(combined.to_string(), "inline static");
+    }
+
+    #[test]
+    fn declspec() {
+        assert_eq!(
+            EntityParser::parse_declspec("__declspec(dllimport) int"),
+            Ok((" int", ()))
+        );
+        assert_eq!(
+            EntityParser::parse_declspec("__declspec (align(16))"),
+            Ok(("", ()))
+        );
+        assert_eq!(
+            EntityParser::parse_declspec("__declspec(deprecated(\"f(x)\")))"),
+            Ok((")", ()))
+        );
+        assert!(EntityParser::parse_declspec("__declspec(align(16)").is_err());
+        assert!(EntityParser::parse_declspec("__declspecs(noinline)").is_err());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! See <https://en.cppreference.com/w/cpp/language/types> for context.
 
-use crate::{EntityParser, IResult};
+use crate::{subparsers::names::atoms::end_of_identifier, EntityParser, IResult};
 use nom::Parser;
 use nom_supreme::ParserExt;
 use std::fmt::{self, Display, Formatter};
@@ -23,12 +23,14 @@ impl EntityParser {
 ///
 /// See EntityParser::parse_legacy_name for semantics.
 ///
+/// If `lenient` is true, keywords are matched case-insensitively.
+///
 #[inline]
-pub(crate) fn legacy_name_parser() -> impl Fn(&str) -> IResult<LegacyName> {
+pub(crate) fn legacy_name_parser(lenient: bool) -> impl Fn(&str) -> IResult<LegacyName> {
     use nom::{character::complete::multispace0, multi::fold_many1};
 
-    // Parser for keywords that can appear in legacy names
-    let keyword = EntityParser::keywords_parser([
+    // Keywords that can appear in legacy names
+    let keywords = [
         (
             "float",
             LegacyNameBuilder {
@@ -99,7 +101,30 @@ pub(crate) fn legacy_name_parser() -> impl Fn(&str) -> IResult<LegacyName> {
                 ..Default::default()
             },
         ),
-    ]);
+        (
+            "__float128",
+            LegacyNameBuilder {
+                base: Some(Base::GnuFloat128),
+                ..Default::default()
+            },
+        ),
+        (
+            "_Float16",
+            LegacyNameBuilder {
+                base: Some(Base::Float16),
+                ..Default::default()
+            },
+        ),
+        (
+            "_Float128",
+            LegacyNameBuilder {
+                base: Some(Base::Float128),
+                ..Default::default()
+            },
+        ),
+    ];
+
+    let keyword = legacy_keywords_parser(keywords, lenient);
 
     // Parser for legacy names based on those keywords
     move |s| {
@@ -141,6 +166,29 @@ pub(crate) fn legacy_name_parser() -> impl Fn(&str) -> IResult<LegacyName> {
     }
 }
 
+/// Generate a parser recognizing the keywords of legacy names, matching them
+/// case-insensitively if `lenient` is true
+fn legacy_keywords_parser<const LEN: usize>(
+    keywords: [(&'static str, LegacyNameBuilder); LEN],
+    lenient: bool,
+) -> impl Fn(&str) -> IResult<LegacyNameBuilder> {
+    use nom_supreme::tag::complete::tag_no_case;
+    let strict = EntityParser::keywords_parser(keywords);
+    move |s| {
+        if !lenient {
+            return strict(s);
+        }
+        let mut last_error = None;
+        for (word, output) in &keywords {
+            match tag_no_case(*word).terminated(end_of_identifier).parse(s) {
+                Ok((remainder, _)) => return Ok((remainder, *output)),
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.expect("There is at least one legacy name keyword"))
+    }
+}
+
 /// C-style type
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[repr(u8)]
@@ -201,6 +249,15 @@ pub enum LegacyName {
 
     /// "long double _Complex"
     LongDoubleComplex,
+
+    /// "_Float16"
+    Float16,
+
+    /// "_Float128"
+    Float128,
+
+    /// "__float128"
+    GnuFloat128,
 }
 //
 impl Display for LegacyName {
@@ -225,6 +282,9 @@ impl Display for LegacyName {
             Self::FloatComplex => "float _Complex",
             Self::DoubleComplex => "double _Complex",
             Self::LongDoubleComplex => "long double _Complex",
+            Self::Float16 => "_Float16",
+            Self::Float128 => "_Float128",
+            Self::GnuFloat128 => "__float128",
         };
         write!(f, "{s}")
     }
@@ -314,6 +374,38 @@ impl LegacyNameBuilder {
                 ))
             }
 
+            LegacyNameBuilder {
+                base: Some(Base::Float16),
+                signedness: None,
+                size: None,
+                complex: false,
+            } => LegacyName::Float16,
+
+            LegacyNameBuilder {
+                base: Some(Base::Float128),
+                signedness: None,
+                size: None,
+                complex: false,
+            } => LegacyName::Float128,
+
+            LegacyNameBuilder {
+                base: Some(Base::GnuFloat128),
+                signedness: None,
+                size: None,
+                complex: false,
+            } => LegacyName::GnuFloat128,
+
+            LegacyNameBuilder {
+                base: Some(base @ (Base::Float16 | Base::Float128 | Base::GnuFloat128)),
+                signedness,
+                size,
+                complex: _,
+            } => {
+                return Err(ParseError::IncompatibleBaseSizeSignedness(
+                    base, size, signedness,
+                ))
+            }
+
             // From here, base can't be a floating-point type ===
             LegacyNameBuilder { complex: true, .. } => {
                 return Err(ParseError::IncompatibleComplexInt(self.base))
@@ -470,6 +562,15 @@ enum Base {
 
     /// "double" (usually IEEE-754 binary64)
     Double,
+
+    /// "_Float16" (IEEE-754 binary16)
+    Float16,
+
+    /// "_Float128" (IEEE-754 binary128)
+    Float128,
+
+    /// "__float128" (GNU extension, usually IEEE-754 binary128)
+    GnuFloat128,
 }
 
 /// Errors that can occur while parsing legacy types
@@ -565,5 +666,97 @@ mod tests {
         test_legacy_name("float _Complex", FloatComplex);
         test_legacy_name("double _Complex", DoubleComplex);
         test_legacy_name("long double _Complex", LongDoubleComplex);
+
+        test_legacy_name("_Float16", Float16);
+        test_legacy_name("_Float128", Float128);
+        test_legacy_name("__float128", GnuFloat128);
+    }
+
+    #[test]
+    fn lenient_legacy_name() {
+        let test_cases = [
+            ("Unsigned Int", LegacyName::UnsignedInt),
+            ("LONG long", LegacyName::SignedLongLong),
+            ("unsigned CHAR", LegacyName::UnsignedChar),
+            ("Double _complex", LegacyName::DoubleComplex),
+        ];
+
+        // Strict mode rejects mixed-case primitives...
+        let mut parser = EntityParser::new();
+        for (input, expected) in test_cases {
+            assert_ne!(parser.parse_legacy_name(input), Ok(("", expected)));
+        }
+        assert!(parser.parse_entity("Unsigned Int").is_err());
+
+        // ...but lenient mode accepts them
+        parser.set_lenient_legacy_names(true);
+        for (input, expected) in test_cases {
+            assert_eq!(parser.parse_legacy_name(input), Ok(("", expected)));
+        }
+        let lenient = parser.parse_entity("Unsigned Int").unwrap();
+        assert_eq!(parser.entity(lenient).to_string(), "unsigned int");
+
+        // Keywords must still end where identifiers do
+        assert!(parser.parse_legacy_name("Integer").is_err());
+
+        // Strict mode can be restored
+        parser.set_lenient_legacy_names(false);
+        assert!(parser.parse_legacy_name("Unsigned Int").is_err());
+    }
+
+    #[test]
+    fn extended_float_name() {
+        let parser = EntityParser::new();
+
+        // Extended floating-point types should not be truncated
+        assert_eq!(
+            parser.parse_legacy_name("__float128>"),
+            Ok((">", LegacyName::GnuFloat128))
+        );
+        assert_eq!(
+            parser.parse_legacy_name("_Float128*"),
+            Ok(("*", LegacyName::Float128))
+        );
+
+        // ...nor combined with modifiers that do not apply to them
+        assert!(parser.parse_legacy_name("unsigned __float128").is_err());
+        assert!(parser.parse_legacy_name("long _Float16").is_err());
+        assert!(parser.parse_legacy_name("_Float16 _Complex").is_err());
+        assert!(parser.parse_legacy_name("double __float128").is_err());
+
+        // Identifiers which merely start like a floating-point type name
+        // should not be recognized as such
+        assert!(parser.parse_legacy_name("__float1280").is_err());
+        assert!(parser.parse_legacy_name("_Float16x").is_err());
+    }
+
+    #[test]
+    fn char_signedness() {
+        let parser = EntityParser::new();
+
+        // char, signed char and unsigned char are three distinct types...
+        let plain = parser.parse_legacy_name("char");
+        let signed = parser.parse_legacy_name("signed char");
+        let unsigned = parser.parse_legacy_name("unsigned char");
+        assert_eq!(plain, Ok(("", LegacyName::Char)));
+        assert_eq!(signed, Ok(("", LegacyName::SignedChar)));
+        assert_eq!(unsigned, Ok(("", LegacyName::UnsignedChar)));
+        assert_ne!(plain, signed);
+        assert_ne!(plain, unsigned);
+        assert_ne!(signed, unsigned);
+
+        // ...whatever order the signedness modifier comes in...
+        assert_eq!(parser.parse_legacy_name("char signed"), signed);
+        assert_eq!(parser.parse_legacy_name("char unsigned"), unsigned);
+
+        // ...and they keep their name when displayed
+        for name in ["char", "signed char", "unsigned char"] {
+            let (_, legacy) = parser.parse_legacy_name(name).unwrap();
+            assert_eq!(legacy.to_string(), name);
+        }
+
+        // Contradictory or size modifiers do not apply to char
+        assert!(parser.parse_legacy_name("signed unsigned char").is_err());
+        assert!(parser.parse_legacy_name("long char").is_err());
     }
 }
@@ -2,13 +2,17 @@
 //!
 //! See <https://en.cppreference.com/w/cpp/language/declarations> for context.
 
+pub mod decl;
 pub mod legacy;
 
-use self::legacy::LegacyName;
-use super::qualifiers::ConstVolatile;
+use self::{decl::DeclSpecifiers, legacy::LegacyName};
+use super::{qualifiers::ConstVolatile, TypeKey, TypeView};
 use crate::{
     display::{CustomDisplay, DisplayState},
-    subparsers::names::scopes::{IdExpression, IdExpressionView},
+    subparsers::{
+        names::scopes::{IdExpression, IdExpressionView},
+        values::{ValueKey, ValueView},
+    },
     EntityParser, IResult,
 };
 use nom::Parser;
@@ -32,18 +36,111 @@ impl EntityParser {
         &self,
         s: &'source str,
     ) -> IResult<'source, TypeSpecifier> {
-        use nom::character::complete::multispace0;
-        (Self::parse_cv.terminated(multispace0))
+        use nom::{character::complete::multispace0, combinator::verify, multi::fold_many0};
+
+        // Declaration specifiers, CV qualifiers, alignment specifiers and MSVC's
+        // __declspec (which we discard) may be freely interleaved. We only have
+        // room for one alignment specifier, so types with several of them are
+        // rejected instead of silently keeping only one.
+        let keyword = Self::keywords_parser([
+            ("const", (DeclSpecifiers::default(), ConstVolatile::CONST)),
+            (
+                "volatile",
+                (DeclSpecifiers::default(), ConstVolatile::VOLATILE),
+            ),
+        ])
+        .or(Self::parse_decl_specifier.map(|decl| (decl, ConstVolatile::default())))
+        .or(Self::parse_declspec.map(|()| Default::default()))
+        .map(|(decl, cv)| (decl, cv, None))
+        .or((|s| self.parse_alignment_specifier_imut(s))
+            .map(|alignment| (Default::default(), Default::default(), Some(alignment))));
+        let qualifiers = fold_many0(
+            keyword.terminated(multispace0),
+            <(
+                DeclSpecifiers,
+                ConstVolatile,
+                Option<AlignmentSpecifier>,
+                bool,
+            )>::default,
+            |(decl, cv, alignment, duplicate_alignment), (new_decl, new_cv, new_alignment)| {
+                (
+                    decl | new_decl,
+                    cv | new_cv,
+                    new_alignment.or(alignment),
+                    duplicate_alignment || (alignment.is_some() && new_alignment.is_some()),
+                )
+            },
+        );
+        let qualifiers = verify(qualifiers, |(_, _, _, duplicate_alignment)| {
+            !duplicate_alignment
+        })
+        .map(|(decl, cv, alignment, _)| (decl, cv, alignment));
+
+        qualifiers
             .and(|s| self.parse_simple_type_imut(s))
-            .map(|(cv, simple_type)| TypeSpecifier { cv, simple_type })
+            .map(|((decl, cv, alignment), simple_type)| TypeSpecifier {
+                decl,
+                cv,
+                alignment,
+                simple_type,
+            })
             .parse(s)
     }
 
+    /// Parser recognizing alignment specifiers `alignas(<type or value>)`
+    fn parse_alignment_specifier_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, AlignmentSpecifier> {
+        use nom::{
+            character::complete::{char, multispace0},
+            sequence::{preceded, terminated},
+        };
+        let end = || multispace0.and(char(')'));
+        let type_like =
+            terminated(|s| self.parse_type_like_imut(s), end()).map(AlignmentSpecifier::TypeLike);
+        let value_like = terminated(|s| self.parse_value_like_imut(s, true, true), end())
+            .map(AlignmentSpecifier::ValueLike);
+        preceded(
+            Self::keyword_parser("alignas")
+                .and(multispace0)
+                .and(char('('))
+                .and(multispace0),
+            type_like.or(value_like),
+        )
+        .parse(s)
+    }
+
     /// Access a previously parsed type specifier
     pub fn type_specifier(&self, ts: TypeSpecifier) -> TypeSpecifierView {
         TypeSpecifierView::new(ts, self)
     }
 
+    /// Copy a previously parsed type specifier into another parser
+    pub(crate) fn copy_type_specifier(&self, ts: TypeSpecifier, dst: &Self) -> TypeSpecifier {
+        TypeSpecifier {
+            decl: ts.decl,
+            cv: ts.cv,
+            alignment: ts.alignment.map(|alignment| match alignment {
+                AlignmentSpecifier::TypeLike(ty) => {
+                    AlignmentSpecifier::TypeLike(self.copy_type_like(ty, dst))
+                }
+                AlignmentSpecifier::ValueLike(value) => {
+                    AlignmentSpecifier::ValueLike(self.copy_value_like(value, dst))
+                }
+            }),
+            simple_type: match ts.simple_type {
+                SimpleType::IdExpression(id) => {
+                    SimpleType::IdExpression(self.copy_id_expression(id, dst))
+                }
+                SimpleType::DependentName(id) => {
+                    SimpleType::DependentName(self.copy_id_expression(id, dst))
+                }
+                other @ (SimpleType::LegacyName(_) | SimpleType::LibibertyAuto(_)) => other,
+            },
+        }
+    }
+
     /// Parser recognizing simple types
     #[inline]
     fn parse_simple_type_imut<'source>(&self, s: &'source str) -> IResult<'source, SimpleType> {
@@ -56,15 +153,29 @@ impl EntityParser {
 
         // The inner simple type can be an id-expression (which must be preceded
         // keywords in obscure circumstances...)
-        let id_header =
-            opt(
-                Self::keywords_parser(["typename", "class", "struct", "enum", "union"])
-                    .and(multispace1),
-            );
-        let id_expression = preceded(
-            id_header,
-            (|s| self.parse_id_expression_imut(s)).map(SimpleType::IdExpression),
-        );
+        let id_header = opt(Self::keywords_parser([
+            ("typename", true),
+            ("class", false),
+            ("struct", false),
+            ("enum", false),
+            ("union", false),
+        ])
+        .terminated(multispace1));
+        let id_expression =
+            id_header
+                .and(|s| self.parse_id_expression_imut(s))
+                .map(|(is_typename, id)| {
+                    // The typename keyword applies to the whole qualified-id and
+                    // tells dependent types apart from static members, so we keep
+                    // it around. Other elaborated type keywords can go.
+                    if is_typename == Some(true)
+                        && !self.id_expression(id).path().scopes().is_empty()
+                    {
+                        SimpleType::DependentName(id)
+                    } else {
+                        SimpleType::IdExpression(id)
+                    }
+                });
 
         // ...or a legacy C-style primitive type with inner spaces...
         let legacy_primitive = (|s| self.parse_legacy_name(s)).map(SimpleType::LegacyName);
@@ -88,9 +199,15 @@ impl EntityParser {
 /// Type specifier
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TypeSpecifier {
+    /// Declaration specifiers (constexpr, static, inline...)
+    decl: DeclSpecifiers,
+
     /// CV qualifiers applying to the simple type
     cv: ConstVolatile,
 
+    /// Alignment specifier (`alignas(...)`), if any
+    alignment: Option<AlignmentSpecifier>,
+
     /// Simple type
     simple_type: SimpleType,
 }
@@ -98,7 +215,9 @@ pub struct TypeSpecifier {
 impl<T: Into<SimpleType>> From<T> for TypeSpecifier {
     fn from(simple_type: T) -> Self {
         Self {
+            decl: DeclSpecifiers::default(),
             cv: ConstVolatile::default(),
+            alignment: None,
             simple_type: simple_type.into(),
         }
     }
@@ -119,11 +238,23 @@ impl<'entities> TypeSpecifierView<'entities> {
         Self { inner, entities }
     }
 
+    /// Declaration specifiers (constexpr, static, inline...)
+    pub fn decl_specifiers(&self) -> DeclSpecifiers {
+        self.inner.decl
+    }
+
     /// CV qualifiers applying to the simple type
     pub fn cv(&self) -> ConstVolatile {
         self.inner.cv
     }
 
+    /// Alignment specifier (`alignas(...)`), if any
+    pub fn alignment(&self) -> Option<AlignmentSpecifierView> {
+        self.inner
+            .alignment
+            .map(|alignment| AlignmentSpecifierView::new(alignment, self.entities))
+    }
+
     /// Simple type
     pub fn simple_type(&self) -> SimpleTypeView {
         self.entities.simple_type(self.inner.simple_type)
@@ -144,10 +275,20 @@ impl<'entities> Display for TypeSpecifierView<'entities> {
 //
 impl<'entities> CustomDisplay for TypeSpecifierView<'entities> {
     fn recursion_depth(&self) -> usize {
-        self.simple_type().recursion_depth()
+        self.simple_type()
+            .recursion_depth()
+            .max(self.alignment().recursion_depth())
     }
 
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        if let Some(alignment) = self.alignment() {
+            alignment.display_impl(f, state)?;
+            write!(f, " ")?;
+        }
+        let decl = self.decl_specifiers();
+        if !decl.is_empty() {
+            write!(f, "{decl} ")?;
+        }
         let cv = self.cv();
         if cv != ConstVolatile::default() {
             write!(f, "{cv} ")?;
@@ -156,12 +297,71 @@ impl<'entities> CustomDisplay for TypeSpecifierView<'entities> {
     }
 }
 
+/// Alignment specifier, as in `alignas(16)` or `alignas(double)`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AlignmentSpecifier {
+    /// Alignment of a type
+    TypeLike(TypeKey),
+
+    /// Alignment given by a constant expression
+    ValueLike(ValueKey),
+}
+
+/// View of an alignment specifier
+#[derive(PartialEq)]
+pub enum AlignmentSpecifierView<'entities> {
+    /// Alignment of a type
+    TypeLike(TypeView<'entities>),
+
+    /// Alignment given by a constant expression
+    ValueLike(ValueView<'entities>),
+}
+//
+impl<'entities> AlignmentSpecifierView<'entities> {
+    /// Set up an alignment specifier view
+    pub fn new(inner: AlignmentSpecifier, entities: &'entities EntityParser) -> Self {
+        match inner {
+            AlignmentSpecifier::TypeLike(t) => Self::TypeLike(entities.type_like(t)),
+            AlignmentSpecifier::ValueLike(v) => Self::ValueLike(entities.value_like(v)),
+        }
+    }
+}
+//
+impl<'entities> Display for AlignmentSpecifierView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for AlignmentSpecifierView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        match self {
+            Self::TypeLike(t) => t.recursion_depth(),
+            Self::ValueLike(v) => v.recursion_depth(),
+        }
+    }
+
+    // FIXME: Add recursion bound based on () sign
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        write!(f, "alignas(")?;
+        match self {
+            Self::TypeLike(t) => t.display_impl(f, state)?,
+            Self::ValueLike(v) => v.display_impl(f, state)?,
+        }
+        write!(f, ")")
+    }
+}
+
 /// Inner simple type specifiers that TypeSpecifier can wrap
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub(crate) enum SimpleType {
     /// Id-expressions
     IdExpression(IdExpression),
 
+    /// Qualified dependent names, disambiguated with a leading typename keyword
+    /// (e.g. "typename std::vector<T>::iterator")
+    DependentName(IdExpression),
+
     /// C-style space-separated type names (e.g. "unsigned int")
     LegacyName(LegacyName),
 
@@ -187,6 +387,10 @@ pub enum SimpleTypeView<'entities> {
     /// Id-expressions
     IdExpression(IdExpressionView<'entities>),
 
+    /// Qualified dependent names, disambiguated with a leading typename keyword
+    /// (e.g. "typename std::vector<T>::iterator")
+    DependentName(IdExpressionView<'entities>),
+
     /// C-style space-separated type names (e.g. "unsigned int")
     LegacyName(LegacyName),
 
@@ -199,6 +403,7 @@ impl<'entities> SimpleTypeView<'entities> {
     pub(crate) fn new(inner: SimpleType, entities: &'entities EntityParser) -> Self {
         match inner {
             SimpleType::IdExpression(i) => Self::IdExpression(entities.id_expression(i)),
+            SimpleType::DependentName(i) => Self::DependentName(entities.id_expression(i)),
             SimpleType::LegacyName(l) => Self::LegacyName(l),
             SimpleType::LibibertyAuto(u) => Self::LibibertyAuto(u),
         }
@@ -214,7 +419,7 @@ impl<'entities> Display for SimpleTypeView<'entities> {
 impl<'entities> CustomDisplay for SimpleTypeView<'entities> {
     fn recursion_depth(&self) -> usize {
         match self {
-            Self::IdExpression(i) => i.recursion_depth(),
+            Self::IdExpression(i) | Self::DependentName(i) => i.recursion_depth(),
             Self::LegacyName(_) => 0,
             Self::LibibertyAuto(_) => 0,
         }
@@ -223,6 +428,10 @@ impl<'entities> CustomDisplay for SimpleTypeView<'entities> {
     fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
         match self {
             Self::IdExpression(i) => i.display_impl(f, state),
+            Self::DependentName(i) => {
+                write!(f, "typename ")?;
+                i.display_impl(f, state)
+            }
             Self::LegacyName(l) => write!(f, "{l}"),
             Self::LibibertyAuto(u) => write!(f, "auto:{u}"),
         }
@@ -264,10 +473,43 @@ mod tests {
             LegacyName::UnsignedInt.into(),
             &["unsigned int"],
         );
+        check_simple_type(
+            &mut parser,
+            "__float128",
+            LegacyName::GnuFloat128.into(),
+            &["__float128"],
+        );
 
         // And we can live with the occasional keyword
         expected = id_expression(&mut parser, "MyClass").into();
         check_simple_type(&mut parser, "class MyClass", expected, &["MyClass"]);
+        check_simple_type(&mut parser, "typename MyClass", expected, &["MyClass"]);
+
+        // ...but typename is kept when it disambiguates a dependent name
+        expected =
+            SimpleType::DependentName(id_expression(&mut parser, "std::vector<T>::iterator"));
+        check_simple_type(
+            &mut parser,
+            "typename std::vector<T>::iterator",
+            expected,
+            &[
+                "typename …::iterator",
+                "typename std::vector<…>::iterator",
+                "typename std::vector<T>::iterator",
+            ],
+        );
+        expected =
+            SimpleType::DependentName(id_expression(&mut parser, "Outer<T>::Inner<U>::type"));
+        check_simple_type(
+            &mut parser,
+            "typename Outer<T>::Inner<U>::type",
+            expected,
+            &[
+                "typename …::type",
+                "typename Outer<…>::Inner<…>::type",
+                "typename Outer<T>::Inner<U>::type",
+            ],
+        );
     }
 
     #[test]
@@ -291,10 +533,125 @@ mod tests {
             &mut parser,
             "const int",
             TypeSpecifier {
+                decl: DeclSpecifiers::default(),
                 simple_type: LegacyName::SignedInt.into(),
                 cv: ConstVolatile::CONST,
+                alignment: None,
             },
             &["const int"],
         );
+
+        // ...and with declaration specifiers
+        check_type_specifier(
+            &mut parser,
+            "constexpr int",
+            TypeSpecifier {
+                decl: DeclSpecifiers::CONSTEXPR,
+                simple_type: LegacyName::SignedInt.into(),
+                cv: ConstVolatile::default(),
+                alignment: None,
+            },
+            &["constexpr int"],
+        );
+        let auto = unwrap_parse(parser.parse_id_expression("auto"));
+        check_type_specifier(
+            &mut parser,
+            "consteval auto",
+            TypeSpecifier {
+                decl: DeclSpecifiers::CONSTEVAL,
+                simple_type: auto.into(),
+                cv: ConstVolatile::default(),
+                alignment: None,
+            },
+            &["consteval auto"],
+        );
+        let int = LegacyName::SignedInt.into();
+        check_type_specifier(
+            &mut parser,
+            "inline static const int",
+            TypeSpecifier {
+                decl: DeclSpecifiers::INLINE | DeclSpecifiers::STATIC,
+                simple_type: int,
+                cv: ConstVolatile::CONST,
+                alignment: None,
+            },
+            &["inline static const int"],
+        );
+
+        // Interleaved specifiers are displayed in canonical order
+        check_type_specifier(
+            &mut parser,
+            "const static inline int",
+            TypeSpecifier {
+                decl: DeclSpecifiers::INLINE | DeclSpecifiers::STATIC,
+                simple_type: int,
+                cv: ConstVolatile::CONST,
+                alignment: None,
+            },
+            &["inline static const int"],
+        );
+
+        // MSVC's __declspec is accepted, but discarded like [[attributes]]
+        check_type_specifier(
+            &mut parser,
+            "__declspec(dllimport) int",
+            int.into(),
+            &["int"],
+        );
+        check_type_specifier(
+            &mut parser,
+            "static __declspec( align(16) ) const int",
+            TypeSpecifier {
+                decl: DeclSpecifiers::STATIC,
+                simple_type: int,
+                cv: ConstVolatile::CONST,
+                alignment: None,
+            },
+            &["static const int"],
+        );
+
+        // Alignment specifiers are kept, and can take a value or a type
+        let sixteen = unwrap_parse(parser.parse_value_like("16", true, true));
+        check_type_specifier(
+            &mut parser,
+            "alignas(16) int",
+            TypeSpecifier {
+                decl: DeclSpecifiers::default(),
+                simple_type: int,
+                cv: ConstVolatile::default(),
+                alignment: Some(AlignmentSpecifier::ValueLike(sixteen)),
+            },
+            &["alignas(16) int"],
+        );
+        let double = unwrap_parse(parser.parse_type_like("double"));
+        let char_type = LegacyName::Char.into();
+        check_type_specifier(
+            &mut parser,
+            "static alignas( double ) const char",
+            TypeSpecifier {
+                decl: DeclSpecifiers::STATIC,
+                simple_type: char_type,
+                cv: ConstVolatile::CONST,
+                alignment: Some(AlignmentSpecifier::TypeLike(double)),
+            },
+            &["alignas(double) static const char"],
+        );
+
+        // Only one alignment specifier can be kept, so duplicates are rejected
+        assert!(parser
+            .parse_type_specifier("alignas(8) alignas(16) int")
+            .is_err());
+        assert!(parser
+            .parse_type_specifier("alignas(16) const alignas(double) int")
+            .is_err());
+
+        // Identifiers that merely start with a specifier are not specifiers
+        let expected = unwrap_parse(parser.parse_id_expression("constexpr_value"));
+        check_type_specifier(
+            &mut parser,
+            "constexpr_value",
+            expected.into(),
+            &["constexpr_value"],
+        );
     }
 }
@@ -29,6 +29,14 @@ impl EntityParser {
     pub fn literal(&self, l: Literal) -> LiteralView {
         LiteralView::new(l, self)
     }
+
+    /// Copy a previously parsed literal into another parser
+    pub(crate) fn copy_literal(&self, l: Literal, dst: &Self) -> Literal {
+        Literal {
+            value: l.value,
+            custom_suffix: l.custom_suffix.map(|id| self.copy_identifier(id, dst)),
+        }
+    }
 }
 
 /// A modern C++ literal, accounting for custom literals
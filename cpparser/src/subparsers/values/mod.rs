@@ -1,8 +1,12 @@
 //! Values and other things that follow the value grammar
 
 pub mod literals;
+pub mod requires;
 
-use self::literals::{Literal, LiteralView};
+use self::{
+    literals::{Literal, LiteralView},
+    requires::{RequiresExpression, RequiresExpressionView},
+};
 use crate::{
     display::{CustomDisplay, DisplayState},
     interning::slice::{SliceItemView, SliceView},
@@ -109,6 +113,66 @@ impl EntityParser {
         ARef::new(self.values.borrow()).map(|values| values.get(key))
     }
 
+    /// Copy a previously parsed value into another parser
+    pub(crate) fn copy_value_like(&self, key: ValueKey, dst: &Self) -> ValueKey {
+        let value = *self.raw_value_like(key);
+        let header = match value.header {
+            ValueHeader::Literal(literal) => ValueHeader::Literal(self.copy_literal(literal, dst)),
+            ValueHeader::Parenthesized(inner) => {
+                ValueHeader::Parenthesized(self.copy_value_like(inner, dst))
+            }
+            ValueHeader::UnaryOp(op, operand) => ValueHeader::UnaryOp(
+                self.copy_operator(op, dst),
+                self.copy_value_like(operand, dst),
+            ),
+            ValueHeader::NewExpression(ne) => {
+                ValueHeader::NewExpression(self.copy_new_expression(ne, dst))
+            }
+            ValueHeader::IdExpression(id) => {
+                ValueHeader::IdExpression(self.copy_id_expression(id, dst))
+            }
+            ValueHeader::RequiresExpression(r) => {
+                ValueHeader::RequiresExpression(self.copy_requires_expression(r, dst))
+            }
+            ValueHeader::Ellipsis => ValueHeader::Ellipsis,
+            ValueHeader::SizeOf(ty) => ValueHeader::SizeOf(self.copy_type_like(ty, dst)),
+            ValueHeader::DeclVal(ty) => ValueHeader::DeclVal(self.copy_type_like(ty, dst)),
+            ValueHeader::Noexcept(inner) => ValueHeader::Noexcept(self.copy_value_like(inner, dst)),
+        };
+        let mut entry = dst.value_trailers.entry();
+        for &after in self.value_trailers.get(value.trailer).iter() {
+            entry.push(match after {
+                AfterValue::ArrayIndex(index) => {
+                    AfterValue::ArrayIndex(self.copy_value_like(index, dst))
+                }
+                AfterValue::FunctionCall(args) => {
+                    AfterValue::FunctionCall(self.copy_function_arguments(args, dst))
+                }
+                AfterValue::BinaryOp(op, rhs) => AfterValue::BinaryOp(
+                    self.copy_operator(op, dst),
+                    self.copy_value_like(rhs, dst),
+                ),
+                AfterValue::TernaryOp(if_true, if_false) => AfterValue::TernaryOp(
+                    self.copy_value_like(if_true, dst),
+                    self.copy_value_like(if_false, dst),
+                ),
+                AfterValue::MemberAccess(id) => {
+                    AfterValue::MemberAccess(self.copy_unqualified_id(id, dst))
+                }
+                AfterValue::PostfixOp(op) => AfterValue::PostfixOp(self.copy_operator(op, dst)),
+                AfterValue::Ellipsis => AfterValue::Ellipsis,
+                AfterValue::PackIndex(index) => {
+                    AfterValue::PackIndex(self.copy_value_like(index, dst))
+                }
+            });
+        }
+        let copy = ValueLike {
+            header,
+            trailer: entry.intern(),
+        };
+        dst.values.borrow_mut().intern(copy)
+    }
+
     /// Tell how many unique types have been parsed so far
     pub fn num_values(&self) -> usize {
         self.values.borrow().len()
@@ -153,6 +217,9 @@ impl EntityParser {
         let new_expression =
             (|s| self.parse_new_expression_imut(s)).map(ValueHeader::NewExpression);
 
+        let requires_expression =
+            (|s| self.parse_requires_expression_imut(s)).map(ValueHeader::RequiresExpression);
+
         let id_expression = (|s| self.parse_id_expression_imut(s)).map(ValueHeader::IdExpression);
 
         let ellipsis = tag("...").value(ValueHeader::Ellipsis);
@@ -161,12 +228,21 @@ impl EntityParser {
             .map(ValueHeader::SizeOf);
         let declval = delimited(tag("declval("), |s| self.parse_type_like_imut(s), char(')'))
             .map(ValueHeader::DeclVal);
+        let noexcept = delimited(
+            tag("noexcept(").and(multispace0),
+            parenthesized_value_like,
+            multispace0.and(char(')')),
+        )
+        .map(ValueHeader::Noexcept);
 
         literal
             .or(new_expression)
             .or(sizeof)
             .or(declval)
-            // Must come after sizeof and declaval as it matches the keywords
+            .or(noexcept)
+            .or(requires_expression)
+            // Must come after sizeof, declval, noexcept and requires-expressions
+            // as it matches the keywords
             .or(id_expression)
             .or(unary_op)
             // Must come after unary_op to match casts as intended
@@ -227,12 +303,22 @@ impl EntityParser {
             (|s| self.parse_function_call_imut(s)).map(AfterValue::FunctionCall);
 
         let member_access = preceded(char('.').and(multispace0), |s| {
-            self.parse_unqualified_id_imut(s)
+            self.parse_unqualified_id_imut(Self::skip_template_disambiguator(s))
         })
         .map(AfterValue::MemberAccess);
 
         let postfix_op = Self::parse_increment_decrement.map(AfterValue::PostfixOp);
 
+        let pack_index = preceded(
+            tag("...").and(multispace0),
+            delimited(
+                char('[').and(multispace0),
+                value_like_index,
+                multispace0.and(char(']')),
+            ),
+        )
+        .map(AfterValue::PackIndex);
+
         let ellipsis = tag("...").value(AfterValue::Ellipsis);
 
         // Since this parser is quite hot (~1M calls on a test workload) and usually
@@ -242,7 +328,7 @@ impl EntityParser {
         match s.as_bytes().first() {
             Some(b'(') => function_call.parse(s),
             Some(b'?') => ternary_op.parse(s),
-            Some(b'.') => member_access.or(ellipsis).parse(s),
+            Some(b'.') => member_access.or(pack_index).or(ellipsis).parse(s),
             Some(b'[') => array_index.parse(s),
             _ => binary_op.or(postfix_op).parse(s),
         }
@@ -319,6 +405,11 @@ impl<'entities> ValueView<'entities> {
     pub fn trailer(&self) -> ValueTrailerView {
         self.entities.value_trailer(self.inner.trailer)
     }
+
+    /// Copy this value into another parser, returning its key there
+    pub(crate) fn copy_into(&self, dst: &EntityParser) -> ValueKey {
+        self.entities.copy_value_like(self.key, dst)
+    }
 }
 //
 impl<'entities> PartialEq for ValueView<'entities> {
@@ -378,6 +469,9 @@ pub(crate) enum ValueHeader {
     /// Named value
     IdExpression(IdExpression),
 
+    /// Requires-expression
+    RequiresExpression(RequiresExpression),
+
     /// Ellipsis sign ... (used in fold expressions)
     Ellipsis,
 
@@ -386,6 +480,9 @@ pub(crate) enum ValueHeader {
 
     /// declval() operator
     DeclVal(TypeKey),
+
+    /// noexcept() operator
+    Noexcept(ValueKey),
 }
 //
 impl From<Literal> for ValueHeader {
@@ -430,6 +527,9 @@ pub enum ValueHeaderView<'entities> {
     /// Named value
     IdExpression(IdExpressionView<'entities>),
 
+    /// Requires-expression
+    RequiresExpression(RequiresExpressionView<'entities>),
+
     /// Ellipsis sign ... (used in fold expressions)
     Ellipsis,
 
@@ -438,6 +538,9 @@ pub enum ValueHeaderView<'entities> {
 
     /// declval() operator
     DeclVal(TypeView<'entities>),
+
+    /// noexcept() operator
+    Noexcept(ValueView<'entities>),
 }
 //
 impl<'entities> ValueHeaderView<'entities> {
@@ -451,9 +554,13 @@ impl<'entities> ValueHeaderView<'entities> {
             }
             ValueHeader::NewExpression(ne) => Self::NewExpression(entities.new_expression(ne)),
             ValueHeader::IdExpression(id) => Self::IdExpression(entities.id_expression(id)),
+            ValueHeader::RequiresExpression(r) => {
+                Self::RequiresExpression(entities.requires_expression(r))
+            }
             ValueHeader::Ellipsis => Self::Ellipsis,
             ValueHeader::SizeOf(t) => Self::SizeOf(entities.type_like(t)),
             ValueHeader::DeclVal(t) => Self::DeclVal(entities.type_like(t)),
+            ValueHeader::Noexcept(v) => Self::Noexcept(entities.value_like(v)),
         }
     }
 }
@@ -472,9 +579,11 @@ impl<'entities> CustomDisplay for ValueHeaderView<'entities> {
             Self::UnaryOp(o, v) => o.recursion_depth().max(v.recursion_depth()),
             Self::NewExpression(n) => n.recursion_depth(),
             Self::IdExpression(i) => i.recursion_depth(),
+            Self::RequiresExpression(r) => r.recursion_depth(),
             Self::Ellipsis => 0,
             Self::SizeOf(t) => t.recursion_depth(),
             Self::DeclVal(t) => t.recursion_depth(),
+            Self::Noexcept(v) => v.recursion_depth(),
         }
     }
 
@@ -493,6 +602,7 @@ impl<'entities> CustomDisplay for ValueHeaderView<'entities> {
             }
             Self::NewExpression(n) => n.display_impl(f, state),
             Self::IdExpression(i) => i.display_impl(f, state),
+            Self::RequiresExpression(r) => r.display_impl(f, state),
             Self::Ellipsis => write!(f, "..."),
             Self::SizeOf(t) | Self::DeclVal(t) => {
                 let keyword = if let Self::SizeOf(_) = self {
@@ -504,6 +614,11 @@ impl<'entities> CustomDisplay for ValueHeaderView<'entities> {
                 t.display_impl(f, state)?;
                 write!(f, ")")
             }
+            Self::Noexcept(v) => {
+                write!(f, "noexcept(")?;
+                v.display_impl(f, state)?;
+                write!(f, ")")
+            }
         }
     }
 }
@@ -535,6 +650,9 @@ pub enum AfterValue {
 
     /// Ellipsis sign ... (used in template parameter pack expansion)
     Ellipsis,
+
+    /// Pack indexing (...[x], C++26)
+    PackIndex(ValueKey),
 }
 //
 impl From<FunctionArgumentsKey> for AfterValue {
@@ -566,6 +684,9 @@ pub enum AfterValueView<'entities> {
 
     /// Ellipsis sign ... (used in template parameter pack expansion)
     Ellipsis,
+
+    /// Pack indexing (...[x], C++26)
+    PackIndex(ValueView<'entities>),
 }
 //
 impl<'entities> AfterValueView<'entities> {
@@ -583,6 +704,7 @@ impl<'entities> AfterValueView<'entities> {
             AfterValue::MemberAccess(m) => Self::MemberAccess(entities.unqualified_id(m)),
             AfterValue::PostfixOp(o) => Self::PostfixOp(entities.operator(o)),
             AfterValue::Ellipsis => Self::Ellipsis,
+            AfterValue::PackIndex(i) => Self::PackIndex(entities.value_like(i)),
         }
     }
 }
@@ -603,6 +725,7 @@ impl<'entities> CustomDisplay for AfterValueView<'entities> {
             Self::MemberAccess(m) => m.recursion_depth(),
             Self::PostfixOp(o) => o.recursion_depth(),
             Self::Ellipsis => 0,
+            Self::PackIndex(i) => i.recursion_depth(),
         }
     }
 
@@ -631,6 +754,12 @@ impl<'entities> CustomDisplay for AfterValueView<'entities> {
             }
             Self::PostfixOp(o) => o.display(f, state, operators::DisplayContext::PostfixUsage),
             Self::Ellipsis => write!(f, "..."),
+            // FIXME: Add a recursion bound on pack indexing
+            Self::PackIndex(i) => {
+                write!(f, "...[")?;
+                i.display_impl(f, state)?;
+                write!(f, "]")
+            }
         }
     }
 }
@@ -712,6 +841,21 @@ mod tests {
         expected = ValueHeader::IdExpression(unwrap_parse(parser.parse_id_expression("MyValue")));
         check_value_header(&mut parser, "MyValue", expected, &["MyValue"]);
 
+        // Requires-expressions are not mistaken for named values
+        expected = ValueHeader::RequiresExpression(unwrap_parse(
+            parser.parse_requires_expression("requires { t.foo(); }"),
+        ));
+        check_value_header(
+            &mut parser,
+            "requires { t.foo(); }",
+            expected,
+            &[
+                "requires { … }",
+                "requires { t…; }",
+                "requires { t.foo(); }",
+            ],
+        );
+
         // Ellipsis (as in fold expressions)
         check_value_header(&mut parser, "...", ValueHeader::Ellipsis, &["..."]);
 
@@ -722,6 +866,11 @@ mod tests {
         // declval operator
         expected = ValueHeader::DeclVal(unwrap_parse(parser.parse_type_like("Lol")));
         check_value_header(&mut parser, "declval(Lol)", expected, &["declval(Lol)"]);
+
+        // noexcept operator
+        expected = ValueHeader::Noexcept(unwrap_parse(parser.parse_value_like("x", true, true)));
+        check_value_header(&mut parser, "noexcept(x)", expected, &["noexcept(x)"]);
+        check_value_header(&mut parser, "noexcept( x )", expected, &["noexcept(x)"]);
     }
 
     #[test]
@@ -769,6 +918,13 @@ mod tests {
         // Member access
         expected = AfterValue::MemberAccess(unwrap_parse(parser.parse_unqualified_id("lol")));
         check_after_value(&mut parser, ".lol", expected, &[".lol"]);
+        expected = AfterValue::MemberAccess(unwrap_parse(parser.parse_unqualified_id("get<0>")));
+        check_after_value(
+            &mut parser,
+            ".template get<0>",
+            expected,
+            &[".get<…>", ".get<0>"],
+        );
 
         // Postfix operator
         check_after_value(
@@ -784,6 +940,12 @@ mod tests {
 
         // Trailing ellipsis
         check_after_value(&mut parser, "...", AfterValue::Ellipsis, &["..."]);
+
+        // Pack indexing
+        expected = AfterValue::PackIndex(unwrap_parse(parser.parse_value_like("i", true, true)));
+        check_after_value(&mut parser, "...[i]", expected, &["...[i]"]);
+        expected = AfterValue::PackIndex(literal_value(&mut parser, "2"));
+        check_after_value(&mut parser, "... [ 2 ]", expected, &["...[2]"]);
     }
 
     #[test]
@@ -834,5 +996,18 @@ mod tests {
                 check_custom_display(parser.value_like(value_key), &["func…", "func(…)[666]", "func(3, 'x')[666]"]);
             }
         );
+        // Variable template specialization
+        assert_matches!(
+            parser.parse_value_like("std::is_integral_v<int>", false, false),
+            Ok((
+                "",
+                value_key
+            )) => {
+                let value = *parser.raw_value_like(value_key);
+                assert_eq!(value.header, ValueHeader::IdExpression(id_expression(&mut parser, "std::is_integral_v<int>")));
+                assert_eq!(parser.raw_value_trailer(value.trailer).len(), 0);
+                assert_eq!(parser.value_like(value_key).to_string(), "std::is_integral_v<int>");
+            }
+        );
     }
 }
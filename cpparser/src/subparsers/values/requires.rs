@@ -0,0 +1,590 @@
+//! Requires-expressions (as in `requires(T t) { t.size(); }`)
+//!
+//! See <https://en.cppreference.com/w/cpp/language/requires> for context.
+
+use crate::{
+    display::{CustomDisplay, DisplayState},
+    interning::slice::{SliceItemView, SliceView},
+    subparsers::{
+        names::atoms::{IdentifierKey, IdentifierView},
+        types::{TypeKey, TypeView},
+        values::{ValueKey, ValueView},
+    },
+    EntityParser, IResult,
+};
+use asylum::{lasso::MiniSpur, sequence::SequenceKey};
+use nom::Parser;
+use nom_supreme::ParserExt;
+use std::fmt::{self, Display, Formatter};
+
+#[cfg(test)]
+use reffers::ARef;
+
+/// Interned requirement sequence key
+///
+/// You can compare two keys as a cheaper alternative to comparing two
+/// requirement sequences as long as both keys were produced by the same
+/// EntityParser.
+///
+pub type RequirementsKey = SequenceKey<RequirementsKeyImpl, REQUIREMENTS_LEN_BITS>;
+type RequirementsKeyImpl = MiniSpur;
+const REQUIREMENTS_LEN_BITS: u32 = 6;
+//
+/// Interned requires-expression parameter list key
+///
+/// You can compare two keys as a cheaper alternative to comparing two
+/// parameter lists as long as both keys were produced by the same EntityParser.
+///
+pub type RequiresParametersKey =
+    SequenceKey<RequiresParametersKeyImpl, REQUIRES_PARAMETERS_LEN_BITS>;
+type RequiresParametersKeyImpl = MiniSpur;
+const REQUIRES_PARAMETERS_LEN_BITS: u32 = 6;
+//
+impl EntityParser {
+    /// Parser recognizing a requires-expression
+    pub fn parse_requires_expression<'source>(
+        &mut self,
+        s: &'source str,
+    ) -> IResult<'source, RequiresExpression> {
+        self.parse_requires_expression_imut(s)
+    }
+
+    /// Implementation of parse_requires_expression using internal mutability
+    pub(crate) fn parse_requires_expression_imut<'source>(
+        &self,
+        s: &'source str,
+    ) -> IResult<'source, RequiresExpression> {
+        use nom::{
+            character::complete::{char, multispace0},
+            combinator::opt,
+            multi::fold_many1,
+            sequence::preceded,
+        };
+        use nom_supreme::multi::parse_separated_terminated;
+
+        // Parameters are typed, and usually named
+        let parameter = (|s| self.parse_type_like_imut(s))
+            .terminated(multispace0)
+            .and(opt(|s| self.parse_identifier_imut(s)))
+            .map(|(ty, name)| RequiresParameter { ty, name });
+        let non_empty_parameters = parse_separated_terminated(
+            parameter,
+            multispace0.and(char(',')).and(multispace0),
+            multispace0.and(char(')')),
+            || self.requires_parameters.entry(),
+            |mut entry, item| {
+                entry.push(item);
+                entry
+            },
+        )
+        .map(|entry| entry.intern());
+        let empty_parameters = char(')').map(|_| self.requires_parameters.entry().intern());
+        let parameters = preceded(
+            char('(').and(multispace0),
+            non_empty_parameters.or(empty_parameters),
+        );
+
+        // Requirements are terminated by semicolons, within braces
+        let requirements = preceded(
+            char('{').and(multispace0),
+            fold_many1(
+                (|s| self.parse_requirement_imut(s)).terminated(multispace0),
+                || self.requirements.entry(),
+                |mut entry, item| {
+                    entry.push(item);
+                    entry
+                },
+            ),
+        )
+        .terminated(char('}'))
+        .map(|entry| entry.intern());
+
+        preceded(
+            Self::keyword_parser("requires").and(multispace0),
+            opt(parameters.terminated(multispace0)).and(requirements),
+        )
+        .map(|(parameters, requirements)| RequiresExpression {
+            parameters,
+            requirements,
+        })
+        .parse(s)
+    }
+
+    /// Access a previously parsed requires-expression
+    pub fn requires_expression(&self, r: RequiresExpression) -> RequiresExpressionView {
+        RequiresExpressionView::new(r, self)
+    }
+
+    /// Copy a previously parsed requires-expression into another parser
+    pub(crate) fn copy_requires_expression(
+        &self,
+        r: RequiresExpression,
+        dst: &Self,
+    ) -> RequiresExpression {
+        let parameters = r.parameters.map(|parameters| {
+            let mut entry = dst.requires_parameters.entry();
+            for &parameter in self.requires_parameters.get(parameters).iter() {
+                entry.push(RequiresParameter {
+                    ty: self.copy_type_like(parameter.ty, dst),
+                    name: parameter.name.map(|name| self.copy_identifier(name, dst)),
+                });
+            }
+            entry.intern()
+        });
+        let mut entry = dst.requirements.entry();
+        for &requirement in self.requirements.get(r.requirements).iter() {
+            entry.push(match requirement {
+                Requirement::Simple(value) => Requirement::Simple(self.copy_value_like(value, dst)),
+                Requirement::Type(ty) => Requirement::Type(self.copy_type_like(ty, dst)),
+                Requirement::Compound {
+                    expression,
+                    noexcept,
+                    constraint,
+                } => Requirement::Compound {
+                    expression: self.copy_value_like(expression, dst),
+                    noexcept,
+                    constraint: constraint.map(|ty| self.copy_type_like(ty, dst)),
+                },
+                Requirement::Nested(value) => Requirement::Nested(self.copy_value_like(value, dst)),
+            });
+        }
+        RequiresExpression {
+            parameters,
+            requirements: entry.intern(),
+        }
+    }
+
+    /// Total number of parameters across all interned requires-expressions
+    pub fn num_requires_parameters(&self) -> usize {
+        self.requires_parameters.borrow().num_items()
+    }
+
+    /// Maximal number of parameters in a single requires-expression
+    pub fn max_requires_parameters_len(&self) -> Option<usize> {
+        self.requires_parameters.borrow().max_sequence_len()
+    }
+
+    /// Total number of requirements across all interned requires-expressions
+    pub fn num_requirements(&self) -> usize {
+        self.requirements.borrow().num_items()
+    }
+
+    /// Maximal number of requirements in a single requires-expression
+    pub fn max_requirements_len(&self) -> Option<usize> {
+        self.requirements.borrow().max_sequence_len()
+    }
+
+    /// Retrieve the requirements of a previously parsed requires-expression
+    #[cfg(test)]
+    pub(crate) fn raw_requirements(&self, key: RequirementsKey) -> ARef<[Requirement]> {
+        self.requirements.get(key)
+    }
+
+    /// Parser recognizing a single requirement, including its trailing `;`
+    fn parse_requirement_imut<'source>(&self, s: &'source str) -> IResult<'source, Requirement> {
+        use nom::{
+            character::complete::{char, multispace0},
+            combinator::opt,
+            sequence::{delimited, preceded, tuple},
+        };
+        use nom_supreme::tag::complete::tag;
+
+        let value_like = |s| self.parse_value_like_imut(s, true, true);
+
+        // Nested requirements start with a requires keyword...
+        let nested = preceded(
+            Self::keyword_parser("requires").and(multispace0),
+            value_like,
+        )
+        .map(Requirement::Nested);
+
+        // ...type requirements start with a typename keyword...
+        let type_requirement = preceded(Self::keyword_parser("typename").and(multispace0), |s| {
+            self.parse_type_like_imut(s)
+        })
+        .map(Requirement::Type);
+
+        // ...compound requirements put the expression within braces...
+        let compound = tuple((
+            delimited(
+                char('{').and(multispace0),
+                value_like,
+                multispace0.and(char('}')),
+            ),
+            opt(multispace0.and(Self::keyword_parser("noexcept"))).map(|opt| opt.is_some()),
+            opt(preceded(multispace0.and(tag("->")).and(multispace0), |s| {
+                self.parse_type_like_imut(s)
+            })),
+        ))
+        .map(|(expression, noexcept, constraint)| Requirement::Compound {
+            expression,
+            noexcept,
+            constraint,
+        });
+
+        // ...and simple requirements are just expressions
+        let simple = value_like.map(Requirement::Simple);
+
+        nested
+            .or(type_requirement)
+            .or(compound)
+            .or(simple)
+            .terminated(multispace0.and(char(';')))
+            .parse(s)
+    }
+
+    /// Access a previously parsed list of requires-expression parameters
+    pub(crate) fn requires_parameters(&self, p: RequiresParametersKey) -> RequiresParametersView {
+        RequiresParametersView::new(p, self.requires_parameters.borrow(), self)
+    }
+
+    /// Access a previously parsed sequence of requirements
+    pub(crate) fn requirements(&self, r: RequirementsKey) -> RequirementsView {
+        RequirementsView::new(r, self.requirements.borrow(), self)
+    }
+}
+
+/// Requires-expression
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RequiresExpression {
+    /// Parameter list, if any
+    parameters: Option<RequiresParametersKey>,
+
+    /// Requirements
+    requirements: RequirementsKey,
+}
+
+/// View of a requires-expression
+pub struct RequiresExpressionView<'entities> {
+    /// Wrapped RequiresExpression
+    inner: RequiresExpression,
+
+    /// Underlying interned entity storage
+    entities: &'entities EntityParser,
+}
+//
+impl<'entities> RequiresExpressionView<'entities> {
+    /// Build a requires-expression view
+    pub(crate) fn new(inner: RequiresExpression, entities: &'entities EntityParser) -> Self {
+        Self { inner, entities }
+    }
+
+    /// Parameter list, if any
+    pub fn parameters(&self) -> Option<RequiresParametersView> {
+        self.inner
+            .parameters
+            .map(|p| self.entities.requires_parameters(p))
+    }
+
+    /// Requirements
+    pub fn requirements(&self) -> RequirementsView {
+        self.entities.requirements(self.inner.requirements)
+    }
+}
+//
+impl<'entities> PartialEq for RequiresExpressionView<'entities> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.entities, other.entities) && (self.inner == other.inner)
+    }
+}
+//
+impl<'entities> Display for RequiresExpressionView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for RequiresExpressionView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        self.parameters()
+            .recursion_depth()
+            .max(self.requirements().recursion_depth())
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        write!(f, "requires")?;
+        self.parameters().display_impl(f, state)?;
+        write!(f, " ")?;
+        self.requirements().display_impl(f, state)
+    }
+}
+
+/// View of the parameter list of a requires-expression
+pub type RequiresParametersView<'entities> = SliceView<
+    'entities,
+    RequiresParameter,
+    RequiresParameterView<'entities>,
+    RequiresParametersKey,
+>;
+
+/// Parameter of a requires-expression
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RequiresParameter {
+    /// Parameter type
+    ty: TypeKey,
+
+    /// Parameter name, if any
+    name: Option<IdentifierKey>,
+}
+
+/// View of a requires-expression parameter
+#[derive(PartialEq)]
+pub struct RequiresParameterView<'entities> {
+    /// Parameter type
+    pub ty: TypeView<'entities>,
+
+    /// Parameter name, if any
+    pub name: Option<IdentifierView<'entities>>,
+}
+//
+impl<'entities> Display for RequiresParameterView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for RequiresParameterView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        self.ty.recursion_depth()
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        self.ty.display_impl(f, state)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+        Ok(())
+    }
+}
+//
+impl<'entities> SliceItemView<'entities> for RequiresParameterView<'entities> {
+    type Inner = RequiresParameter;
+
+    fn new(inner: Self::Inner, entities: &'entities EntityParser) -> Self {
+        Self {
+            ty: entities.type_like(inner.ty),
+            name: inner.name.map(|name| entities.identifier(name)),
+        }
+    }
+
+    const DISPLAY_HEADER: &'static str = "(";
+
+    const DISPLAY_SEPARATOR: &'static str = ", ";
+
+    const DISPLAY_TRAILER: &'static str = ")";
+}
+
+/// View of the requirements of a requires-expression
+pub type RequirementsView<'entities> =
+    SliceView<'entities, Requirement, RequirementView<'entities>, RequirementsKey>;
+
+/// Requirement from a requires-expression
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Requirement {
+    /// Simple requirement (`expression;`)
+    Simple(ValueKey),
+
+    /// Type requirement (`typename T::type;`)
+    Type(TypeKey),
+
+    /// Compound requirement (`{ expression } noexcept -> constraint;`)
+    Compound {
+        /// Expression that must be valid
+        expression: ValueKey,
+
+        /// Truth that the expression must not throw
+        noexcept: bool,
+
+        /// Type constraint that the expression's type must satisfy, if any
+        constraint: Option<TypeKey>,
+    },
+
+    /// Nested requirement (`requires constraint;`)
+    Nested(ValueKey),
+}
+
+/// View of a requirement from a requires-expression
+#[derive(PartialEq)]
+pub enum RequirementView<'entities> {
+    /// Simple requirement (`expression;`)
+    Simple(ValueView<'entities>),
+
+    /// Type requirement (`typename T::type;`)
+    Type(TypeView<'entities>),
+
+    /// Compound requirement (`{ expression } noexcept -> constraint;`)
+    Compound {
+        /// Expression that must be valid
+        expression: ValueView<'entities>,
+
+        /// Truth that the expression must not throw
+        noexcept: bool,
+
+        /// Type constraint that the expression's type must satisfy, if any
+        constraint: Option<TypeView<'entities>>,
+    },
+
+    /// Nested requirement (`requires constraint;`)
+    Nested(ValueView<'entities>),
+}
+//
+impl<'entities> Display for RequirementView<'entities> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        self.display_impl(f, &DisplayState::default())
+    }
+}
+//
+impl<'entities> CustomDisplay for RequirementView<'entities> {
+    fn recursion_depth(&self) -> usize {
+        match self {
+            Self::Simple(v) | Self::Nested(v) => v.recursion_depth(),
+            Self::Type(t) => t.recursion_depth(),
+            Self::Compound {
+                expression,
+                constraint,
+                ..
+            } => expression
+                .recursion_depth()
+                .max(constraint.recursion_depth()),
+        }
+    }
+
+    fn display_impl(&self, f: &mut Formatter<'_>, state: &DisplayState) -> Result<(), fmt::Error> {
+        match self {
+            Self::Simple(v) => v.display_impl(f, state)?,
+            Self::Type(t) => {
+                write!(f, "typename ")?;
+                t.display_impl(f, state)?;
+            }
+            Self::Compound {
+                expression,
+                noexcept,
+                constraint,
+            } => {
+                write!(f, "{{ ")?;
+                expression.display_impl(f, state)?;
+                write!(f, " }}")?;
+                if *noexcept {
+                    write!(f, " noexcept")?;
+                }
+                if let Some(constraint) = constraint {
+                    write!(f, " -> ")?;
+                    constraint.display_impl(f, state)?;
+                }
+            }
+            Self::Nested(v) => {
+                write!(f, "requires ")?;
+                v.display_impl(f, state)?;
+            }
+        }
+        write!(f, ";")
+    }
+}
+//
+impl<'entities> SliceItemView<'entities> for RequirementView<'entities> {
+    type Inner = Requirement;
+
+    fn new(inner: Self::Inner, entities: &'entities EntityParser) -> Self {
+        match inner {
+            Requirement::Simple(v) => Self::Simple(entities.value_like(v)),
+            Requirement::Type(t) => Self::Type(entities.type_like(t)),
+            Requirement::Compound {
+                expression,
+                noexcept,
+                constraint,
+            } => Self::Compound {
+                expression: entities.value_like(expression),
+                noexcept,
+                constraint: constraint.map(|c| entities.type_like(c)),
+            },
+            Requirement::Nested(v) => Self::Nested(entities.value_like(v)),
+        }
+    }
+
+    const DISPLAY_HEADER: &'static str = "{ ";
+
+    const DISPLAY_SEPARATOR: &'static str = " ";
+
+    const DISPLAY_TRAILER: &'static str = " }";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{display::tests::check_custom_display, tests::unwrap_parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn requires_expression() {
+        let mut parser = EntityParser::new();
+        let value_like =
+            |parser: &mut EntityParser, s| unwrap_parse(parser.parse_value_like(s, true, true));
+        let type_like = |parser: &mut EntityParser, s| unwrap_parse(parser.parse_type_like(s));
+
+        // Simple requirement, without parameters
+        let expr = unwrap_parse(parser.parse_requires_expression("requires { t.foo(); }"));
+        assert_eq!(expr.parameters, None);
+        let expected = [Requirement::Simple(value_like(&mut parser, "t.foo()"))];
+        assert_eq!(&parser.raw_requirements(expr.requirements)[..], &expected);
+        check_custom_display(
+            parser.requires_expression(expr),
+            &[
+                "requires { … }",
+                "requires { t…; }",
+                "requires { t.foo(); }",
+            ],
+        );
+
+        // Compound requirement, with parameters
+        let expr = unwrap_parse(
+            parser.parse_requires_expression("requires(T t) { { t.size() } -> std::integral; }"),
+        );
+        assert_eq!(
+            parser
+                .requires_expression(expr)
+                .parameters()
+                .map(|p| p.to_string())
+                .as_deref(),
+            Some("(T t)")
+        );
+        let expected = [Requirement::Compound {
+            expression: value_like(&mut parser, "t.size()"),
+            noexcept: false,
+            constraint: Some(type_like(&mut parser, "std::integral")),
+        }];
+        assert_eq!(&parser.raw_requirements(expr.requirements)[..], &expected);
+        check_custom_display(
+            parser.requires_expression(expr),
+            &[
+                "requires(…) { … }",
+                "requires(T t) { { t… } -> …::integral; }",
+                "requires(T t) { { t.size() } -> std::integral; }",
+            ],
+        );
+
+        // All requirement kinds, with several parameters
+        let expr = unwrap_parse(parser.parse_requires_expression(
+            "requires (const T& a, T b) { a + b; typename T::value_type; \
+            {a.swap(b)} noexcept; requires std::is_same_v<T, int>; }",
+        ));
+        let expected = [
+            Requirement::Simple(value_like(&mut parser, "a + b")),
+            Requirement::Type(type_like(&mut parser, "T::value_type")),
+            Requirement::Compound {
+                expression: value_like(&mut parser, "a.swap(b)"),
+                noexcept: true,
+                constraint: None,
+            },
+            Requirement::Nested(value_like(&mut parser, "std::is_same_v<T, int>")),
+        ];
+        assert_eq!(&parser.raw_requirements(expr.requirements)[..], &expected);
+        assert_eq!(
+            parser.requires_expression(expr).to_string(),
+            "requires(const T& a, T b) { a + b; typename T::value_type; \
+            { a.swap(b) } noexcept; requires std::is_same_v<T, int>; }"
+        );
+
+        // Requirement sequences cannot be empty
+        assert!(parser.parse_requires_expression("requires { }").is_err());
+    }
+}
@@ -14,6 +14,11 @@ struct Args {
     #[clap(short = 'c', long = "cols")]
     max_cols: u16,
 
+    /// Accept legacy primitive type names with non-standard casing (e.g.
+    /// `Unsigned Int`)
+    #[clap(long)]
+    lenient: bool,
+
     /// C++ entity name to be simplified (can also be specified via stdin)
     entity: Option<String>,
 }
@@ -41,6 +46,7 @@ fn main() {
 
     // Parse C++ entity name
     let mut entities = EntityParser::new();
+    entities.set_lenient_legacy_names(args.lenient);
     let entity = entities
         .parse_entity(&entity)
         .expect("Failed to parse entity name");
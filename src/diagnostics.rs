@@ -0,0 +1,370 @@
+//! Diagnostics about C++ entities that could not be demangled or parsed
+
+use clang_time_trace::{
+    ActivityArgumentError, ActivityArgumentType, ClangTrace, ParsedActivityArgument, ParsedSymbol,
+};
+use std::{collections::HashMap, rc::Rc};
+
+/// Number of characters of unparsed input used to identify a failure pattern
+const PATTERN_CHARS: usize = 24;
+
+/// Activity arguments that could not be turned into a C++ entity, grouped by
+/// pattern of failure
+#[derive(Debug, Default)]
+pub struct ParseDiagnostics {
+    /// Parsing failures, indexed by failure pattern
+    patterns: HashMap<Box<str>, FailurePattern>,
+
+    /// Total number of parsing failures
+    num_failures: usize,
+}
+//
+impl ParseDiagnostics {
+    /// Check out all activity arguments of a trace which are C++ entities or
+    /// symbols, and record those that could not be parsed
+    pub fn collect(trace: &mut ClangTrace) -> Self {
+        let mut diagnostics = Self::default();
+        for_each_entity_arg(
+            trace,
+            |trace, activity_name, detail, parse_result| match parse_result {
+                Ok(ParsedActivityArgument::Symbol(symbol)) => {
+                    diagnostics.record_symbol(trace, activity_name, &symbol)
+                }
+                Ok(_) => {}
+                Err(error) => diagnostics.record_error(activity_name, detail, &error),
+            },
+        );
+        diagnostics
+    }
+
+    /// Record a C++ symbol, if it could not be parsed
+    pub fn record_symbol(&mut self, trace: &mut ClangTrace, activity: &str, symbol: &ParsedSymbol) {
+        match symbol {
+            ParsedSymbol::Parsed(_) => {}
+            ParsedSymbol::Demangled(demangled) => {
+                if let Err(error) = trace.parse_entity(demangled) {
+                    self.record_error(activity, demangled.clone(), &error)
+                }
+            }
+            ParsedSymbol::MaybeMangled(mangled) => {
+                // Compiler-generated symbols like __cxx_global_var_init.N
+                // should be grouped together regardless of their suffix
+                let prefix = mangled.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+                self.record(
+                    format!("not demangled: {prefix}").into(),
+                    activity,
+                    mangled.clone(),
+                    "failed to demangle symbol".into(),
+                )
+            }
+        }
+    }
+
+    /// Record a failure to parse an activity argument
+    pub fn record_error(&mut self, activity: &str, input: Rc<str>, error: &ActivityArgumentError) {
+        let pattern = match error {
+            ActivityArgumentError::BadCppEntity(e) => {
+                let unparsed = e.input.chars().take(PATTERN_CHARS).collect::<String>();
+                format!("{:?} error at {unparsed:?}", e.code)
+            }
+            other => other.to_string(),
+        };
+        self.record(pattern.into(), activity, input, error.to_string().into())
+    }
+
+    /// Record a parsing failure that matches a certain pattern
+    fn record(&mut self, pattern: Box<str>, activity: &str, input: Rc<str>, error: Box<str>) {
+        self.num_failures += 1;
+        self.patterns
+            .entry(pattern)
+            .and_modify(|stats| stats.count += 1)
+            .or_insert_with(|| FailurePattern {
+                count: 1,
+                activity: activity.into(),
+                example: input,
+                error,
+            });
+    }
+
+    /// Total number of parsing failures
+    pub fn num_failures(&self) -> usize {
+        self.num_failures
+    }
+
+    /// Number of distinct failure patterns
+    pub fn num_patterns(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Most common failure patterns, by decreasing number of occurences
+    pub fn top_patterns(&self, max_patterns: usize) -> Box<[(&str, &FailurePattern)]> {
+        let mut patterns = self
+            .patterns
+            .iter()
+            .map(|(pattern, stats)| (&**pattern, stats))
+            .collect::<Vec<_>>();
+        patterns.sort_unstable_by(|(pattern1, stats1), (pattern2, stats2)| {
+            stats2
+                .count
+                .cmp(&stats1.count)
+                .then_with(|| pattern1.cmp(pattern2))
+        });
+        patterns.truncate(max_patterns);
+        patterns.into_boxed_slice()
+    }
+}
+
+/// Pattern of C++ entity parsing failure
+#[derive(Debug, Eq, PartialEq)]
+pub struct FailurePattern {
+    /// Number of activity arguments that failed to parse in this way
+    pub count: usize,
+
+    /// Name of the activity where this failure was first observed
+    pub activity: Box<str>,
+
+    /// First activity argument that failed to parse in this way
+    pub example: Rc<str>,
+
+    /// Error that was emitted upon parsing this first argument
+    pub error: Box<str>,
+}
+
+/// Fraction of C++ entity and symbol arguments that could be parsed, broken
+/// down by activity type
+#[derive(Debug, Default)]
+pub struct ParseCoverage {
+    /// Coverage for each activity type, indexed by activity name
+    activities: HashMap<Box<str>, ActivityCoverage>,
+}
+//
+impl ParseCoverage {
+    /// Maximal number of failing inputs that are recorded per activity type
+    pub const MAX_FAILURES: usize = 3;
+
+    /// Try to parse every C++ entity and symbol argument of a trace
+    pub fn measure(trace: &mut ClangTrace) -> Self {
+        let mut coverage = Self::default();
+        for_each_entity_arg(trace, |_trace, activity_name, detail, parse_result| {
+            let parsed = match parse_result {
+                Ok(ParsedActivityArgument::CppEntity(_))
+                | Ok(ParsedActivityArgument::Symbol(ParsedSymbol::Parsed(_))) => true,
+                Ok(ParsedActivityArgument::Symbol(
+                    ParsedSymbol::Demangled(_) | ParsedSymbol::MaybeMangled(_),
+                ))
+                | Err(_) => false,
+                Ok(_) => return,
+            };
+            let activity = coverage.activities.entry(activity_name.into()).or_default();
+            activity.num_args += 1;
+            if parsed {
+                activity.num_parsed += 1;
+            } else if activity.failures.len() < Self::MAX_FAILURES {
+                activity.failures.push(detail);
+            }
+        });
+        coverage
+    }
+
+    /// Total number of C++ entity and symbol arguments
+    pub fn num_args(&self) -> usize {
+        self.activities.values().map(|a| a.num_args).sum()
+    }
+
+    /// Number of C++ entity and symbol arguments that could be parsed
+    pub fn num_parsed(&self) -> usize {
+        self.activities.values().map(|a| a.num_parsed).sum()
+    }
+
+    /// Fraction of C++ entity and symbol arguments that could be parsed
+    pub fn fraction(&self) -> f64 {
+        ActivityCoverage::ratio(self.num_parsed(), self.num_args())
+    }
+
+    /// Coverage of each activity type, by increasing coverage fraction
+    pub fn by_activity(&self) -> Box<[(&str, &ActivityCoverage)]> {
+        let mut activities = self
+            .activities
+            .iter()
+            .map(|(name, coverage)| (&**name, coverage))
+            .collect::<Box<[_]>>();
+        activities.sort_unstable_by(|(name1, coverage1), (name2, coverage2)| {
+            coverage1
+                .fraction()
+                .partial_cmp(&coverage2.fraction())
+                .expect("No NaNs expected in coverage fractions")
+                .then_with(|| name1.cmp(name2))
+        });
+        activities
+    }
+}
+
+/// Parse coverage for one activity type
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ActivityCoverage {
+    /// Number of C++ entity and symbol arguments
+    pub num_args: usize,
+
+    /// Number of arguments that could be parsed
+    pub num_parsed: usize,
+
+    /// First few arguments that could not be parsed
+    pub failures: Vec<Rc<str>>,
+}
+//
+impl ActivityCoverage {
+    /// Fraction of arguments that could be parsed
+    pub fn fraction(&self) -> f64 {
+        Self::ratio(self.num_parsed, self.num_args)
+    }
+
+    /// Coverage ratio, with the convention that nothing to parse means success
+    fn ratio(num_parsed: usize, num_args: usize) -> f64 {
+        if num_args == 0 {
+            1.0
+        } else {
+            num_parsed as f64 / num_args as f64
+        }
+    }
+}
+
+/// Parse the argument of every activity that features a C++ entity or symbol
+///
+/// The provided callback receives the trace, the activity name, the raw
+/// activity argument and the argument parsing result.
+///
+fn for_each_entity_arg(
+    trace: &mut ClangTrace,
+    mut callback: impl FnMut(
+        &mut ClangTrace,
+        &str,
+        Rc<str>,
+        Result<ParsedActivityArgument, ActivityArgumentError>,
+    ),
+) {
+    let activity_ids = trace
+        .all_activities()
+        .map(|activity_trace| activity_trace.id())
+        .collect::<Vec<_>>();
+    for id in activity_ids {
+        let activity_trace = trace.activity_trace(id);
+        let activity = activity_trace.activity();
+        let activity_name = Box::<str>::from(activity.name());
+        let raw_arg = activity.raw_argument().clone();
+        if !matches!(
+            raw_arg.arg_type(),
+            ActivityArgumentType::CppEntity
+                | ActivityArgumentType::Symbol
+                | ActivityArgumentType::SymbolOpt
+        ) {
+            continue;
+        }
+        let Some(detail) = raw_arg.detail() else {
+            continue;
+        };
+        let parse_result = raw_arg.parse(trace);
+        callback(trace, &activity_name, detail, parse_result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::with_test_trace;
+
+    #[test]
+    fn unparseable_symbol() {
+        const UNPARSEABLE: &str = "std::vector<int>::_M_realloc_insert<>(int*, &&)";
+        with_test_trace(|trace| {
+            let mut diagnostics = ParseDiagnostics::default();
+            diagnostics.record_symbol(
+                trace,
+                "LoopUnrollPass",
+                &ParsedSymbol::Demangled(UNPARSEABLE.into()),
+            );
+            diagnostics.record_symbol(
+                trace,
+                "PromotePass",
+                &ParsedSymbol::MaybeMangled("__cxx_global_var_init.1".into()),
+            );
+            diagnostics.record_symbol(
+                trace,
+                "SROAPass",
+                &ParsedSymbol::MaybeMangled("__cxx_global_var_init.2".into()),
+            );
+            assert_eq!(diagnostics.num_failures(), 3);
+
+            let top_patterns = diagnostics.top_patterns(usize::MAX);
+            assert_eq!(top_patterns.len(), 2);
+
+            let (pattern, stats) = &top_patterns[0];
+            assert_eq!(*pattern, "not demangled: __cxx_global_var_init");
+            assert_eq!(stats.count, 2);
+            assert_eq!(&*stats.activity, "PromotePass");
+            assert_eq!(&*stats.example, "__cxx_global_var_init.1");
+
+            let (pattern, stats) = &top_patterns[1];
+            assert!(pattern.contains("&&)"), "Unexpected pattern {pattern:?}");
+            assert_eq!(stats.count, 1);
+            assert_eq!(&*stats.activity, "LoopUnrollPass");
+            assert_eq!(&*stats.example, UNPARSEABLE);
+            assert!(stats.error.starts_with("failed to parse C++ entity"));
+
+            assert_eq!(diagnostics.top_patterns(1).len(), 1);
+        });
+    }
+
+    #[test]
+    fn collect() {
+        with_test_trace(|trace| {
+            let diagnostics = ParseDiagnostics::collect(trace);
+            let top_patterns = diagnostics.top_patterns(usize::MAX);
+            assert_eq!(
+                top_patterns
+                    .iter()
+                    .map(|(_pattern, stats)| stats.count)
+                    .sum::<usize>(),
+                diagnostics.num_failures()
+            );
+            assert!(top_patterns
+                .iter()
+                .any(|(_pattern, stats)| stats.example.contains("_M_realloc_insert")));
+        });
+    }
+
+    #[test]
+    fn coverage() {
+        with_test_trace(|trace| {
+            let coverage = ParseCoverage::measure(trace);
+            assert!(coverage.num_args() > 0);
+            assert!(coverage.num_parsed() <= coverage.num_args());
+            let fraction = coverage.fraction();
+            assert!(
+                (0.95..=1.0).contains(&fraction),
+                "Unexpected parse coverage {fraction}"
+            );
+
+            let by_activity = coverage.by_activity();
+            assert_eq!(
+                by_activity
+                    .iter()
+                    .map(|(_name, activity)| activity.num_args)
+                    .sum::<usize>(),
+                coverage.num_args()
+            );
+            for window in by_activity.windows(2) {
+                assert!(window[0].1.fraction() <= window[1].1.fraction());
+            }
+            for (_name, activity) in by_activity.iter() {
+                assert_eq!(
+                    activity.failures.len(),
+                    (activity.num_args - activity.num_parsed).min(ParseCoverage::MAX_FAILURES)
+                );
+            }
+            assert!(by_activity.iter().any(|(_name, activity)| activity
+                .failures
+                .iter()
+                .any(|failure| failure.starts_with("__cxx_global_var_init"))));
+        });
+    }
+}
@@ -3,11 +3,15 @@
 #![deny(missing_docs)]
 
 mod clang;
+mod diagnostics;
 mod trace;
 mod ui;
 
-use clap::{Parser, ValueEnum};
-use std::{io, path::PathBuf};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
 /// Analyze where your compilation time is spent in order to optimize it
 ///
@@ -22,6 +26,10 @@ use std::{io, path::PathBuf};
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 pub struct CliArgs {
+    /// Alternative operating mode
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Clang time-trace file to be analyzed
     ///
     /// If no input file is specified, will enter full-build profiling mode.
@@ -49,11 +57,84 @@ pub struct CliArgs {
 
     /// Hierarchical profile display threshold, as a percentage of total duration
     ///
-    /// Only used by the stdio user interface, the TUI displays everything.
+    /// The stdio user interface hides callees below this threshold. The TUI
+    /// displays everything, unless --fold-other is specified.
     ///
     #[clap(short = 'i', long, default_value = "0.5")]
     hierarchical_threshold: f32,
 
+    /// Collapse recursive activities in the hierarchical profile
+    ///
+    /// Chains of nested activities with identical names and arguments, as
+    /// produced by some deeply recursive processes, are then displayed as a
+    /// single node annotated with the recursion depth.
+    ///
+    /// Only used by the stdio user interface.
+    ///
+    #[clap(long)]
+    collapse_recursion: bool,
+
+    /// Fold callees below the threshold in the hierarchical profile
+    ///
+    /// Instead of merely reporting how many callees of an activity are below
+    /// the hierarchical profile display threshold, display them as a single
+    /// "(other)" node whose duration is the sum of their durations.
+    ///
+    /// In the TUI, callees are folded when zooming on an activity.
+    ///
+    #[clap(long)]
+    fold_other: bool,
+
+    /// Abbreviate activity types in the flat profile
+    ///
+    /// Instead of repeating the full activity type name on every line of the
+    /// flat profile, print a legend mapping each activity type to a short
+    /// code (e.g. `IC = InstantiateClass`) once, then use these codes.
+    ///
+    /// Only used by the stdio user interface.
+    ///
+    #[clap(long)]
+    kind_legend: bool,
+
+    /// Display durations as a number of microseconds with grouped digits
+    ///
+    /// By default, durations are scaled to a human-readable unit. With this
+    /// option, they are instead displayed as a number of microseconds whose
+    /// digits are grouped by thousands using the specified separator, e.g.
+    /// `--micros ,` displays "1,234,567µs".
+    ///
+    /// Only used by the stdio user interface.
+    ///
+    #[clap(long, value_name = "SEPARATOR")]
+    micros: Option<char>,
+
+    /// Display file paths relative to this directory
+    ///
+    /// Paths of files which are outside of this directory, such as system
+    /// headers, are still displayed in full. Relative paths are resolved
+    /// against the working directory.
+    ///
+    #[clap(long, value_name = "DIR")]
+    base_dir: Option<PathBuf>,
+
+    /// Turn file paths into clickable hyperlinks to the corresponding file
+    ///
+    /// This uses OSC 8 terminal escape sequences, which are not understood by
+    /// every terminal emulator and would clutter reports saved to a file.
+    ///
+    /// Only used by the stdio user interface.
+    ///
+    #[clap(long)]
+    hyperlink_paths: bool,
+
+    /// Write the report to a file instead of standard output
+    ///
+    /// Only used by the stdio user interface, the TUI always uses the
+    /// terminal.
+    ///
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
     /// Maximal number of terminal columns to be used in the display
     ///
     /// Only used by the stdio user interface, the TUI detects the screen width.
@@ -77,6 +158,42 @@ pub struct CliArgs {
     #[clap(short = 'g', long = "granularity")]
     time_trace_granularity: Option<u64>,
 
+    /// Report C++ symbols and entities which could not be parsed
+    ///
+    /// In addition to the usual profile, display a summary of the most common
+    /// patterns of C++ symbols that could not be demangled, and of C++ entity
+    /// names that could not be parsed. This is useful when investigating why
+    /// some activity arguments are displayed in a suboptimal way.
+    ///
+    /// Only used by the stdio user interface.
+    ///
+    #[clap(short, long)]
+    verbose: bool,
+
+    /// Report C++ parser coverage instead of profiling
+    ///
+    /// Try to parse every C++ entity and symbol from the input time-trace
+    /// file, then report which fraction of them could be parsed, for each
+    /// activity type, along with a few inputs that could not be parsed.
+    ///
+    /// This is a non-interactive report, which always uses stdio.
+    ///
+    #[clap(long)]
+    coverage: bool,
+
+    /// Report the compilation phases as a single line of metrics
+    ///
+    /// Instead of profiling, emit one line of `key=value` pairs giving the
+    /// time spent in each top-level compilation phase and in the whole
+    /// compilation, in microseconds (e.g. `frontend_us=1234 backend_us=567
+    /// total_us=1801`). Keys are stable, which makes this easy to grep or
+    /// ingest into a dashboard in order to track build times across CI runs.
+    ///
+    /// This is a non-interactive report, which always uses stdio.
+    ///
+    #[clap(long)]
+    phase_metrics: bool,
+
     /// Path to the full-build profile
     ///
     /// This is used in full-build profiling mode to directly display the build
@@ -92,6 +209,23 @@ pub struct CliArgs {
     build_profile: Option<PathBuf>,
 }
 //
+/// Alternative operating modes
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Profile all compilation units of a build directory together
+    ///
+    /// Load the compilation database of a CMake build directory, measure the
+    /// time-trace of every compilation unit if it does not exist or is
+    /// outdated, then display the hottest activities across the whole build.
+    ///
+    /// This is a non-interactive report, which always uses stdio.
+    ///
+    Aggregate {
+        /// Build directory (defaults to the working directory)
+        build_dir: Option<PathBuf>,
+    },
+}
+//
 /// Select desired user interface
 #[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
 pub enum UI {
@@ -107,7 +241,14 @@ pub enum UI {
 
 fn main() {
     // Set up infrastructure and process CLI arguments
-    let args = CliArgs::parse();
+    let mut args = CliArgs::parse();
+    args.base_dir = args.base_dir.as_deref().map(absolute_dir);
+    if let Some(Command::Aggregate { .. }) = args.command {
+        return ui::stdio::run_aggregate(args);
+    }
+    if args.coverage || args.phase_metrics {
+        return ui::stdio::run(args);
+    }
     match args.ui {
         UI::Auto => {
             if termion::is_tty(&io::stdin()) && termion::is_tty(&io::stdout()) {
@@ -121,10 +262,44 @@ fn main() {
     }
 }
 
+/// Turn a directory path into an absolute path, so that it can be compared
+/// with the absolute file paths found in clang time-traces
+///
+/// Symlinks and `..` components are resolved if the directory exists,
+/// otherwise the path is merely made relative to the working directory.
+///
+fn absolute_dir(dir: &Path) -> PathBuf {
+    fs::canonicalize(dir).unwrap_or_else(|_| {
+        env::current_dir()
+            .map(|current_dir| current_dir.join(dir))
+            .unwrap_or_else(|_| dir.to_owned())
+    })
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use clang_time_trace::ClangTrace;
-    use std::{cell::OnceCell, cell::RefCell, str::FromStr};
+    use std::{cell::OnceCell, cell::RefCell, env, path::Path, str::FromStr};
+
+    #[test]
+    fn absolute_dir() {
+        // Existing relative directories are resolved
+        let current_dir = env::current_dir().unwrap();
+        assert_eq!(super::absolute_dir(Path::new(".")), current_dir);
+        assert_eq!(
+            super::absolute_dir(Path::new("src/../tests")),
+            current_dir.join("tests")
+        );
+
+        // Missing relative directories are made relative to the working dir
+        assert_eq!(
+            super::absolute_dir(Path::new("no/such/dir")),
+            current_dir.join("no/such/dir")
+        );
+
+        // Absolute directories are kept as is
+        assert_eq!(super::absolute_dir(Path::new("/")), Path::new("/"));
+    }
 
     // Reference ClangTrace used by all tests which need one
     //
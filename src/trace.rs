@@ -1,7 +1,42 @@
 //! Analysis used in the display of compilation profiles
 
-use clang_time_trace::{ActivityTrace, ClangTrace, Duration};
-use std::collections::HashMap;
+use clang_time_trace::{ActivityId, ActivityTrace, ActivityTraceId, ClangTrace, Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+/// Summary of the trace properties that most displays need
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceSummary {
+    /// Name of the clang process
+    pub process_name: Box<str>,
+
+    /// Total duration of the root activities
+    pub root_duration: Duration,
+
+    /// Number of activities in the trace
+    pub activity_count: usize,
+
+    /// Number of threads which performed activities
+    pub thread_count: usize,
+}
+//
+impl TraceSummary {
+    /// Summarize a clang trace
+    pub fn new(trace: &ClangTrace) -> Self {
+        Self {
+            process_name: trace.process_name().into(),
+            root_duration: trace.root_activities().map(|root| root.duration()).sum(),
+            activity_count: trace.activity_count(),
+            thread_count: trace
+                .all_activities()
+                .map(|activity| activity.tid())
+                .collect::<HashSet<_>>()
+                .len(),
+        }
+    }
+}
 
 /// Given a set of root nodes, compute the associated duration norm used to go
 /// from absolute child durations to percentages of the root durations
@@ -12,19 +47,87 @@ pub fn duration_norm<'a>(roots: impl Iterator<Item = ActivityTrace<'a>>) -> Dura
 
 /// Breakdown of self-duration by activity type, ordered by decreasing duration
 pub fn activity_type_breakdown(trace: &ClangTrace) -> Box<[(Box<str>, Duration)]> {
-    let mut profile = HashMap::<Box<str>, Duration>::new();
+    let mut profile = HashMap::<ActivityId, Duration>::new();
     for activity_trace in trace.all_activities() {
         *profile
-            .entry(activity_trace.activity().name().into())
+            .entry(activity_trace.activity().id().clone())
             .or_default() += activity_trace.self_duration();
     }
-    let mut profile = profile.into_iter().collect::<Box<[_]>>();
+    let mut profile = profile
+        .into_iter()
+        .map(|(id, duration)| (Box::<str>::from(id.name()), duration))
+        .collect::<Box<[_]>>();
     profile.sort_unstable_by(|(_, d1), (_, d2)| {
         d2.partial_cmp(d1).expect("No NaNs expected in time-trace")
     });
     profile
 }
 
+/// Self-duration of one kind of activity, summed across several traces
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateActivity {
+    /// Name of the activity
+    pub name: Box<str>,
+
+    /// Raw activity argument (file, symbol...), if aggregating by argument
+    pub detail: Option<Rc<str>>,
+
+    /// Self-duration summed across all traces
+    pub self_duration: Duration,
+
+    /// Contribution of each trace where this activity occurs, in input order,
+    /// identified by the name of the clang process that produced the trace
+    pub per_trace: Box<[(Box<str>, Duration)]>,
+}
+
+/// Breakdown of self-duration by activity type across multiple traces (e.g.
+/// one per translation unit), ordered by decreasing duration
+///
+/// If `by_detail` is set, activities are further broken down by raw argument
+/// (file path, symbol name...), which does not require parsing arguments.
+///
+pub fn aggregate_breakdown<'a>(
+    traces: impl IntoIterator<Item = &'a ClangTrace>,
+    by_detail: bool,
+) -> Box<[AggregateActivity]> {
+    // Only self-durations are summed, so traces need not share a timeline
+    let mut profile = HashMap::<_, (Duration, Vec<(Box<str>, Duration)>)>::new();
+    for trace in traces {
+        let mut trace_profile = HashMap::<_, Duration>::new();
+        for activity_trace in trace.all_activities() {
+            let activity = activity_trace.activity();
+            let detail = by_detail
+                .then(|| activity.raw_argument().detail())
+                .flatten();
+            *trace_profile
+                .entry((activity.id().clone(), detail))
+                .or_default() += activity_trace.self_duration();
+        }
+        for (key, self_duration) in trace_profile {
+            let (total, per_trace) = profile.entry(key).or_default();
+            *total += self_duration;
+            per_trace.push((trace.process_name().into(), self_duration));
+        }
+    }
+    let mut profile = profile
+        .into_iter()
+        .map(
+            |((id, detail), (self_duration, per_trace))| AggregateActivity {
+                name: id.name().into(),
+                detail,
+                self_duration,
+                per_trace: per_trace.into(),
+            },
+        )
+        .collect::<Box<[_]>>();
+    profile.sort_unstable_by(|a1, a2| {
+        a2.self_duration
+            .partial_cmp(&a1.self_duration)
+            .expect("No NaNs expected in time-trace")
+    });
+    profile
+}
+
 /// Extract the hottest activities from an activity iterator
 ///
 /// - `duration` is the sorting criterion (can be duration(), self_duration(),
@@ -47,11 +150,99 @@ pub fn hottest_activities<'activities>(
     children
 }
 
+/// Aggregate of activities that were folded into a single "(other)" node
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FoldedActivities {
+    /// Number of folded activities
+    pub count: usize,
+
+    /// Sum of the durations of the folded activities
+    pub duration: Duration,
+
+    /// Sum of the self-durations of the folded activities
+    pub self_duration: Duration,
+}
+
+/// Like `hottest_activities()`, but also fold the activities that are below
+/// the duration threshold into an aggregate, if there are any
+///
+/// This is typically applied to the direct children of an activity in order
+/// to summarize the long tail of tiny callees that most traces feature.
+///
+pub fn fold_activities<'activities>(
+    activities: impl Iterator<Item = ActivityTrace<'activities>> + Clone,
+    mut duration: impl FnMut(&ActivityTrace) -> Duration,
+    threshold: Duration,
+) -> (Box<[ActivityTrace<'activities>]>, Option<FoldedActivities>) {
+    let hottest = hottest_activities(activities.clone(), &mut duration, threshold);
+    let folded = activities.filter(|a| duration(a) < threshold).fold(
+        FoldedActivities::default(),
+        |mut acc, a| {
+            acc.count += 1;
+            acc.duration += a.duration();
+            acc.self_duration += a.self_duration();
+            acc
+        },
+    );
+    (hottest, (folded.count > 0).then_some(folded))
+}
+
+/// Chain of nested activities with identical names and arguments
+///
+/// Deeply recursive processes can produce long chains of activities whose only
+/// child is the same activity again. Such chains are best displayed as a single
+/// node, whose children are those of the innermost activity of the chain.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RecursionChain {
+    /// Innermost activity of the chain
+    pub inner: ActivityTraceId,
+
+    /// Number of activities in the chain (1 if there is no recursion)
+    pub depth: usize,
+
+    /// Time spent processing the chain (duration of the outermost activity)
+    pub duration: Duration,
+
+    /// Time specifically spent in the activities of the chain
+    pub self_duration: Duration,
+}
+
+/// Find the chain of recursive activities that starts at a certain activity
+pub fn recursion_chain(trace: &ClangTrace, outer: ActivityTraceId) -> RecursionChain {
+    let outer = trace.activity_trace(outer);
+    let mut chain = RecursionChain {
+        inner: outer.id(),
+        depth: 1,
+        duration: outer.duration(),
+        self_duration: outer.self_duration(),
+    };
+    loop {
+        // Only collapse an activity whose single child is the same activity
+        let inner = trace.activity_trace(chain.inner);
+        let mut children = inner.direct_children();
+        let child = match (children.next(), children.next()) {
+            (Some(child), None)
+                if child.activity().id() == inner.activity().id()
+                    && child.activity().raw_argument() == inner.activity().raw_argument() =>
+            {
+                child
+            }
+            _ => return chain,
+        };
+        chain.inner = child.id();
+        chain.depth += 1;
+        chain.self_duration += child.self_duration();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::with_test_trace;
+    use assert_matches::assert_matches;
     use clang_time_trace::{MICROSECOND, MILLISECOND, SECOND};
+    use std::str::FromStr;
 
     fn assert_close(actual: Duration, reference: Duration) {
         assert!((actual - reference).abs() < 0.01 * reference.abs());
@@ -67,6 +258,194 @@ mod tests {
         });
     }
 
+    #[test]
+    fn phase_breakdown() {
+        with_test_trace(|trace| {
+            let phases = trace.phase_breakdown();
+            assert_eq!(
+                phases.iter().map(|(id, _)| id.name()).collect::<Vec<_>>(),
+                ["Frontend", "Backend"]
+            );
+            assert_close(phases[0].1.duration, 2.63 * SECOND);
+            assert_close(phases[1].1.duration, 1.11 * SECOND);
+
+            // Phases account for nearly all of the compiler's execution time
+            let total_fraction = phases.iter().map(|(_, phase)| phase.fraction).sum();
+            assert_close(total_fraction, 1.0);
+            let total_duration = phases.iter().map(|(_, phase)| phase.duration).sum();
+            assert_close(total_duration, 3.77 * SECOND);
+        });
+    }
+
+    #[test]
+    fn find_activities() {
+        with_test_trace(|trace| {
+            let instantiations = trace
+                .find_activities("InstantiateClass")
+                .collect::<Vec<_>>();
+            assert!(!instantiations.is_empty());
+            assert!(instantiations
+                .iter()
+                .all(|activity| *activity.activity().id() == ActivityId::InstantiateClass));
+            assert_eq!(
+                instantiations.len(),
+                trace
+                    .all_activities()
+                    .filter(|activity| activity.activity().name() == "InstantiateClass")
+                    .count()
+            );
+            assert_eq!(
+                trace
+                    .find_first("InstantiateClass")
+                    .map(|activity| activity.id()),
+                instantiations.first().map(|activity| activity.id())
+            );
+
+            assert_eq!(trace.find_activities("NotAnActivity").count(), 0);
+            assert!(trace.find_first("NotAnActivity").is_none());
+        });
+    }
+
+    #[test]
+    fn descendants() {
+        with_test_trace(|trace| {
+            for root in trace.root_activities() {
+                // Descendants are the same activities as all_children()...
+                let mut descendants = root.descendants().map(|d| d.id()).collect::<Vec<_>>();
+                let mut all_children = root.all_children().map(|c| c.id()).collect::<Vec<_>>();
+                assert_eq!(descendants.len(), all_children.len());
+                descendants.sort_unstable();
+                all_children.sort_unstable();
+                assert_eq!(descendants, all_children);
+
+                // ...in pre-order, with depths relative to the root
+                let mut depths = HashMap::from([(root.id(), 0)]);
+                for (descendant, depth) in root.descendants_with_depth() {
+                    let parent = descendant.parent().expect("Descendants have a parent");
+                    assert_eq!(depth, depths[&parent.id()] + 1);
+                    depths.insert(descendant.id(), depth);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn fanout_histogram() {
+        with_test_trace(|trace| {
+            let histogram = trace.fanout_histogram();
+            assert_eq!(histogram.values().sum::<usize>(), trace.activity_count());
+
+            // Leaves are the most common kind of activity
+            let leaves = histogram[&0];
+            assert_eq!(
+                leaves,
+                trace.all_activities().filter(|a| a.is_leaf()).count()
+            );
+            assert!(histogram.values().all(|&count| count <= leaves));
+
+            // Each activity but the roots is the child of another activity
+            let num_children = histogram
+                .iter()
+                .map(|(&fanout, &count)| fanout * count)
+                .sum::<usize>();
+            assert_eq!(
+                num_children,
+                trace.activity_count() - trace.root_activities().count()
+            );
+        });
+    }
+
+    #[test]
+    fn critical_path() {
+        with_test_trace(|trace| {
+            let path = trace
+                .critical_path()
+                .iter()
+                .map(|&id| trace.activity_trace(id))
+                .collect::<Vec<_>>();
+            assert!(!path.is_empty());
+
+            // The path goes from a root down to a leaf through direct children
+            assert!(path[0].parent().is_none());
+            assert!(path.last().unwrap().is_leaf());
+            for pair in path.windows(2) {
+                let parent = pair[1].parent().map(|parent| parent.id());
+                assert_eq!(parent, Some(pair[0].id()));
+            }
+
+            // Nested activities cannot last longer than their parent
+            for pair in path.windows(2) {
+                assert!(pair[1].duration() <= pair[0].duration());
+            }
+            assert_close(path[0].duration(), 3.77 * SECOND);
+        });
+    }
+
+    #[test]
+    fn trace_summary() {
+        with_test_trace(|trace| {
+            let summary = TraceSummary::new(trace);
+            assert_eq!(&*summary.process_name, "clang-14.0.5");
+            assert_close(summary.root_duration, 3.77 * SECOND);
+            assert_eq!(summary.activity_count, 3402);
+            assert_eq!(summary.thread_count, 1);
+        });
+    }
+
+    #[test]
+    fn multi_thread_trace_summary() {
+        // Build a trace where a second thread works alongside the main thread
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        { "ph": "X", "pid": 42, "tid": 43, "ts": 30, "dur": 50, "name": "Backend" },
+        { "ph": "X", "pid": 42, "tid": 42, "ts": 11, "dur": 40, "name": "Frontend" },
+        { "ph": "X", "pid": 42, "tid": 42, "ts": 10, "dur": 60, "name": "ExecuteCompiler" },
+        { "ph": "M", "pid": 42, "tid": 42, "ts": 0, "cat": "", "name": "process_name", "args": { "name": "clang-14.0.0" } }
+    ]
+}"#,
+        )
+        .expect("This is a known-good parse which should not fail");
+        let summary = TraceSummary::new(&trace);
+        assert_eq!(summary.activity_count, 3);
+        assert_eq!(summary.thread_count, 2);
+        assert_close(summary.root_duration, 110.0);
+    }
+
+    #[test]
+    fn duration_only_pass() {
+        with_test_trace(|trace| {
+            // Summaries and flat profiles do not need activity arguments...
+            TraceSummary::new(trace);
+            super::activity_type_breakdown(trace);
+            super::hottest_activities(
+                trace.all_activities(),
+                |activity| activity.self_duration(),
+                0.0,
+            );
+            assert_eq!(trace.parsed_argument_count(), 0);
+
+            // ...which are only parsed on demand
+            let root = trace.root_activities().next().unwrap();
+            let raw_arg = root.activity().raw_argument().clone();
+            raw_arg.parse(trace).unwrap();
+            assert_eq!(trace.parsed_argument_count(), 1);
+        });
+    }
+
+    #[test]
+    fn referenced_files() {
+        with_test_trace(|trace| {
+            let files = trace
+                .referenced_files()
+                .map(|path| path.to_string())
+                .collect::<Vec<_>>();
+            let unique = files.iter().collect::<HashSet<_>>();
+            assert_eq!(unique.len(), files.len());
+            assert!(files.iter().any(|file| file == "/usr/include/features.h"));
+        });
+    }
+
     #[test]
     fn activity_type_breakdown() {
         let expected = [
@@ -133,6 +512,39 @@ mod tests {
         });
     }
 
+    #[test]
+    fn aggregate_breakdown() {
+        with_test_trace(|trace| {
+            let trace = &*trace;
+
+            // Aggregating two copies of a trace doubles durations
+            let single = super::activity_type_breakdown(trace);
+            let aggregate = super::aggregate_breakdown([trace, trace], false);
+            assert_eq!(aggregate.len(), single.len());
+            for (single_name, single_duration) in single.iter() {
+                let aggregate = aggregate.iter().find(|a| &a.name == single_name).unwrap();
+                assert_eq!(aggregate.detail, None);
+                assert_close(aggregate.self_duration, 2.0 * single_duration);
+                assert_eq!(aggregate.per_trace.len(), 2);
+                for (process_name, duration) in aggregate.per_trace.iter() {
+                    assert_eq!(&**process_name, "clang-14.0.5");
+                    assert_close(*duration, *single_duration);
+                }
+            }
+
+            // Breaking down by argument preserves the total duration
+            let by_detail = super::aggregate_breakdown([trace, trace], true);
+            assert!(by_detail.len() > aggregate.len());
+            assert!(by_detail
+                .iter()
+                .any(|a| a.detail.as_deref() == Some("/usr/include/features.h")));
+            assert_close(
+                by_detail.iter().map(|a| a.self_duration).sum(),
+                aggregate.iter().map(|a| a.self_duration).sum(),
+            );
+        });
+    }
+
     #[test]
     fn hottest_activities() {
         let expected = [
@@ -162,4 +574,95 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn fold_activities() {
+        with_test_trace(|trace| {
+            let root = trace.root_activities().next().unwrap();
+            let frontend = root
+                .direct_children()
+                .find(|child| child.activity().name() == "Frontend")
+                .unwrap();
+            let threshold = 10.0 * MILLISECOND;
+            let (kept, folded) =
+                super::fold_activities(frontend.direct_children(), |a| a.duration(), threshold);
+
+            // Children are either kept or folded, depending on their duration
+            let folded = folded.unwrap();
+            assert!(kept.iter().all(|child| child.duration() >= threshold));
+            assert_eq!(
+                kept.len() + folded.count,
+                frontend.direct_children().count()
+            );
+
+            // The aggregate preserves durations of folded children
+            let expected = frontend
+                .direct_children()
+                .filter(|child| child.duration() < threshold)
+                .map(|child| child.duration())
+                .sum::<Duration>();
+            assert_eq!(folded.duration, expected);
+            assert_close(
+                kept.iter().map(|child| child.duration()).sum::<Duration>() + folded.duration,
+                frontend.duration() - frontend.self_duration(),
+            );
+
+            // Nothing is folded when all children are above the threshold
+            let (kept, folded) =
+                super::fold_activities(frontend.direct_children(), |a| a.duration(), 0.0);
+            assert_eq!(kept.len(), frontend.direct_children().count());
+            assert_eq!(folded, None);
+        });
+    }
+
+    #[test]
+    fn recursion_chain() {
+        // Build a trace where f<int> recursively instantiates itself, except at
+        // the deepest level of recursion where it instantiates g<int>.
+        let trace = ClangTrace::from_str(
+            r#"{
+    "traceEvents": [
+        { "ph": "X", "pid": 1, "tid": 0, "ts": 15, "dur": 10, "name": "InstantiateFunction", "args": { "detail": "g<int>" } },
+        { "ph": "X", "pid": 1, "tid": 0, "ts": 14, "dur": 20, "name": "InstantiateFunction", "args": { "detail": "f<int>" } },
+        { "ph": "X", "pid": 1, "tid": 0, "ts": 13, "dur": 40, "name": "InstantiateFunction", "args": { "detail": "f<int>" } },
+        { "ph": "X", "pid": 1, "tid": 0, "ts": 12, "dur": 80, "name": "InstantiateFunction", "args": { "detail": "f<int>" } },
+        { "ph": "X", "pid": 1, "tid": 0, "ts": 11, "dur": 90, "name": "Frontend" },
+        { "ph": "X", "pid": 1, "tid": 0, "ts": 10, "dur": 100, "name": "ExecuteCompiler" },
+        { "ph": "M", "pid": 1, "tid": 0, "ts": 0, "cat": "", "name": "process_name", "args": { "name": "clang-14.0.0" } }
+    ]
+}"#,
+        )
+        .expect("This is a known-good parse which should not fail");
+        let root = trace.root_activities().next().unwrap();
+        let frontend = root.direct_children().next().unwrap();
+        let outer_f = frontend.direct_children().next().unwrap();
+
+        // Non-recursive activities are not collapsed
+        assert_eq!(
+            super::recursion_chain(&trace, frontend.id()),
+            RecursionChain {
+                inner: frontend.id(),
+                depth: 1,
+                duration: frontend.duration(),
+                self_duration: frontend.self_duration(),
+            }
+        );
+
+        // Recursive activities are collapsed, with durations preserved
+        let chain = super::recursion_chain(&trace, outer_f.id());
+        assert_eq!(chain.depth, 3);
+        assert_eq!(chain.duration, outer_f.duration());
+        assert_close(chain.self_duration, 70.0);
+        let inner = trace.activity_trace(chain.inner);
+        assert_close(
+            chain.self_duration + inner.duration() - inner.self_duration(),
+            chain.duration,
+        );
+        let mut inner_children = inner.direct_children();
+        assert_matches!(inner_children.next(), Some(child) => {
+            assert_eq!(child.activity().name(), "InstantiateFunction");
+            assert_eq!(child.duration(), 10.0);
+        });
+        assert_eq!(inner_children.next(), None);
+    }
 }
@@ -1,11 +1,40 @@
 //! Utilities for displaying clang activities
 
-use super::DisplayConfig;
-use clang_time_trace::{ActivityArgument, ActivityId, CustomDisplay, DisplayState, Symbol};
-use std::io;
+use super::{
+    duration::{DurationFormat, TraceDuration},
+    DisplayConfig,
+};
+use clang_time_trace::{
+    ActivityArgument, ActivityId, ClangTrace, CustomDisplay, DisplayState, Duration, EntityView,
+    ParsedActivityArgument, ParsedSymbol, SharedEntity, Symbol,
+};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+/// Optional features of activity displays
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ActivityDisplayOptions {
+    /// Turn file paths into OSC 8 hyperlinks to the corresponding file
+    ///
+    /// Hyperlink escape sequences do not take up any terminal column, so they
+    /// are not accounted for in column budgets. Only enable this when the
+    /// output goes to a terminal that supports them.
+    ///
+    pub hyperlink_paths: bool,
+
+    /// Display file paths relative to this directory (typically the build or
+    /// source root of the project being profiled)
+    ///
+    /// Paths outside of this directory are displayed in full. Hyperlinks, if
+    /// enabled, always point to the full path.
+    ///
+    pub base_dir: Option<PathBuf>,
+}
+
 /// Try to display an activity's name and argument in finite space
 ///
 /// If successful, returns truth that horizontal text wrapping should be enabled
@@ -21,80 +50,376 @@ pub fn display_activity_desc(
     mut output: impl io::Write,
     activity_id: &ActivityId,
     activity_arg: &ActivityArgument,
-    mut config: DisplayConfig,
+    config: DisplayConfig,
+    options: ActivityDisplayOptions,
 ) -> Result<bool, ActivityDescError> {
-    let activity_name = activity_id.name();
-    let has_argument = *activity_arg != ActivityArgument::Nothing;
-    let paren_width = '('.width().expect("Not a control char");
-    debug_assert_eq!(Some(paren_width), ')'.width());
-
-    // Can we display at least ActivityName + (…) if there are parameters?
-    // FIXME: Remove allow-directive once pretty printing is implemented
-    #[allow(unused_mut)]
-    let mut should_wrap = match &mut config {
-        // In the single-line case, this is only true if there are enough
-        // terminal columns available, otherwise we error out
+    // Single-line displays are handled by one-shot descriptions, where C++
+    // entities are only rendered at the width that is left for them
+    let (tot_cols, header_cols, trailer_cols) = match config {
         DisplayConfig::SingleLine { max_cols } => {
-            if usize::from(*max_cols) < activity_name.width() + 3 * (has_argument as usize) {
-                return Err(ActivityDescError::NotEnoughCols(*max_cols));
-            } else {
-                write!(output, "{activity_name}")?;
-                *max_cols =
-                    max_cols.saturating_sub((activity_name.width() + 2 * paren_width) as u16);
-            }
-            false
+            let name = activity_id.name();
+            let arg_cols = max_cols.saturating_sub(name.width() as u16 + 2);
+            let desc = ActivityDesc {
+                name: name.into(),
+                argument: ArgumentDesc::one_shot(activity_arg, arg_cols),
+            };
+            desc.display(output, max_cols, options)?;
+            return Ok(false);
         }
-
-        // In the multi-line case this is always true, we only need to update
-        // the header/trailer col count for the argument display (if any)
         DisplayConfig::MultiLine {
+            tot_cols,
             header_cols,
             trailer_cols,
-            ..
-        } => {
-            write!(output, "{activity_name}")?;
-            *header_cols += (activity_name.width() + paren_width) as u16;
-            *trailer_cols += paren_width as u16;
-            true
-        }
+        } => (tot_cols, header_cols, trailer_cols),
     };
 
-    // If there are no parameters, stop here
-    if !has_argument {
-        return Ok(should_wrap);
+    // In the multi-line case we can always display the activity name, we only
+    // need to update the header/trailer col count for the argument display
+    let activity_name = activity_id.name();
+    write!(output, "{activity_name}")?;
+    if *activity_arg == ActivityArgument::Nothing {
+        return Ok(true);
     }
+    let paren_width = '('.width().expect("Not a control char");
+    debug_assert_eq!(Some(paren_width), ')'.width());
+    let config = DisplayConfig::MultiLine {
+        tot_cols,
+        header_cols: header_cols + (activity_name.width() + paren_width) as u16,
+        trailer_cols: trailer_cols + paren_width as u16,
+    };
 
-    // Otheriwe, account for the reserved space and display the parameters
+    // Display the parameters
     write!(output, "(")?;
+    let should_wrap = display_activity_argument(&mut output, activity_arg, config, options)?;
+    write!(output, ")")?;
+    Ok(should_wrap)
+}
+
+/// Try to display an activity's argument alone in finite space
+///
+/// Unlike `display_activity_desc()`, this displays neither the activity name
+/// nor parentheses around the argument, which is useful when the argument
+/// goes to a dedicated column. Nothing is displayed if there is no argument.
+///
+/// Return value semantics are the same as for `display_activity_desc()`,
+/// except Err(NotEnoughCols) is returned if no column is available at all.
+///
+pub fn display_activity_argument(
+    mut output: impl io::Write,
+    activity_arg: &ActivityArgument,
+    config: DisplayConfig,
+    options: ActivityDisplayOptions,
+) -> Result<bool, ActivityDescError> {
+    // Single-line displays are handled by one-shot descriptions
+    if let DisplayConfig::SingleLine { max_cols } = config {
+        if max_cols == 0 && *activity_arg != ActivityArgument::Nothing {
+            return Err(ActivityDescError::NotEnoughCols(max_cols));
+        }
+        ArgumentDesc::one_shot(activity_arg, max_cols).display(output, max_cols, options)?;
+        return Ok(false);
+    }
+
+    // FIXME: Remove allow-directive once pretty printing is implemented
+    #[allow(unused_mut)]
+    let mut should_wrap = true;
     match activity_arg {
+        ActivityArgument::Nothing => Ok(()),
         ActivityArgument::UnnamedLoop => {
             super::display_string(&mut output, "<unnamed loop>", config)
         }
         ActivityArgument::Module => super::display_string(&mut output, "[module]", config),
         ActivityArgument::String(s)
+        | ActivityArgument::Other(s)
         | ActivityArgument::Symbol(Symbol::Demangled(s))
         | ActivityArgument::Symbol(Symbol::MaybeMangled(s)) => {
             super::display_string(&mut output, s, config)
         }
         ActivityArgument::FilePath(p) => {
-            write!(output, "{}", super::path::display_path(p, config))
+            let display = super::path::display_path(p, config, options.base_dir.as_deref());
+            if options.hyperlink_paths {
+                display_file_hyperlink(&mut output, &p.to_boxed_path(), &display)
+            } else {
+                write!(output, "{display}")
+            }
         }
         ActivityArgument::CppEntity(e) | ActivityArgument::Symbol(Symbol::Parsed(e)) => {
-            match config {
-                DisplayConfig::SingleLine { max_cols } => {
-                    write!(output, "{}", e.bounded_display(max_cols))
+            // FIXME: Restore this once pretty-printing is implemented
+            /* should_wrap = false; */
+            write!(output, "{}", e.display(&DisplayState::default()))
+        }
+    }?;
+    Ok(should_wrap)
+}
+
+/// Width-independent description of an activity
+///
+/// Resolving an activity's argument and rendering it is comparatively
+/// expensive. When the same activity must be displayed at several widths, as
+/// happens when the terminal is resized, it is better to do it once and to
+/// cheaply truncate the result to each new width afterwards. C++ entities are
+/// the exception: they are shared with the trace and rendered at the
+/// recursion depth that fits each new width.
+///
+#[derive(Clone, Debug)]
+pub struct ActivityDesc {
+    /// Name of the activity
+    name: Box<str>,
+
+    /// Argument of the activity
+    argument: ArgumentDesc,
+}
+//
+impl ActivityDesc {
+    /// Render an activity's name and (previously parsed) argument
+    pub fn new(
+        trace: &ClangTrace,
+        activity_id: &ActivityId,
+        parsed_arg: &ParsedActivityArgument,
+    ) -> Self {
+        Self {
+            name: activity_id.name().into(),
+            argument: ArgumentDesc::new(trace, parsed_arg),
+        }
+    }
+
+    /// Display another label, such as an abbreviation, instead of the
+    /// activity's name
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.name = label.into();
+        self
+    }
+
+    /// Try to display the activity's name and argument on a single line
+    ///
+    /// Returns Err(NotEnoughCols) if not even the activity name can fit in
+    /// that space, see `display_activity_desc()` for more info.
+    ///
+    pub fn display(
+        &self,
+        mut output: impl io::Write,
+        max_cols: u16,
+        options: ActivityDisplayOptions,
+    ) -> Result<(), ActivityDescError> {
+        let has_argument = !matches!(self.argument, ArgumentDesc::Nothing);
+        let paren_width = '('.width().expect("Not a control char");
+        debug_assert_eq!(Some(paren_width), ')'.width());
+
+        // Can we display at least ActivityName + (…) if there are parameters?
+        if usize::from(max_cols) < self.name.width() + 3 * (has_argument as usize) {
+            return Err(ActivityDescError::NotEnoughCols(max_cols));
+        }
+        write!(output, "{}", self.name)?;
+
+        // If there are no parameters, stop here
+        if !has_argument {
+            return Ok(());
+        }
+
+        // Otherwise, account for the reserved space and display the parameters
+        let max_cols = max_cols.saturating_sub((self.name.width() + 2 * paren_width) as u16);
+        write!(output, "(")?;
+        self.argument.display(&mut output, max_cols, options)?;
+        write!(output, ")")?;
+        Ok(())
+    }
+}
+
+/// Width-independent rendering of an activity argument
+#[derive(Clone, Debug)]
+enum ArgumentDesc {
+    /// No argument
+    Nothing,
+
+    /// Text, which is truncated by eliding the middle
+    Text(Box<str>),
+
+    /// File path, which is truncated component-wise
+    FilePath {
+        /// Components of the path
+        components: Box<[Box<str>]>,
+
+        /// Full path, which hyperlinks point to
+        path: PathBuf,
+    },
+
+    /// C++ entity, which is only rendered at display time because the
+    /// recursion depth that fits depends on the available space
+    CppEntity(SharedEntity),
+}
+//
+impl ArgumentDesc {
+    /// Render a parsed activity argument, for display at any width
+    fn new(trace: &ClangTrace, parsed_arg: &ParsedActivityArgument) -> Self {
+        match parsed_arg {
+            ParsedActivityArgument::CppEntity(key)
+            | ParsedActivityArgument::Symbol(ParsedSymbol::Parsed(key)) => {
+                Self::CppEntity(trace.shared_entity(*key))
+            }
+            other => Self::from_argument(&other.resolve(trace), |_| {
+                unreachable!("C++ entities should have been handled above")
+            }),
+        }
+    }
+
+    /// Render an activity argument, for display in max_cols columns only
+    fn one_shot(activity_arg: &ActivityArgument, max_cols: u16) -> Self {
+        Self::from_argument(activity_arg, |e| {
+            Self::Text(e.bounded_display(max_cols.max(1)).into())
+        })
+    }
+
+    /// Render an activity argument, using a callback for C++ entities
+    fn from_argument(
+        activity_arg: &ActivityArgument,
+        cpp_entity: impl FnOnce(&EntityView) -> Self,
+    ) -> Self {
+        let text = |s: &str| Self::Text(s.into());
+        match activity_arg {
+            ActivityArgument::Nothing => Self::Nothing,
+            ActivityArgument::UnnamedLoop => text("<unnamed loop>"),
+            ActivityArgument::Module => text("[module]"),
+            ActivityArgument::String(s)
+            | ActivityArgument::Other(s)
+            | ActivityArgument::Symbol(Symbol::Demangled(s))
+            | ActivityArgument::Symbol(Symbol::MaybeMangled(s)) => text(s),
+            ActivityArgument::FilePath(p) => Self::FilePath {
+                components: p.components().map(|c| c.value().into()).collect(),
+                path: p.to_boxed_path().into(),
+            },
+            ActivityArgument::CppEntity(e) | ActivityArgument::Symbol(Symbol::Parsed(e)) => {
+                cpp_entity(e)
+            }
+        }
+    }
+
+    /// Display the argument on a single line, using at most max_cols columns
+    ///
+    /// At least one column must be available unless there is no argument.
+    ///
+    fn display(
+        &self,
+        mut output: impl io::Write,
+        max_cols: u16,
+        options: ActivityDisplayOptions,
+    ) -> io::Result<()> {
+        match self {
+            Self::Nothing => Ok(()),
+            Self::Text(s) => {
+                super::display_string(&mut output, s, DisplayConfig::SingleLine { max_cols })
+            }
+            Self::FilePath { components, path } => {
+                let display = options
+                    .base_dir
+                    .as_deref()
+                    .and_then(|base_dir| {
+                        super::path::truncate_relative_path(path, base_dir, max_cols)
+                    })
+                    .unwrap_or_else(|| {
+                        super::path::truncate_path_iter(components.iter(), max_cols)
+                    });
+                if options.hyperlink_paths {
+                    display_file_hyperlink(output, path, &display)
+                } else {
+                    write!(output, "{display}")
                 }
-                DisplayConfig::MultiLine { .. } => {
-                    // FIXME: Restore this once pretty-printing is implemented
-                    /* should_wrap = false; */
-                    write!(output, "{}", e.display(&DisplayState::default()))
+            }
+            Self::CppEntity(entity) => {
+                // Use the deepest recursion depth that fits
+                let (display, _width) = entity.to_bounded_string(max_cols);
+                write!(output, "{display}")
+            }
+        }
+    }
+}
+
+/// Display some text as an OSC 8 hyperlink to a local file
+///
+/// Terminals that do not support OSC 8 will just ignore the escape sequences
+/// and display the text as usual.
+///
+fn display_file_hyperlink(mut output: impl io::Write, path: &Path, text: &str) -> io::Result<()> {
+    write!(
+        output,
+        "\x1b]8;;{}\x1b\\{text}\x1b]8;;\x1b\\",
+        file_url(path)
+    )
+}
+
+/// Percent-encoded file:// URL pointing to a local file
+///
+/// Relative paths are resolved with respect to the current directory.
+///
+fn file_url(path: &Path) -> String {
+    let absolute = if path.is_absolute() {
+        path.to_owned()
+    } else {
+        std::env::current_dir().map_or_else(|_| path.to_owned(), |cwd| cwd.join(path))
+    };
+    let mut url = String::from("file://");
+    for byte in absolute.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            url.push(char::from(byte));
+        } else {
+            url.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    url
+}
+
+/// Display an activity trace, ideally with associated profiling information
+pub fn display_activity(
+    mut output: impl io::Write,
+    desc: &ActivityDesc,
+    max_cols: u16,
+    duration: Duration,
+    duration_norm: Duration,
+    duration_format: DurationFormat,
+    options: ActivityDisplayOptions,
+) -> io::Result<()> {
+    assert!(max_cols >= 1);
+
+    // Display the trailing profiling numbers in a private string to know its
+    // display width and how many columns that leaves for the activity id.
+    let mut trailer = Vec::<u8>::new();
+    display_profile_info(&mut trailer, duration, duration_norm, duration_format)?;
+    let trailer = std::str::from_utf8(&trailer[..])
+        .expect("display_profile_info shouldn't produce non-UTF8 bytes");
+    let other_cols = max_cols.saturating_sub(trailer.width() as u16);
+
+    // Try to display both the activity id and the profiling numbers
+    match desc.display(&mut output, other_cols, options.clone()) {
+        Ok(()) => {
+            // Success, can just print out the profiling numbers
+            write!(output, "{trailer}")
+        }
+        Err(ActivityDescError::NotEnoughCols(_)) => {
+            // Not enough space for both, try to display activity ID alone
+            match desc.display(&mut output, max_cols, options) {
+                Ok(()) => Ok(()),
+                Err(ActivityDescError::IoError(e)) => Err(e),
+                Err(ActivityDescError::NotEnoughCols(_)) => {
+                    // Seems the best we can do is an ellipsis placeholder...
+                    write!(output, "…")
                 }
             }
         }
-        ActivityArgument::Nothing => unreachable!("Handled above"),
-    }?;
-    write!(output, ")")?;
-    Ok(should_wrap)
+        Err(ActivityDescError::IoError(e)) => Err(e),
+    }
+}
+
+/// Display profiling information (absolute and relative duration)
+pub fn display_profile_info(
+    mut output: impl io::Write,
+    duration: Duration,
+    duration_norm: Duration,
+    duration_format: DurationFormat,
+) -> io::Result<()> {
+    write!(
+        output,
+        " [{}, {}]",
+        duration_format.display(duration),
+        TraceDuration(duration).percentage(duration_norm)
+    )
 }
 
 /// Error that is emitted when an activity id cannot be displayed
@@ -119,6 +444,22 @@ mod tests {
 
     use super::*;
 
+    /// Find an activity whose argument is a file path
+    fn file_path_activity(trace: &mut ClangTrace) -> (ActivityTraceId, ParsedActivityArgument) {
+        trace
+            .all_activities()
+            .map(|activity_trace| activity_trace.id())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .find_map(|id| {
+                let raw_arg = trace.activity_trace(id).activity().raw_argument().clone();
+                let parsed_arg = raw_arg.parse(trace).ok()?;
+                matches!(parsed_arg, ParsedActivityArgument::FilePath(_))
+                    .then_some((id, parsed_arg))
+            })
+            .unwrap()
+    }
+
     #[test]
     fn display_activity_id() {
         let mut display = Vec::new();
@@ -137,6 +478,7 @@ mod tests {
                     &parsed_arg.resolve(trace),
                     // FIXME: Also test MultiLine once pretty printing is ironed out
                     DisplayConfig::SingleLine { max_cols },
+                    ActivityDisplayOptions::default(),
                 );
                 if expected_display.is_empty() {
                     assert_eq!(
@@ -364,4 +706,323 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn display_activity_argument() {
+        fn display(arg: &ActivityArgument, max_cols: u16) -> Option<String> {
+            let mut output = Vec::new();
+            let result = super::display_activity_argument(
+                &mut output,
+                arg,
+                DisplayConfig::SingleLine { max_cols },
+                ActivityDisplayOptions::default(),
+            );
+            match result {
+                Ok(should_wrap) => {
+                    assert!(!should_wrap);
+                    let output = String::from_utf8(output).unwrap();
+                    assert!(output.width() <= max_cols.into());
+                    Some(output)
+                }
+                Err(ActivityDescError::NotEnoughCols(cols)) => {
+                    assert_eq!(cols, max_cols);
+                    assert!(output.is_empty());
+                    None
+                }
+                Err(ActivityDescError::IoError(e)) => panic!("Unexpected I/O error: {e}"),
+            }
+        }
+
+        // Arguments that do not need a trace
+        assert_eq!(display(&ActivityArgument::Nothing, 0).as_deref(), Some(""));
+        assert_eq!(display(&ActivityArgument::UnnamedLoop, 0), None);
+        assert_eq!(
+            display(&ActivityArgument::UnnamedLoop, 1).as_deref(),
+            Some("…")
+        );
+        assert_eq!(
+            display(&ActivityArgument::UnnamedLoop, 7).as_deref(),
+            Some("<un…op>")
+        );
+        assert_eq!(
+            display(&ActivityArgument::UnnamedLoop, 14).as_deref(),
+            Some("<unnamed loop>")
+        );
+        assert_eq!(
+            display(&ActivityArgument::Module, 5).as_deref(),
+            Some("[m…e]")
+        );
+        assert_eq!(
+            display(&ActivityArgument::Module, 8).as_deref(),
+            Some("[module]")
+        );
+        let string = ActivityArgument::String("Loop Pass Manager");
+        assert_eq!(display(&string, 0), None);
+        assert_eq!(display(&string, 9).as_deref(), Some("Loop…ager"));
+        assert_eq!(display(&string, 17).as_deref(), Some("Loop Pass Manager"));
+
+        // Arguments of unknown kinds are displayed in their JSON form
+        let other = ActivityArgument::Other(r#"{"bytes":1024,"kind":"heap"}"#);
+        assert_eq!(display(&other, 0), None);
+        assert_eq!(display(&other, 11).as_deref(), Some(r#"{"byt…eap"}"#));
+        assert_eq!(
+            display(&other, 28).as_deref(),
+            Some(r#"{"bytes":1024,"kind":"heap"}"#)
+        );
+
+        // Arguments that reference trace data
+        with_test_trace(|trace| {
+            // Find the first argument of each kind in the test dataset
+            let mut path_arg = None;
+            let mut entity_arg = None;
+            let mut mangled_arg = None;
+            let mut parsed_mangled_arg = None;
+            let activity_trace_ids = trace
+                .all_activities()
+                .map(|activity_trace| activity_trace.id())
+                .collect::<Vec<_>>();
+            for id in activity_trace_ids {
+                let raw_arg = trace.activity_trace(id).activity().raw_argument().clone();
+                let slot = match raw_arg.arg_type() {
+                    ActivityArgumentType::FilePathOrModule => &mut path_arg,
+                    ActivityArgumentType::CppEntity => &mut entity_arg,
+                    ActivityArgumentType::Symbol => &mut mangled_arg,
+                    _ => continue,
+                };
+                let parsed_arg = raw_arg.parse(trace).unwrap();
+                let slot = match parsed_arg {
+                    ParsedActivityArgument::Symbol(ParsedSymbol::Parsed(_)) => {
+                        &mut parsed_mangled_arg
+                    }
+                    ParsedActivityArgument::Symbol(ParsedSymbol::Demangled(_)) => continue,
+                    _ => slot,
+                };
+                slot.get_or_insert(parsed_arg);
+            }
+
+            let path_arg = path_arg.unwrap();
+            let path_arg = path_arg.resolve(trace);
+            assert_eq!(display(&path_arg, 0), None);
+            assert_eq!(display(&path_arg, 11).as_deref(), Some("…"));
+            assert_eq!(display(&path_arg, 12).as_deref(), Some("…/features.h"));
+            assert_eq!(
+                display(&path_arg, 23).as_deref(),
+                Some("/usr/include/features.h")
+            );
+
+            let entity_arg = entity_arg.unwrap();
+            let entity_arg = entity_arg.resolve(trace);
+            assert_eq!(display(&entity_arg, 0), None);
+            assert_eq!(display(&entity_arg, 13).as_deref(), Some("…"));
+            assert_eq!(display(&entity_arg, 14).as_deref(), Some("…::char_traits"));
+            assert_eq!(
+                display(&entity_arg, 16).as_deref(),
+                Some("std::char_traits")
+            );
+
+            let mangled_arg = mangled_arg.unwrap();
+            let mangled_arg = mangled_arg.resolve(trace);
+            assert_eq!(
+                display(&mangled_arg, 20).as_deref(),
+                Some("__cxx_glo…l_var_init")
+            );
+            assert_eq!(
+                display(&mangled_arg, 21).as_deref(),
+                Some("__cxx_global_var_init")
+            );
+
+            let parsed_mangled_arg = parsed_mangled_arg.unwrap();
+            let parsed_mangled_arg = parsed_mangled_arg.resolve(trace);
+            assert_eq!(display(&parsed_mangled_arg, 14).as_deref(), Some("…"));
+            assert_eq!(
+                display(&parsed_mangled_arg, 15).as_deref(),
+                Some("…::test_method…")
+            );
+            assert_eq!(
+                display(&parsed_mangled_arg, 53).as_deref(),
+                Some("TrackFittingGainMatrixSmoother::Smooth::test_method()")
+            );
+        });
+    }
+
+    #[test]
+    fn width_independent_entities() {
+        with_test_trace(|trace| {
+            let entity_activities = trace
+                .all_activities()
+                .map(|activity_trace| activity_trace.id())
+                .collect::<Vec<_>>()
+                .into_iter()
+                .filter_map(|id| {
+                    let raw_arg = trace.activity_trace(id).activity().raw_argument().clone();
+                    let parsed_arg = raw_arg.parse(trace).ok()?;
+                    matches!(
+                        parsed_arg,
+                        ParsedActivityArgument::CppEntity(_)
+                            | ParsedActivityArgument::Symbol(ParsedSymbol::Parsed(_))
+                    )
+                    .then_some((id, parsed_arg))
+                })
+                .collect::<Vec<_>>();
+            assert!(!entity_activities.is_empty());
+
+            // Shared entities are displayed like one-shot renderings at any width
+            for (id, parsed_arg) in entity_activities {
+                let activity_trace = trace.activity_trace(id);
+                let activity_id = activity_trace.activity().id();
+                let desc = ActivityDesc::new(trace, activity_id, &parsed_arg);
+                assert_matches!(desc.argument, ArgumentDesc::CppEntity(_));
+                let arg = parsed_arg.resolve(trace);
+                for max_cols in (0..=120).step_by(7) {
+                    let (mut shared, mut one_shot) = (Vec::new(), Vec::new());
+                    let shared_ok = desc
+                        .display(&mut shared, max_cols, ActivityDisplayOptions::default())
+                        .is_ok();
+                    let one_shot_ok = super::display_activity_desc(
+                        &mut one_shot,
+                        activity_id,
+                        &arg,
+                        DisplayConfig::SingleLine { max_cols },
+                        ActivityDisplayOptions::default(),
+                    )
+                    .is_ok();
+                    assert_eq!(shared_ok, one_shot_ok);
+                    assert_eq!(shared, one_shot);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn hyperlink_paths() {
+        with_test_trace(|trace| {
+            let (id, parsed_arg) = file_path_activity(trace);
+            let activity_id = trace.activity_trace(id).activity().id().clone();
+            let arg = parsed_arg.resolve(trace);
+
+            // Compare hyperlinked displays to plain ones
+            let display = |options, config| {
+                let mut output = Vec::new();
+                super::display_activity_desc(&mut output, &activity_id, &arg, config, options)
+                    .unwrap();
+                String::from_utf8(output).unwrap()
+            };
+            let plain = ActivityDisplayOptions::default();
+            let linked = ActivityDisplayOptions {
+                hyperlink_paths: true,
+                ..Default::default()
+            };
+            let multi_line = DisplayConfig::MultiLine {
+                tot_cols: 10,
+                header_cols: 0,
+                trailer_cols: 0,
+            };
+            for config in (0..=40)
+                .map(|max_cols| DisplayConfig::SingleLine { max_cols })
+                .chain(std::iter::once(multi_line))
+            {
+                if let DisplayConfig::SingleLine { max_cols } = config {
+                    let mut output = Vec::new();
+                    if ActivityDesc::new(trace, &activity_id, &parsed_arg)
+                        .display(&mut output, max_cols, ActivityDisplayOptions::default())
+                        .is_err()
+                    {
+                        continue;
+                    }
+                }
+                let plain_display = display(plain.clone(), config);
+                let linked_display = display(linked.clone(), config);
+
+                // The link must target the absolute path of the file...
+                let link_start = "\x1b]8;;file:///usr/include/features.h\x1b\\";
+                let link_end = "\x1b]8;;\x1b\\";
+                let (header, rest) = linked_display.split_once(link_start).unwrap();
+                let (text, trailer) = rest.split_once(link_end).unwrap();
+
+                // ...and escape sequences must not affect the visible display
+                let visible = format!("{header}{text}{trailer}");
+                assert_eq!(visible, plain_display);
+                if let DisplayConfig::SingleLine { max_cols } = config {
+                    assert!(visible.width() <= max_cols.into());
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn relative_paths() {
+        with_test_trace(|trace| {
+            let (id, parsed_arg) = file_path_activity(trace);
+            let arg = parsed_arg.resolve(trace);
+            let desc =
+                ActivityDesc::new(trace, trace.activity_trace(id).activity().id(), &parsed_arg);
+
+            // Render the path of /usr/include/features.h with some base directory
+            let display = |base_dir: Option<&str>, config| {
+                let options = ActivityDisplayOptions {
+                    base_dir: base_dir.map(PathBuf::from),
+                    ..Default::default()
+                };
+                let mut output = Vec::new();
+                super::display_activity_argument(&mut output, &arg, config, options.clone())
+                    .unwrap();
+                let output = String::from_utf8(output).unwrap();
+
+                // Width-independent descriptions must agree
+                if let DisplayConfig::SingleLine { max_cols } = config {
+                    let mut desc_output = Vec::new();
+                    desc.argument
+                        .display(&mut desc_output, max_cols, options)
+                        .unwrap();
+                    assert_eq!(String::from_utf8(desc_output).unwrap(), output);
+                }
+                output
+            };
+            let single_line = |max_cols| DisplayConfig::SingleLine { max_cols };
+            let multi_line = DisplayConfig::MultiLine {
+                tot_cols: 10,
+                header_cols: 0,
+                trailer_cols: 0,
+            };
+
+            // Without a base directory, paths are displayed in full
+            assert_eq!(display(None, single_line(40)), "/usr/include/features.h");
+            assert_eq!(display(None, multi_line), "/usr/include/features.h");
+
+            // Paths within the base directory are displayed relative to it...
+            for base_dir in ["/usr", "/usr/"] {
+                assert_eq!(
+                    display(Some(base_dir), single_line(40)),
+                    "include/features.h"
+                );
+                assert_eq!(display(Some(base_dir), multi_line), "include/features.h");
+            }
+            assert_eq!(display(Some("/usr/include"), single_line(40)), "features.h");
+
+            // ...before truncation...
+            assert_eq!(display(Some("/usr"), single_line(12)), "…/features.h");
+            assert_eq!(display(None, single_line(12)), "…/features.h");
+
+            // ...and others are displayed in full
+            for base_dir in ["/opt", "/usr/inc", "/usr/include/features.h"] {
+                assert_eq!(
+                    display(Some(base_dir), single_line(40)),
+                    "/usr/include/features.h"
+                );
+                assert_eq!(
+                    display(Some(base_dir), multi_line),
+                    "/usr/include/features.h"
+                );
+            }
+
+            // Relative base directories work once made absolute at startup
+            let current_dir = std::env::current_dir().unwrap();
+            let relative_usr = "../".repeat(current_dir.components().count() - 1) + "usr";
+            let base_dir = crate::absolute_dir(Path::new(&relative_usr));
+            assert_eq!(
+                display(base_dir.to_str(), single_line(40)),
+                "include/features.h"
+            );
+        });
+    }
 }
@@ -1,19 +1,108 @@
 //! Utility to display durations
 
 use clang_time_trace::{Duration, DAY, HOUR, MICROSECOND, MILLISECOND, MINUTE, SECOND};
-use std::io;
+use std::fmt::{self, Display, Formatter};
 
-/// Display a duration in a human-readable format
-pub fn display_duration(output: impl io::Write, duration: Duration) -> io::Result<()> {
-    display_duration_impl(output, duration, None)
+/// Duration wrapper whose Display impl auto-scales to a human-readable unit
+///
+/// Sub-second durations are displayed in µs or ms, longer durations in s,
+/// then in a [[D]d ][[H]H:][M]M:SS.ss format beyond one minute.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct TraceDuration(pub Duration);
+//
+impl TraceDuration {
+    /// Express this duration as a percentage, given a norm which is the
+    /// inverse of the reference duration (see `trace::duration_norm()`)
+    pub fn percentage(self, duration_norm: Duration) -> Percentage {
+        Percentage(self.0 * duration_norm)
+    }
+
+    /// Display this duration as an integral number of microseconds, with
+    /// digits grouped by thousands using the specified separator
+    pub fn grouped_micros(self, separator: char) -> GroupedMicros {
+        GroupedMicros {
+            duration: self.0,
+            separator,
+        }
+    }
+}
+//
+impl Display for TraceDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        display_duration_impl(f, self.0, None)
+    }
+}
+
+/// Duration displayed as a number of microseconds with grouped digits
+///
+/// For example, 1234567µs is displayed as "1,234,567µs" if the separator is a
+/// comma. Numbers below 1000 are not affected by digit grouping.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupedMicros {
+    /// Displayed duration
+    duration: Duration,
+
+    /// Separator inserted between groups of three digits
+    separator: char,
+}
+//
+impl Display for GroupedMicros {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        // The sign, if any, goes before the first group of digits
+        let micros = (self.duration / MICROSECOND).round();
+        if micros < 0.0 {
+            write!(f, "-")?;
+        }
+        let digits = format!("{:.0}", micros.abs());
+        for (idx, digit) in digits.chars().enumerate() {
+            if idx > 0 && (digits.len() - idx) % 3 == 0 {
+                write!(f, "{}", self.separator)?;
+            }
+            write!(f, "{digit}")?;
+        }
+        write!(f, "µs")
+    }
+}
+
+/// Way durations should be displayed
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DurationFormat {
+    /// Auto-scale to a human-readable unit (see `TraceDuration`)
+    #[default]
+    Scaled,
+
+    /// Number of microseconds with grouped digits (see `GroupedMicros`)
+    GroupedMicros(char),
+}
+//
+impl DurationFormat {
+    /// Display a duration in this format
+    pub fn display(self, duration: Duration) -> String {
+        match self {
+            DurationFormat::Scaled => TraceDuration(duration).to_string(),
+            DurationFormat::GroupedMicros(separator) => TraceDuration(duration)
+                .grouped_micros(separator)
+                .to_string(),
+        }
+    }
 }
 
-/// Implementation of display_duration that allows for HH:MM:SS format
+/// Fraction of some reference quantity, displayed as a percentage
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Percentage(pub f64);
+//
+impl Display for Percentage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{:.2}%", self.0 * 100.0)
+    }
+}
+
+/// Implementation of TraceDuration's Display that allows for HH:MM:SS format
 fn display_duration_impl(
-    mut output: impl io::Write,
+    output: &mut Formatter<'_>,
     duration: Duration,
     force_hms: Option<ForceHMS>,
-) -> io::Result<()> {
+) -> Result<(), fmt::Error> {
     if duration >= 23.0 * HOUR + 59.0 * MINUTE + 59.995 * SECOND {
         let mut days = (duration / DAY).floor();
         let mut remainder = duration - days * DAY;
@@ -73,21 +162,11 @@ enum ForceHMS {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use assert_matches::assert_matches;
 
     #[test]
     fn display_duration() {
-        let mut buffer = Vec::new();
-        let mut check_display = |duration, expected: &str| {
-            buffer.clear();
-            assert_matches!(super::display_duration(&mut buffer, duration), Ok(()));
-            assert_eq!(
-                buffer,
-                expected.as_bytes(),
-                "Expected {}, got {:?}",
-                expected,
-                std::str::from_utf8(&buffer)
-            );
+        let check_display = |duration, expected: &str| {
+            assert_eq!(TraceDuration(duration).to_string(), expected);
         };
         check_display(4.12345 * MICROSECOND, "4.12345µs");
         check_display(999.999 * MICROSECOND, "999.999µs");
@@ -119,4 +198,66 @@ mod tests {
             "8d 06:02:01.36",
         );
     }
+
+    #[test]
+    fn trace_duration() {
+        assert_eq!(TraceDuration(12.5 * MICROSECOND).to_string(), "12.5µs");
+        assert_eq!(TraceDuration(42.0 * MILLISECOND).to_string(), "42.00ms");
+        assert_eq!(TraceDuration(3.21987 * SECOND).to_string(), "3.22s");
+        assert_eq!(TraceDuration(90.0 * SECOND).to_string(), "1:30.00");
+        assert_eq!(TraceDuration(2.5 * HOUR).to_string(), "2:30:00.00");
+    }
+
+    #[test]
+    fn grouped_micros() {
+        let check_display = |duration, separator, expected: &str| {
+            assert_eq!(
+                TraceDuration(duration)
+                    .grouped_micros(separator)
+                    .to_string(),
+                expected
+            );
+            assert_eq!(
+                DurationFormat::GroupedMicros(separator).display(duration),
+                expected
+            );
+        };
+        check_display(0.0, ',', "0µs");
+        check_display(12.4 * MICROSECOND, ',', "12µs");
+        check_display(999.0 * MICROSECOND, ',', "999µs");
+        check_display(1000.0 * MICROSECOND, ',', "1,000µs");
+        check_display(123456.0 * MICROSECOND, ',', "123,456µs");
+        check_display(1234567.0 * MICROSECOND, ',', "1,234,567µs");
+        check_display(1234567.0 * MICROSECOND, ' ', "1 234 567µs");
+        check_display(-12.0 * MICROSECOND, ',', "-12µs");
+        check_display(-123456.0 * MICROSECOND, ',', "-123,456µs");
+        check_display(-1234567.0 * MICROSECOND, ',', "-1,234,567µs");
+        check_display(-0.4 * MICROSECOND, ',', "0µs");
+        assert_eq!(
+            DurationFormat::Scaled.display(1234567.0 * MICROSECOND),
+            "1.23s"
+        );
+    }
+
+    #[test]
+    fn percentage() {
+        let norm = 1.0 / (2.0 * SECOND);
+        assert_eq!(
+            TraceDuration(500.0 * MILLISECOND)
+                .percentage(norm)
+                .to_string(),
+            "25.00%"
+        );
+        assert_eq!(
+            TraceDuration(2.0 * SECOND).percentage(norm).to_string(),
+            "100.00%"
+        );
+        assert_eq!(
+            TraceDuration(1.0 * MICROSECOND)
+                .percentage(norm)
+                .to_string(),
+            "0.00%"
+        );
+        assert_eq!(Percentage(0.123456).to_string(), "12.35%");
+    }
 }
@@ -2,7 +2,10 @@
 
 use super::DisplayConfig;
 use clang_time_trace::InternedPath;
-use std::{path::MAIN_SEPARATOR as PATH_SEPARATOR, sync::OnceLock};
+use std::{
+    path::{Path, MAIN_SEPARATOR as PATH_SEPARATOR},
+    sync::OnceLock,
+};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
@@ -21,15 +24,52 @@ use unicode_width::UnicodeWidthStr;
 /// wouldn't handle those strings correctly either, likely because they use a
 /// similar algorithm, so we're state of the art in this respect...
 ///
-pub fn display_path(path: &InternedPath, config: DisplayConfig) -> Box<str> {
+/// If `base_dir` is specified, paths within that directory are displayed
+/// relative to it, see `relative_path()`.
+///
+pub fn display_path(
+    path: &InternedPath,
+    config: DisplayConfig,
+    base_dir: Option<&Path>,
+) -> Box<str> {
+    let full_path = path.to_boxed_path();
     match config {
-        DisplayConfig::SingleLine { max_cols } => {
-            truncate_path_iter(path.components().map(|c| c.value()), max_cols)
-        }
-        DisplayConfig::MultiLine { .. } => path.to_boxed_path().display().to_string().into(),
+        DisplayConfig::SingleLine { max_cols } => base_dir
+            .and_then(|base_dir| truncate_relative_path(&full_path, base_dir, max_cols))
+            .unwrap_or_else(|| truncate_path_iter(path.components().map(|c| c.value()), max_cols)),
+        DisplayConfig::MultiLine { .. } => base_dir
+            .and_then(|base_dir| relative_path(&full_path, base_dir))
+            .unwrap_or(&full_path)
+            .display()
+            .to_string()
+            .into(),
     }
 }
 
+/// Like `truncate_path_iter()`, but for the path of a file relative to a base
+/// directory, if the file is located inside of that directory
+pub fn truncate_relative_path(path: &Path, base_dir: &Path, cols: u16) -> Option<Box<str>> {
+    let relative = relative_path(path, base_dir)?;
+    Some(truncate_path_iter(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy()),
+        cols,
+    ))
+}
+
+/// Path of a file relative to a base directory, if the file is located
+/// inside of that directory
+///
+/// None is returned if the file is outside of the base directory, or if it
+/// is the base directory itself, as there would be nothing left to display.
+///
+pub fn relative_path<'path>(path: &'path Path, base_dir: &Path) -> Option<&'path Path> {
+    path.strip_prefix(base_dir)
+        .ok()
+        .filter(|relative| relative.components().next().is_some())
+}
+
 /// Easily testable implementation of truncate_path that takes an iterator of
 /// path components as input instead of an InternedPath
 pub fn truncate_path_iter(
@@ -179,14 +219,19 @@ fn display_components(
         for _ in 0..elided_components {
             components.next();
         }
-    } else {
+    } else if accepted_front > 0 {
         // If no elision occurs, avoid double separator (front + back)
         buffer.pop();
     }
 
-    // Add back components and associated separators
+    // Add back components and associated separators, knowing that relative
+    // paths displayed without elision must not start with a separator
+    let mut need_separator = accepted_front + elided_components > 0;
     for _ in 0..accepted_back {
-        buffer.push(PATH_SEPARATOR);
+        if need_separator {
+            buffer.push(PATH_SEPARATOR);
+        }
+        need_separator = true;
         buffer.push_str(
             components
                 .next()
@@ -419,6 +464,10 @@ mod tests {
         test_display_component("/usr/include/stuff.h", (2, 2), "/usr/include/stuff.h");
         test_display_component("/usr/include/stuff.h", (3, 0), "/usr/include/…");
         test_display_component("/usr/include/stuff.h", (3, 1), "/usr/include/stuff.h");
+        test_display_component("include/stuff.h", (0, 2), "include/stuff.h");
+        test_display_component("include/stuff.h", (1, 1), "include/stuff.h");
+        test_display_component("stuff.h", (0, 1), "stuff.h");
+        test_display_component("stuff.h", (1, 0), "stuff.h");
     }
 
     #[test]
@@ -437,4 +486,32 @@ mod tests {
         test_display_filename("stuff.h", 7, "…/st….h");
         test_display_filename("stuff.h", 8, "…/st…f.h");
     }
+
+    #[test]
+    fn relative_path() {
+        let path = Path::new("/home/user/project/src/main.cpp");
+        let relative = |base_dir| super::relative_path(path, Path::new(base_dir));
+        assert_eq!(
+            relative("/home/user/project"),
+            Some(Path::new("src/main.cpp"))
+        );
+        assert_eq!(
+            relative("/home/user/project/"),
+            Some(Path::new("src/main.cpp"))
+        );
+        assert_eq!(relative("/home/user/proj"), None);
+        assert_eq!(relative("/opt"), None);
+        assert_eq!(relative("/home/user/project/src/main.cpp"), None);
+
+        let truncate = |base_dir, cols| truncate_relative_path(path, Path::new(base_dir), cols);
+        assert_eq!(
+            truncate("/home/user/project", 12).as_deref(),
+            Some("src/main.cpp")
+        );
+        assert_eq!(
+            truncate("/home/user/project", 11).as_deref(),
+            Some("…/main.cpp")
+        );
+        assert_eq!(truncate("/opt", 100), None);
+    }
 }
@@ -1,11 +1,21 @@
 //! User interface module
 
+use self::display::activity::ActivityDisplayOptions;
+use crate::CliArgs;
 use clang_time_trace::{ActivityTraceId, ClangTrace, ParsedActivityArgument};
 
 mod display;
 pub mod stdio;
 pub mod tui;
 
+/// Activity display options selected on the command line
+fn display_options(args: &CliArgs) -> ActivityDisplayOptions {
+    ActivityDisplayOptions {
+        hyperlink_paths: args.hyperlink_paths,
+        base_dir: args.base_dir.clone(),
+    }
+}
+
 /// Try to parse an activity argument using its intended logic
 ///
 /// If that fails, log an error, then treat it as a string argument
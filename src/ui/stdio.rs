@@ -1,16 +1,26 @@
 //! Display facilities which are specific to the non-interactive stdio display
 
 use super::display::{
-    activity::{self, ActivityDescError},
-    duration::display_duration,
+    activity::{display_activity, display_profile_info, ActivityDesc, ActivityDisplayOptions},
+    display_string,
+    duration::{DurationFormat, Percentage},
     metadata::metadata,
     DisplayConfig,
 };
-use crate::{trace, CliArgs};
-use clang_time_trace::{
-    ActivityArgument, ActivityId, ActivityTrace, ActivityTraceId, ClangTrace, Duration,
+use crate::{
+    clang,
+    diagnostics::{ParseCoverage, ParseDiagnostics},
+    trace, CliArgs, Command,
+};
+use clang_time_trace::{ActivityId, ActivityTrace, ActivityTraceId, ClangTrace, Duration};
+use cmakeperf::commands::{CompilationDatabase, DatabaseEntry, ProductFreshness};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::{self, BufWriter, Write},
+    process::{self, Stdio},
+    sync::OnceLock,
 };
-use std::io;
 use termtree::{GlyphPalette, Tree};
 use unicode_width::UnicodeWidthStr;
 
@@ -20,7 +30,7 @@ pub fn run(args: CliArgs) {
     env_logger::init();
 
     // The stdio display does not support full-build profiling
-    let input = if let Some(input) = args.input {
+    let input = if let Some(input) = &args.input {
         input
     } else {
         return eprintln!(
@@ -45,66 +55,358 @@ pub fn run(args: CliArgs) {
         }
     };
 
+    // Set up the report output
+    let mut output = match report_output(&args) {
+        Ok(output) => output,
+        Err(e) => return eprintln!("Failed to create report output: {e}"),
+    };
+
+    // Write the report: either C++ parser coverage, phase metrics or the
+    // actual profile
+    let result = if args.coverage {
+        print_parse_coverage(&mut output, &mut trace, max_cols)
+    } else if args.phase_metrics {
+        print_phase_metrics(&mut output, &trace)
+    } else {
+        write_report(&mut output, &mut trace, &args, max_cols)
+    };
+    if let Err(e) = result.and_then(|()| output.flush()) {
+        eprintln!("Failed to write report: {e}");
+    }
+
+    // Conclude on parser/interner usage during this session
+    #[cfg(feature = "unstable_interner_stats")]
+    trace.log_interner_usage();
+}
+
+/// Set up the sink to which reports are written
+///
+/// This is the file specified via the `--output` CLI option, if any, and
+/// standard output otherwise.
+///
+fn report_output(args: &CliArgs) -> io::Result<Box<dyn io::Write>> {
+    Ok(match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout()),
+    })
+}
+
+/// Write the profile of a clang trace
+fn write_report(
+    output: &mut impl io::Write,
+    trace: &mut ClangTrace,
+    args: &CliArgs,
+    max_cols: u16,
+) -> io::Result<()> {
+    // Determine how durations should be displayed
+    let duration_format = args
+        .micros
+        .map_or(DurationFormat::Scaled, DurationFormat::GroupedMicros);
+    let display_options = super::display_options(args);
+
     // Display basic metadata
-    println!("\n{}", metadata(&trace, max_cols));
+    writeln!(output, "\n{}", metadata(trace, max_cols))?;
 
     // Use total clang execution time as a duration norm
     let duration_norm = trace::duration_norm(trace.root_activities());
 
     // Activity types by self-duration
     let self_threshold = args.self_threshold as Duration / 100.0;
-    print_activity_type_profile(&trace, duration_norm, self_threshold);
+    print_activity_type_profile(
+        output,
+        trace,
+        duration_norm,
+        self_threshold,
+        duration_format,
+    )?;
 
     // Flat activity profile by self-duration
-    print_flat_profile(&mut trace, duration_norm, self_threshold, max_cols);
+    print_flat_profile(
+        output,
+        trace,
+        duration_norm,
+        self_threshold,
+        max_cols,
+        (duration_format, display_options.clone()),
+        args.kind_legend,
+    )?;
 
     // Display hierarchical profile
-    print_hierarchical_profile(
-        &mut trace,
+    let config = TreeConfig {
+        palette: GlyphPalette {
+            middle_item: "├",
+            last_item: "└",
+            item_indent: "─",
+            middle_skip: "│",
+            last_skip: " ",
+            skip_indent: " ",
+        },
         duration_norm,
-        args.hierarchical_threshold as Duration / 100.0,
-        max_cols,
-    );
+        threshold: args.hierarchical_threshold as Duration / 100.0,
+        collapse_recursion: args.collapse_recursion,
+        fold_other: args.fold_other,
+        duration_format,
+        display_options,
+    };
+    print_hierarchical_profile(output, trace, &config, max_cols)?;
 
-    // Conclude on parser/interner usage during this session
-    #[cfg(feature = "unstable_interner_stats")]
-    trace.log_interner_usage();
+    // Display C++ parsing diagnostics, if requested
+    if args.verbose {
+        print_parse_diagnostics(output, trace, max_cols)?;
+    }
+    Ok(())
+}
+
+/// Profile a whole build directory using the stdio display
+pub fn run_aggregate(args: CliArgs) {
+    // Set up logging using env_logger
+    env_logger::init();
+
+    // Move to the build directory, where the compilation database resides
+    let Some(Command::Aggregate { build_dir }) = &args.command else {
+        unreachable!("Should only be called in aggregate mode")
+    };
+    if let Some(build_dir) = build_dir {
+        if let Err(e) = std::env::set_current_dir(build_dir) {
+            return eprintln!(
+                "Failed to enter build directory {}: {e}",
+                build_dir.display()
+            );
+        }
+    }
+
+    // Determine column budget
+    let max_cols = termion::terminal_size()
+        .map(|(width, _height)| width.min(args.max_cols))
+        .unwrap_or(args.max_cols);
+
+    // Load the compilation database
+    let database = match CompilationDatabase::load() {
+        Ok(database) => database,
+        Err(e) => return eprintln!("Failed to load compilation database: {e}"),
+    };
+
+    // Load the time-trace of every compilation unit, measuring it if needed
+    let mut entries = database.entries().collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|entry| entry.input());
+    eprintln!("Processing {} compilation unit(s)...", entries.len());
+    let mut clangpp = None;
+    let mut traces = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match load_or_measure_trace(entry, &mut clangpp, args.time_trace_granularity) {
+            Ok(trace) => traces.push(trace),
+            Err(e) => eprintln!("Ignoring {}: {e}", entry.input().display()),
+        }
+    }
+    if traces.is_empty() {
+        return eprintln!("No time-trace could be loaded, nothing to display");
+    }
+
+    // Display the aggregate profile
+    let mut output = match report_output(&args) {
+        Ok(output) => output,
+        Err(e) => return eprintln!("Failed to create report output: {e}"),
+    };
+    let result = writeln!(output, "\nAggregated {} time-trace(s)", traces.len())
+        .and_then(|()| {
+            print_aggregate_profile(
+                &mut output,
+                &traces,
+                args.self_threshold as Duration / 100.0,
+                max_cols,
+                args.micros
+                    .map_or(DurationFormat::Scaled, DurationFormat::GroupedMicros),
+            )
+        })
+        .and_then(|()| output.flush());
+    if let Err(e) = result {
+        eprintln!("Failed to write report: {e}");
+    }
+}
+
+/// Load the time-trace associated with a compilation database entry,
+/// measuring it first if it does not exist or is outdated
+///
+/// `clangpp` caches the result of the clang++ lookup, which is only performed
+/// when a time-trace must be measured.
+///
+fn load_or_measure_trace(
+    entry: &DatabaseEntry,
+    clangpp: &mut Option<Result<String, String>>,
+    time_trace_granularity: Option<u64>,
+) -> Result<ClangTrace, String> {
+    // Determine clang time-trace output file name
+    let mut output = entry.output().map_err(|e| {
+        format!(
+            "failed to extract output file from compilation command {:?} ({e})",
+            entry.raw_command()
+        )
+    })?;
+    output.set_extension("json");
+
+    // Measure the time-trace if needed
+    let freshness = entry
+        .derived_freshness(&output)
+        .map_err(|e| format!("failed to check time-trace freshness ({e})"))?;
+    if let ProductFreshness::Nonexistent | ProductFreshness::Outdated = freshness {
+        let clangpp = clangpp
+            .get_or_insert_with(|| {
+                clang::find_clangpp()
+                    .map(|program| program.as_ref().to_owned())
+                    .map_err(|e| e.to_string())
+            })
+            .as_ref()?;
+        eprintln!("Measuring {}...", output.display());
+        let mut command = process::Command::new(clangpp);
+        command.current_dir(entry.current_dir()).arg("-ftime-trace");
+        if let Some(granularity) = time_trace_granularity {
+            command.arg(format!("-ftime-trace-granularity={granularity}"));
+        }
+        for arg in entry.args() {
+            command.arg(arg.as_ref());
+        }
+        let status = command
+            .stdout(Stdio::null())
+            .status()
+            .map_err(|e| format!("failed to start clang++ ({e})"))?;
+        if !status.success() {
+            return Err(format!("clang++ failed with status {status}"));
+        }
+    }
+
+    // Load the time-trace
+    ClangTrace::from_file(&output)
+        .map_err(|e| format!("failed to load time-trace {} ({e})", output.display()))
+}
+
+/// Display the activities where most time is spent across several traces
+fn print_aggregate_profile(
+    output: &mut impl io::Write,
+    traces: &[ClangTrace],
+    threshold: Duration,
+    max_cols: u16,
+    duration_format: DurationFormat,
+) -> io::Result<()> {
+    // Use total clang execution time as a duration norm
+    let duration_norm = 1.0
+        / traces
+            .iter()
+            .map(|trace| {
+                trace
+                    .root_activities()
+                    .map(|root| root.duration())
+                    .sum::<Duration>()
+            })
+            .sum::<Duration>();
+
+    // Activity types and activities by self-duration, summed across traces
+    for (by_detail, header, items) in [
+        (
+            false,
+            "Self-duration breakdown by activity type:",
+            "activity types",
+        ),
+        (true, "Hottest activities by self-duration:", "activities"),
+    ] {
+        writeln!(output, "\n{header}")?;
+        let breakdown = trace::aggregate_breakdown(traces, by_detail);
+        for (idx, activity) in breakdown.iter().enumerate() {
+            if activity.self_duration * duration_norm < threshold {
+                writeln!(
+                    output,
+                    "- ... and {} other {items} below {} ...",
+                    breakdown.len() - idx,
+                    Percentage(threshold),
+                )?;
+                break;
+            }
+            write!(output, "- {}", activity.name)?;
+            if let Some(detail) = &activity.detail {
+                write!(output, "(")?;
+                display_string(
+                    &mut *output,
+                    detail,
+                    DisplayConfig::SingleLine {
+                        max_cols: max_cols
+                            .saturating_sub(activity.name.width() as u16 + 50)
+                            .max(1),
+                    },
+                )?;
+                write!(output, ")")?;
+            }
+            display_profile_info(
+                &mut *output,
+                activity.self_duration,
+                duration_norm,
+                duration_format,
+            )?;
+            writeln!(output, " in {} trace(s)", activity.per_trace.len())?;
+        }
+    }
+    Ok(())
 }
 
 /// Display the amount of time spent on various activity types
-fn print_activity_type_profile(trace: &ClangTrace, duration_norm: Duration, threshold: Duration) {
-    println!("\nSelf-duration breakdown by activity type:");
+fn print_activity_type_profile(
+    output: &mut impl io::Write,
+    trace: &ClangTrace,
+    duration_norm: Duration,
+    threshold: Duration,
+    duration_format: DurationFormat,
+) -> io::Result<()> {
+    writeln!(output, "\nSelf-duration breakdown by activity type:")?;
     let activity_type_breakdown = trace::activity_type_breakdown(trace);
     for (idx, (name, duration)) in activity_type_breakdown.iter().enumerate() {
         if duration * duration_norm < threshold {
-            println!(
-                "- ... and {} other activity types below {:.2}% ...",
+            writeln!(
+                output,
+                "- ... and {} other activity types below {} ...",
                 activity_type_breakdown.len() - idx,
-                threshold * 100.0,
-            );
+                Percentage(threshold),
+            )?;
             break;
         }
-        print!("- {name}");
-        display_profile_info(std::io::stdout(), *duration, duration_norm)
-            .expect("Writing to stdout shouldn't fail");
-        println!();
+        write!(output, "- {name}")?;
+        display_profile_info(&mut *output, *duration, duration_norm, duration_format)?;
+        writeln!(output)?;
     }
+    Ok(())
 }
 
 /// Display the hottest activities by the self_duration metric
+///
+/// If `kind_legend` is set, activity types are abbreviated using the codes
+/// from `activity_type_codes()`, and a legend is displayed first.
+///
 fn print_flat_profile(
+    output: &mut impl io::Write,
     trace: &mut ClangTrace,
     duration_norm: Duration,
     threshold: Duration,
     max_cols: u16,
-) {
-    println!("\nHottest activities by self-duration:");
+    (duration_format, display_options): (DurationFormat, ActivityDisplayOptions),
+    kind_legend: bool,
+) -> io::Result<()> {
     let hottest = trace::hottest_activities(
         trace.all_activities(),
         |a| a.self_duration() * duration_norm,
         threshold,
     );
     let hottest_ids = hottest.iter().map(ActivityTrace::id).collect::<Vec<_>>();
+
+    // Display the activity type legend, if requested
+    let codes = kind_legend
+        .then(|| activity_type_codes(hottest.iter().map(|activity| activity.activity().name())));
+    if let Some(codes) = &codes {
+        writeln!(output, "\nActivity type legend:")?;
+        let mut legend = codes.iter().collect::<Vec<_>>();
+        legend.sort_unstable_by_key(|(_name, code)| &**code);
+        for (name, code) in legend {
+            writeln!(output, "- {code} = {name}")?;
+        }
+    }
+
+    writeln!(output, "\nHottest activities by self-duration:")?;
     let num_hottest = hottest_ids.len();
     for id in hottest_ids.into_iter() {
         // Parse activity argument
@@ -112,81 +414,198 @@ fn print_flat_profile(
         let activity_trace = &trace.activity_trace(id);
 
         // Display activity
+        let activity_id = activity_trace.activity().id();
+        let mut desc = ActivityDesc::new(trace, activity_id, &parsed_arg);
+        if let Some(codes) = &codes {
+            desc = desc.with_label(&codes[activity_id.name()]);
+        }
         let duration = activity_trace.self_duration();
-        print!("- ");
+        write!(output, "- ")?;
         display_activity(
-            std::io::stdout(),
-            activity_trace.activity().id(),
-            &parsed_arg.resolve(trace),
+            &mut *output,
+            &desc,
             max_cols - 2,
             duration,
             duration_norm,
-        )
-        .expect("Writing to stdout shouldn't fail");
-        println!();
+            duration_format,
+            display_options.clone(),
+        )?;
+        writeln!(output)?;
     }
     let num_activities = trace.all_activities().count();
     if num_hottest < num_activities {
-        println!(
-            "- ... and {} other activities below {:.2}% ...",
+        writeln!(
+            output,
+            "- ... and {} other activities below {} ...",
             num_activities - num_hottest,
-            threshold * 100.0
-        );
+            Percentage(threshold)
+        )?;
+    }
+    Ok(())
+}
+
+/// Assign short codes to activity types, for use in compact reports
+///
+/// See `activity_type_code()` for how the codes are chosen.
+///
+fn activity_type_codes<'name>(
+    names: impl IntoIterator<Item = &'name str>,
+) -> BTreeMap<Box<str>, Box<str>> {
+    names
+        .into_iter()
+        .map(|name| (name.into(), activity_type_code(name)))
+        .collect()
+}
+
+/// Short code for an activity type, for use in compact reports
+///
+/// Codes are made of the initials of the words of the activity name, e.g.
+/// `IC` for `InstantiateClass` or `CGF` for `CodeGen Function`. Activity
+/// types whose initials clash get a numerical suffix, in alphabetical order
+/// of their names among all the activity types that crofiler knows about.
+/// A code thus only depends on the activity type, not on which other types
+/// are present in a given profile. Activity types that crofiler does not know
+/// about are not abbreviated.
+///
+fn activity_type_code(name: &str) -> Box<str> {
+    static KNOWN_CODES: OnceLock<HashMap<&'static str, Box<str>>> = OnceLock::new();
+    let known_codes = KNOWN_CODES.get_or_init(|| {
+        let names = ActivityId::known_names().collect::<BTreeSet<_>>();
+        let mut used_codes = HashSet::new();
+        names
+            .into_iter()
+            .map(|name| {
+                let initials = activity_type_initials(name);
+                let mut code = initials.clone();
+                let mut suffix = 2;
+                while !used_codes.insert(code.clone()) {
+                    code = format!("{initials}{suffix}");
+                    suffix += 1;
+                }
+                (name, code.into())
+            })
+            .collect()
+    });
+    known_codes
+        .get(name)
+        .cloned()
+        .unwrap_or_else(|| name.into())
+}
+
+/// Initials of the words of an activity name, see `activity_type_codes()`
+fn activity_type_initials(name: &str) -> String {
+    let mut initials = String::new();
+    let mut prev_alphanumeric = false;
+    for c in name.chars() {
+        if c.is_alphanumeric() && (!prev_alphanumeric || c.is_uppercase()) {
+            initials.extend(c.to_uppercase());
+        }
+        prev_alphanumeric = c.is_alphanumeric();
+    }
+    if initials.is_empty() {
+        name.to_owned()
+    } else {
+        initials
     }
 }
 
 /// Display a hierarchical profile
 fn print_hierarchical_profile(
+    output: &mut impl io::Write,
     trace: &mut ClangTrace,
-    duration_norm: Duration,
-    threshold: Duration,
+    config: &TreeConfig,
     max_cols: u16,
-) {
-    let palette = GlyphPalette {
-        middle_item: "├",
-        last_item: "└",
-        item_indent: "─",
-        middle_skip: "│",
-        last_skip: " ",
-        skip_indent: " ",
-    };
-    println!("\nHierarchical profile:");
+) -> io::Result<()> {
+    writeln!(output, "\nHierarchical profile:")?;
     assert_eq!(trace.root_activities().count(), 1);
     let root_id = trace
         .root_activities()
         .next()
         .expect("There should be one ExecuteCompiler root activity")
         .id();
-    println!(
+    writeln!(
+        output,
         "{}",
-        hierarchical_profile_tree(trace, palette, root_id, duration_norm, threshold, max_cols)
-    );
+        hierarchical_profile_tree(trace, config, root_id, max_cols)
+    )
+}
+
+/// Configuration of the hierarchical profile display
+#[derive(Clone)]
+struct TreeConfig {
+    /// Glyphs used to draw the tree
+    palette: GlyphPalette,
+
+    /// Norm used to turn durations into percentages
+    duration_norm: Duration,
+
+    /// Normalized duration below which callees are not displayed
+    threshold: Duration,
+
+    /// Collapse chains of recursive activities into a single node
+    collapse_recursion: bool,
+
+    /// Fold callees below the threshold into a single "(other)" node
+    fold_other: bool,
+
+    /// Way durations should be displayed
+    duration_format: DurationFormat,
+
+    /// Optional features of activity displays
+    display_options: ActivityDisplayOptions,
 }
 
 /// Make a tree display of the hierarchical profile of some build
 fn hierarchical_profile_tree(
     trace: &mut ClangTrace,
-    palette: GlyphPalette,
+    config: &TreeConfig,
     root_id: ActivityTraceId,
-    duration_norm: Duration,
-    threshold: Duration,
     max_cols: u16,
 ) -> Tree<Box<str>> {
+    let TreeConfig {
+        palette,
+        duration_norm,
+        threshold,
+        collapse_recursion,
+        fold_other,
+        duration_format,
+        ref display_options,
+    } = *config;
+
     // Parse root node argument
     let root_parsed_arg = crate::ui::force_parse_arg(trace, root_id);
     let root = trace.activity_trace(root_id);
 
+    // If requested, collapse recursive activities into the root node, and
+    // annotate it with the recursion depth if there is enough room for that
+    let (parent_id, mut annotation) = if collapse_recursion {
+        let chain = trace::recursion_chain(trace, root_id);
+        let annotation = if chain.depth > 1 {
+            format!(" ×{}", chain.depth)
+        } else {
+            String::new()
+        };
+        (chain.inner, annotation)
+    } else {
+        (root_id, String::new())
+    };
+    if annotation.width() >= max_cols.into() {
+        annotation.clear();
+    }
+
     // Render root node
     let mut root_display = Vec::<u8>::new();
     display_activity(
         &mut root_display,
-        root.activity().id(),
-        &root_parsed_arg.resolve(trace),
-        max_cols,
+        &ActivityDesc::new(trace, root.activity().id(), &root_parsed_arg),
+        max_cols - annotation.width() as u16,
         root.duration(),
         duration_norm,
+        duration_format,
+        display_options.clone(),
     )
     .expect("Writing to a collection shouldn't fail");
+    root_display.extend_from_slice(annotation.as_bytes());
     let root_display = String::from_utf8(root_display)
         .expect("display_activity shouldn't produce non-UTF8 bytes")
         .into_boxed_str();
@@ -201,9 +620,10 @@ fn hierarchical_profile_tree(
     }
 
     // Collect hottest children
-    let num_children = root.direct_children().count();
-    let hottest_children = trace::hottest_activities(
-        root.direct_children(),
+    let parent = trace.activity_trace(parent_id);
+    let num_children = parent.direct_children().count();
+    let (hottest_children, folded) = trace::fold_activities(
+        parent.direct_children(),
         |a| a.duration() * duration_norm,
         threshold,
     );
@@ -214,25 +634,25 @@ fn hierarchical_profile_tree(
     let num_hottest = hottest_ids.len();
 
     // Render hottest children
-    let make_child_tree = |child_id| {
-        hierarchical_profile_tree(
-            trace,
-            palette,
-            child_id,
-            duration_norm,
-            threshold,
-            child_cols,
-        )
-    };
+    let make_child_tree = |child_id| hierarchical_profile_tree(trace, config, child_id, child_cols);
     tree = if num_hottest == num_children {
         tree.with_leaves(hottest_ids.into_iter().map(make_child_tree))
     } else {
-        // If there are more children, warn about it
-        let mut terminator = format!(
-            "…{} callee(s) below {:.2}%…",
-            num_children - num_hottest,
-            threshold * 100.0
-        );
+        // If there are more children, either fold them into an aggregate
+        // node or warn about them
+        let mut terminator = match folded {
+            Some(folded) if fold_other => {
+                let mut other = format!("(other ×{})", folded.count).into_bytes();
+                display_profile_info(&mut other, folded.duration, duration_norm, duration_format)
+                    .expect("Writing to a collection shouldn't fail");
+                String::from_utf8(other).expect("display_profile_info should emit UTF-8")
+            }
+            _ => format!(
+                "…{} callee(s) below {}…",
+                num_children - num_hottest,
+                Percentage(threshold)
+            ),
+        };
         if terminator.width() > child_cols.into() {
             terminator.clear();
             terminator.push('…');
@@ -249,70 +669,312 @@ fn hierarchical_profile_tree(
     tree
 }
 
-/// Display an activity trace, ideally with associated profiling information
-fn display_activity(
-    mut output: impl io::Write,
-    activity_id: &ActivityId,
-    activity_arg: &ActivityArgument,
+/// Display the most common patterns of C++ symbol/entity parsing failure
+fn print_parse_diagnostics(
+    output: &mut impl io::Write,
+    trace: &mut ClangTrace,
     max_cols: u16,
-    duration: Duration,
-    duration_norm: Duration,
 ) -> io::Result<()> {
-    assert!(max_cols >= 1);
-
-    // Display the trailing profiling numbers in a private string to know its
-    // display width and how many columns that leaves for the activity id.
-    let mut trailer = Vec::<u8>::new();
-    display_profile_info(&mut trailer, duration, duration_norm)?;
-    let trailer = std::str::from_utf8(&trailer[..])
-        .expect("display_profile_info shouldn't produce non-UTF8 bytes");
-    let other_cols = max_cols.saturating_sub(trailer.width() as u16);
-
-    // Try to display both the activity id and the profiling numbers
-    match activity::display_activity_desc(
-        &mut output,
-        activity_id,
-        activity_arg,
-        DisplayConfig::SingleLine {
-            max_cols: other_cols,
-        },
-    ) {
-        Ok(false) => {
-            // Success, can just print out the profiling numbers
-            write!(output, "{trailer}")
-        }
-        Err(ActivityDescError::NotEnoughCols(_)) => {
-            // Not enough space for both, try to display activity ID alone
-            match activity::display_activity_desc(
-                &mut output,
-                activity_id,
-                activity_arg,
-                DisplayConfig::SingleLine { max_cols },
-            ) {
-                Ok(false) => Ok(()),
-                Err(ActivityDescError::IoError(e)) => Err(e),
-                Err(ActivityDescError::NotEnoughCols(_)) => {
-                    // Seems the best we can do is an ellipsis placeholder...
-                    write!(output, "…")
-                }
-                Ok(true) => unreachable!("No wrapping on single-line display"),
-            }
-        }
-        Err(ActivityDescError::IoError(e)) => Err(e),
-        Ok(true) => unreachable!("No wrapping on single-line displays"),
+    /// Maximal number of failure patterns to be displayed
+    const MAX_PATTERNS: usize = 10;
+
+    let diagnostics = ParseDiagnostics::collect(trace);
+    writeln!(
+        output,
+        "\nC++ symbols and entities that could not be parsed: {}",
+        diagnostics.num_failures()
+    )?;
+    let top_patterns = diagnostics.top_patterns(MAX_PATTERNS);
+    let example_cols = max_cols.saturating_sub(8).max(1);
+    for (pattern, stats) in top_patterns.iter() {
+        writeln!(output, "- {pattern} [{} occurence(s)]", stats.count)?;
+        write!(output, "  in {}(", stats.activity)?;
+        display_string(
+            &mut *output,
+            &stats.example,
+            DisplayConfig::SingleLine {
+                max_cols: example_cols
+                    .saturating_sub(stats.activity.width() as u16 + 1)
+                    .max(1),
+            },
+        )?;
+        writeln!(output, ")")?;
+        write!(output, "  ")?;
+        display_string(
+            &mut *output,
+            &stats.error,
+            DisplayConfig::SingleLine {
+                max_cols: max_cols.saturating_sub(2).max(1),
+            },
+        )?;
+        writeln!(output)?;
+    }
+    if top_patterns.len() < diagnostics.num_patterns() {
+        writeln!(
+            output,
+            "- ... and {} other failure patterns ...",
+            diagnostics.num_patterns() - top_patterns.len()
+        )?;
     }
+    Ok(())
 }
 
-/// Display profiling information (absolute and relative duration)
-fn display_profile_info(
-    mut output: impl io::Write,
-    duration: Duration,
-    duration_norm: Duration,
+/// Display which fraction of C++ entities and symbols could be parsed
+fn print_parse_coverage(
+    output: &mut impl io::Write,
+    trace: &mut ClangTrace,
+    max_cols: u16,
 ) -> io::Result<()> {
-    write!(output, " [")?;
-    display_duration(&mut output, duration)?;
-    let percent = duration * duration_norm * 100.0;
-    write!(output, ", {percent:.2}%]")
+    let coverage = ParseCoverage::measure(trace);
+    writeln!(
+        output,
+        "\nParsed {} out of {} C++ entities and symbols ({})",
+        coverage.num_parsed(),
+        coverage.num_args(),
+        Percentage(coverage.fraction())
+    )?;
+    writeln!(output, "\nCoverage by activity type:")?;
+    let failure_cols = max_cols.saturating_sub(4).max(1);
+    for (name, activity) in coverage.by_activity().iter() {
+        writeln!(
+            output,
+            "- {name}: {}/{} ({})",
+            activity.num_parsed,
+            activity.num_args,
+            Percentage(activity.fraction())
+        )?;
+        for failure in &activity.failures {
+            write!(output, "  * ")?;
+            display_string(
+                &mut *output,
+                failure,
+                DisplayConfig::SingleLine {
+                    max_cols: failure_cols,
+                },
+            )?;
+            writeln!(output)?;
+        }
+    }
+    Ok(())
 }
 
-// FIXME: Add tests ?
+/// Phases which are always reported by the phase metrics, in this order
+const CANONICAL_PHASES: [ActivityId; 2] = [ActivityId::Frontend, ActivityId::Backend];
+
+/// Display the compilation phase breakdown as a line of key=value metrics
+///
+/// The canonical phases are always reported, even if they did not occur, then
+/// other phases follow in key order, and the total compilation time comes last.
+///
+fn print_phase_metrics(output: &mut impl io::Write, trace: &ClangTrace) -> io::Result<()> {
+    let mut phases = trace.phase_breakdown().into_vec();
+    let mut metrics = Vec::new();
+    for canonical in &CANONICAL_PHASES {
+        let duration = phases
+            .iter()
+            .position(|(id, _)| id == canonical)
+            .map_or(0.0, |idx| phases.swap_remove(idx).1.duration);
+        metrics.push((phase_metric_key(canonical), duration));
+    }
+    let mut others = phases
+        .into_iter()
+        .map(|(id, phase)| (phase_metric_key(&id), phase.duration))
+        .collect::<Vec<_>>();
+    others.sort_unstable_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    metrics.extend(others);
+    let total = trace.root_activities().map(|root| root.duration()).sum();
+    metrics.push(("total_us".to_owned(), total));
+
+    let line = metrics
+        .into_iter()
+        .map(|(key, duration)| format!("{key}={duration:.0}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(output, "{line}")
+}
+
+/// Metric key associated with a compilation phase (e.g. "frontend_us")
+fn phase_metric_key(id: &ActivityId) -> String {
+    let name = id
+        .name()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{name}_us")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::with_test_trace;
+    use clap::Parser;
+
+    #[test]
+    fn report_to_file() {
+        with_test_trace(|trace| {
+            // Write the report to an in-memory buffer, as a stand-in for stdout
+            let args = CliArgs::parse_from(["crofiler", "--ui=stdio", "-v", "trace.json"]);
+            let mut expected = Vec::new();
+            write_report(&mut expected, trace, &args, 120).unwrap();
+            assert!(!expected.is_empty());
+
+            // Write it to a file via --output, check the contents are the same
+            let report_dir = tempfile::tempdir().unwrap();
+            let report_path = report_dir.path().join("report.txt");
+            let args = CliArgs::parse_from([
+                "crofiler".as_ref(),
+                "--ui=stdio".as_ref(),
+                "-v".as_ref(),
+                "--output".as_ref(),
+                report_path.as_os_str(),
+                "trace.json".as_ref(),
+            ]);
+            let mut output = report_output(&args).unwrap();
+            write_report(&mut output, trace, &args, 120).unwrap();
+            output.flush().unwrap();
+            std::mem::drop(output);
+            assert_eq!(std::fs::read(&report_path).unwrap(), expected);
+        })
+    }
+
+    #[test]
+    fn phase_metrics() {
+        with_test_trace(|trace| {
+            let mut output = Vec::new();
+            print_phase_metrics(&mut output, trace).unwrap();
+            let output = String::from_utf8(output).unwrap();
+            assert!(output.ends_with('\n'));
+            assert_eq!(output.lines().count(), 1);
+
+            let metrics = output
+                .trim_end()
+                .split(' ')
+                .map(|metric| {
+                    let (key, value) = metric.split_once('=').unwrap();
+                    (key, value.parse::<u64>().unwrap())
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                metrics.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+                ["frontend_us", "backend_us", "total_us"]
+            );
+
+            let phases = trace.phase_breakdown();
+            let expected_duration = |id| {
+                let (_, phase) = phases.iter().find(|(phase_id, _)| *phase_id == id).unwrap();
+                phase.duration.round() as u64
+            };
+            assert_eq!(metrics[0].1, expected_duration(ActivityId::Frontend));
+            assert_eq!(metrics[1].1, expected_duration(ActivityId::Backend));
+            let total = trace
+                .root_activities()
+                .map(|root| root.duration())
+                .sum::<Duration>();
+            assert_eq!(metrics[2].1, total.round() as u64);
+        })
+    }
+
+    #[test]
+    fn activity_type_codes() {
+        let codes = super::activity_type_codes([
+            "InstantiateClass",
+            "CodeGen Function",
+            "Frontend",
+            "InstantiateClass",
+            "Unknown activity",
+        ]);
+        let codes = codes
+            .iter()
+            .map(|(name, code)| (&**name, &**code))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                ("CodeGen Function", "CGF"),
+                ("Frontend", "F"),
+                ("InstantiateClass", "IC"),
+                ("Unknown activity", "Unknown activity"),
+            ]
+        );
+
+        // Codes do not depend on which other activity types are present
+        for other in ["InstantiateFunction", "IPSCCPPass", "InstCombinePass"] {
+            let codes = super::activity_type_codes(["InstantiateClass", other]);
+            assert_eq!(&*codes["InstantiateClass"], "IC");
+        }
+
+        // Codes of known activity types are unambiguous
+        let all_codes = super::activity_type_codes(ActivityId::known_names());
+        assert_eq!(
+            all_codes.values().collect::<HashSet<_>>().len(),
+            all_codes.len()
+        );
+    }
+
+    #[test]
+    fn kind_legend() {
+        with_test_trace(|trace| {
+            let duration_norm = crate::trace::duration_norm(trace.root_activities());
+            let threshold = 0.002;
+            let mut output = Vec::new();
+            print_flat_profile(
+                &mut output,
+                trace,
+                duration_norm,
+                threshold,
+                200,
+                (DurationFormat::Scaled, ActivityDisplayOptions::default()),
+                true,
+            )
+            .unwrap();
+            let output = String::from_utf8(output).unwrap();
+
+            // Extract the legend and the coded rows
+            let (legend, rows) = output
+                .trim_start()
+                .strip_prefix("Activity type legend:\n")
+                .unwrap()
+                .split_once("\n\nHottest activities by self-duration:\n")
+                .unwrap();
+            let legend = legend
+                .lines()
+                .map(|line| {
+                    let (code, name) = line.strip_prefix("- ").unwrap().split_once(" = ").unwrap();
+                    (code, name)
+                })
+                .collect::<HashMap<_, _>>();
+            let rows = rows
+                .lines()
+                .filter(|row| !row.starts_with("- ... "))
+                .map(|row| row.strip_prefix("- ").unwrap())
+                .collect::<Vec<_>>();
+
+            // Every row maps back to the activity type of the matching activity
+            let hottest = crate::trace::hottest_activities(
+                trace.all_activities(),
+                |a| a.self_duration() * duration_norm,
+                threshold,
+            );
+            assert_eq!(rows.len(), hottest.len());
+            for (row, activity) in rows.iter().zip(hottest.iter()) {
+                let code = row.split(['(', ' ']).next().unwrap();
+                assert_eq!(legend[code], activity.activity().name(), "{row}");
+            }
+
+            // The legend covers every activity type, and nothing else
+            let names = hottest
+                .iter()
+                .map(|activity| activity.activity().name())
+                .collect::<HashSet<_>>();
+            assert!(names.len() > 1);
+            assert_eq!(legend.len(), names.len());
+            assert_eq!(legend.values().copied().collect::<HashSet<_>>(), names);
+        })
+    }
+}
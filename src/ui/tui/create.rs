@@ -44,6 +44,9 @@ impl CreatePrompt {
                     None
                 }
             }
+
+            // There is a profile and it is known to be up to date
+            ProductFreshness::Fresh => None,
         }
     }
 
@@ -1,6 +1,6 @@
 //! Initialization of the text user interface
 
-use super::{names::ViewName::GlobalDialog, trace, State};
+use super::{names::ViewName::GlobalDialog, processing::ProfileSuffix, trace, State};
 use cursive::{
     event::{Event, Key},
     traits::Scrollable,
@@ -57,6 +57,9 @@ pub fn setup_cursive(state: State) -> CursiveRunnable {
     // B displays an interactive backtrace
     set_global_dialog_callback(&mut cursive, 'b', backtrace_dialog);
 
+    // S searches activities by file path
+    set_global_dialog_callback(&mut cursive, 's', trace::search::search_dialog);
+
     // Set up help text
     set_global_dialog_callback(&mut cursive, 'h', super::help_dialog);
 
@@ -64,6 +67,9 @@ pub fn setup_cursive(state: State) -> CursiveRunnable {
     cursive
 }
 
+/// Horizontal space taken by the backtrace dialog's decorations
+const BACKTRACE_MARGIN: u16 = 8;
+
 /// Exit the current cursive layer if there's another one underneath, keep the
 /// profile layer tracking up to date while doing so.
 fn exit_current_layer(cursive: &mut Cursive) {
@@ -86,15 +92,43 @@ fn exit_current_layer(cursive: &mut Cursive) {
 
 /// Interactive backtrace dialog
 fn backtrace_dialog(cursive: &mut Cursive) -> Option<Dialog> {
+    // Leave room for the dialog's borders, padding and scrollbar
+    let (terminal_width, _terminal_height) =
+        termion::terminal_size().expect("Could not read terminal configuration");
+    let max_cols = terminal_width.saturating_sub(BACKTRACE_MARGIN);
+
+    // Profiled activities are described along with their duration, other
+    // profiles (e.g. the profile root) are described by their name
     let mut select = SelectView::new();
     let layers_below_profile = super::with_state(cursive, |state| {
-        select.add_all(
+        let activity_descs = state.processing_thread.describe_activities(
             state
                 .profile_stack
                 .iter()
+                .filter_map(|profile| profile.parent_id())
+                .collect(),
+            max_cols,
+            Some(ProfileSuffix::Duration),
+        );
+        let mut activity_descs = activity_descs.iter();
+        let names = state
+            .profile_stack
+            .iter()
+            .map(|profile| match profile.parent_id() {
+                Some(_) => String::from(
+                    &**activity_descs
+                        .next()
+                        .expect("There should be one description per activity"),
+                ),
+                None => String::from(profile.parent_name()),
+            })
+            .collect::<Vec<_>>();
+        select.add_all(
+            names
+                .into_iter()
                 .enumerate()
                 .rev()
-                .map(|(idx, profile)| (String::from(profile.parent_name()), idx)),
+                .map(|(idx, name)| (name, idx)),
         );
         state.layers_below_profile
     });
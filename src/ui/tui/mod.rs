@@ -11,6 +11,7 @@ use self::{
     processing::ProcessingThread,
     trace::display::{ProfileDisplay, ProfileLayer},
 };
+use super::display::activity::ActivityDisplayOptions;
 use crate::CliArgs;
 use clang_time_trace::Duration;
 use cursive::{views::Dialog, Cursive};
@@ -44,9 +45,18 @@ pub fn run(args: CliArgs) {
     enable_panic_logging();
 
     // Start the processing thread and set up the text user interface
+    // Cursive cannot render hyperlink escape sequences
+    let display_options = ActivityDisplayOptions {
+        hyperlink_paths: false,
+        ..super::display_options(&args)
+    };
     let mut cursive = init::setup_cursive(State {
-        processing_thread: ProcessingThread::start(),
+        processing_thread: ProcessingThread::start(display_options.clone()),
+        display_options,
         global_percent_norm: None,
+        fold_threshold: args
+            .fold_other
+            .then_some(args.hierarchical_threshold as Duration / 100.0),
         profile_stack: Vec::new(),
         showing_full_build: false,
         layers_below_profile: 0,
@@ -81,12 +91,19 @@ pub struct State {
     /// Handle to the processing thread
     processing_thread: ProcessingThread,
 
+    /// Options used by the processing thread to display activities
+    display_options: ActivityDisplayOptions,
+
     /// Norm to compute percentages of the full clang execution time
     ///
     /// Will be set when the first layer of hierarchical profiling is displayed.
     ///
     global_percent_norm: Option<Finite<Duration>>,
 
+    /// Fraction of the full clang execution time below which the callees of an
+    /// activity are folded into a single "(other)" row, if enabled
+    fold_threshold: Option<Duration>,
+
     /// Current stack of profiling UI layers
     profile_stack: Vec<ProfileLayer>,
 
@@ -126,6 +143,7 @@ fn help_dialog(cursive: &mut Cursive) -> Option<Dialog> {
         Self is that minus the time spent on callees\n\
         Activity is what clang was doing\n\
         + means that there are callees to zoom on\n\
+        (other) merges the callees folded by --fold-other\n\
         \n\
         Available commands:\n\
         - Up/Down selects an activity\n\
@@ -136,6 +154,7 @@ fn help_dialog(cursive: &mut Cursive) -> Option<Dialog> {
         - U switches between duration units\n\
         - B shows the backtrace to the current activity\n\
         - F toggles between flat and hierarchical profiles\n\
+        - S searches activities by file path\n\
         - Q quits this program\n\
         \n\
         Logs go to syslog to avoid display corruption"
@@ -1,21 +1,30 @@
 //! Processing thread of the TUI interface (owns the ClangTrace and takes care
 //! of all the expensive rendering operations to allow good responsiveness).
 
-use crate::ui::display::{
-    activity::{display_activity_desc, ActivityDescError},
-    metadata::metadata,
-    DisplayConfig,
+use crate::{
+    trace::{self, FoldedActivities, TraceSummary},
+    ui::display::{
+        activity::{
+            display_activity, display_activity_desc, ActivityDesc, ActivityDescError,
+            ActivityDisplayOptions,
+        },
+        duration::DurationFormat,
+        metadata::metadata,
+        DisplayConfig,
+    },
 };
 use clang_time_trace::{
-    ActivityTrace, ActivityTraceId, ClangTrace, ClangTraceLoadError, Duration,
-    ParsedActivityArgument,
+    ActivityArgument, ActivityArgumentType, ActivityTrace, ActivityTraceId, ClangTrace,
+    ClangTraceLoadError, Duration, ParsedActivityArgument,
 };
 
 use std::{
     collections::HashMap,
     io::Write,
     path::Path,
+    rc::Rc,
     sync::{
+        atomic::{AtomicBool, Ordering},
         mpsc::{self, Receiver, Sender},
         Arc,
     },
@@ -28,6 +37,13 @@ pub type ActivityInfoList = Box<[ActivityInfo]>;
 /// Owned list of activity descriptions
 pub type ActivityDescList = Box<[Arc<str>]>;
 
+/// Set of activity kinds, as named by `Activity::name()`
+pub type ActivityKinds = Box<[Box<str>]>;
+
+/// List of activities above some duration threshold, along with an aggregate
+/// of the activities below that threshold (if any)
+pub type FoldedActivityList = (ActivityInfoList, Option<FoldedActivities>);
+
 /// Encapsulation of the processing thread
 #[derive(Debug)]
 pub struct ProcessingThread {
@@ -45,27 +61,29 @@ pub struct ProcessingThread {
     /// Channel to receive lists of activities from the processing thread
     activities_receiver: Receiver<ActivityInfoList>,
 
-    /// Channel to receive activity descriptions from the processing thread
-    descs_receiver: Receiver<ActivityDescList>,
+    /// Channel to receive folded lists of activities from the processing thread
+    folded_receiver: Receiver<FoldedActivityList>,
 }
 //
 impl ProcessingThread {
-    /// Start the processing thread
-    pub fn start() -> Self {
+    /// Start the processing thread, which will display activities using the
+    /// specified options
+    pub fn start(display_options: ActivityDisplayOptions) -> Self {
         // Set up processing thread state and communication channels
         let (instruction_sender, instruction_receiver) = mpsc::channel();
         let (string_sender, string_receiver) = mpsc::channel();
         let (activities_sender, activities_receiver) = mpsc::channel();
-        let (descs_sender, descs_receiver) = mpsc::channel();
+        let (folded_sender, folded_receiver) = mpsc::channel();
 
         // Spawn the processing thread
         let handle = thread::spawn(move || {
             // Process instructions until the main thread hangs up
             worker(
+                display_options,
                 instruction_receiver,
                 string_sender,
                 activities_sender,
-                descs_sender,
+                folded_sender,
             );
         });
 
@@ -75,7 +93,7 @@ impl ProcessingThread {
             instruction_sender,
             string_receiver,
             activities_receiver,
-            descs_receiver,
+            folded_receiver,
         }
     }
 
@@ -85,7 +103,8 @@ impl ProcessingThread {
     /// loading screen by the UI thread. But the processing thread itself will
     /// be unresponsive for the duration of the loading process.
     ///
-    /// Once the trace is loaded, the callback will be called with the result.
+    /// Once the trace is loaded, the callback will be called with the result,
+    /// which summarizes the trace's main properties on success.
     ///
     pub fn start_load_trace(
         &mut self,
@@ -111,9 +130,9 @@ impl ProcessingThread {
         desc
     }
 
-    /// Get the list of root activities
-    pub fn get_root_activities(&self) -> ActivityInfoList {
-        self.request(Instruction::GetRootActivities);
+    /// Get the list of root activities, optionally restricted to some kinds
+    pub fn get_root_activities(&self, kinds: Option<ActivityKinds>) -> ActivityInfoList {
+        self.request(Instruction::GetRootActivities(kinds));
         Self::fetch(&self.activities_receiver)
     }
 
@@ -123,29 +142,79 @@ impl ProcessingThread {
         Self::fetch(&self.activities_receiver)
     }
 
-    /// Get the list of a node's direct children
-    pub fn get_direct_children(&self, id: ActivityTraceId) -> ActivityInfoList {
-        self.request(Instruction::GetDirectChildren(id));
+    /// Get the list of a node's direct children, optionally restricted to
+    /// some activity kinds
+    pub fn get_direct_children(
+        &self,
+        id: ActivityTraceId,
+        kinds: Option<ActivityKinds>,
+    ) -> ActivityInfoList {
+        self.request(Instruction::GetDirectChildren(id, kinds));
         Self::fetch(&self.activities_receiver)
     }
 
+    /// Get the list of a node's direct children whose duration is above a
+    /// threshold, with the other children folded into an aggregate
+    pub fn get_folded_children(
+        &self,
+        id: ActivityTraceId,
+        threshold: Duration,
+    ) -> FoldedActivityList {
+        self.request(Instruction::GetFoldedChildren { id, threshold });
+        Self::fetch(&self.folded_receiver)
+    }
+
     /// Get the list of all of a node's direct children
     pub fn get_all_children(&self, id: ActivityTraceId) -> ActivityInfoList {
         self.request(Instruction::GetAllChildren(id));
         Self::fetch(&self.activities_receiver)
     }
 
-    /// Describe a set of activities
+    /// Get the list of activities operating on a file whose path contains
+    /// a certain substring
+    pub fn find_by_file_path(&self, substring: impl Into<Box<str>>) -> ActivityInfoList {
+        self.request(Instruction::FindByFilePath {
+            substring: substring.into(),
+        });
+        Self::fetch(&self.activities_receiver)
+    }
+
+    /// Describe a set of activities, optionally followed by profiling info
     pub fn describe_activities(
         &self,
         activities: Box<[ActivityTraceId]>,
         max_cols: u16,
+        suffix: Option<ProfileSuffix>,
     ) -> ActivityDescList {
-        self.request(Instruction::DescribeActivities {
+        self.stream_activity_descriptions(activities, max_cols, suffix)
+            .map(|(_id, desc)| desc)
+            .collect()
+    }
+
+    /// Describe a set of activities like `describe_activities()`, but stream
+    /// the descriptions back one by one as soon as they are computed
+    ///
+    /// This lets the UI display the first descriptions of a long list while
+    /// the following ones are being rendered. The processing thread only
+    /// handles subsequent requests once all descriptions have been sent, or
+    /// the stream has been canceled or dropped.
+    ///
+    pub fn stream_activity_descriptions(
+        &self,
+        activities: Box<[ActivityTraceId]>,
+        max_cols: u16,
+        suffix: Option<ProfileSuffix>,
+    ) -> DescriptionStream {
+        let (sender, receiver) = mpsc::channel();
+        let canceled = Arc::new(AtomicBool::new(false));
+        self.request(Instruction::StreamActivityDescriptions {
             activities,
             max_cols,
+            suffix,
+            sender,
+            canceled: canceled.clone(),
         });
-        Self::fetch(&self.descs_receiver)
+        DescriptionStream { receiver, canceled }
     }
 
     /// Describe a single activity fully, tell if the result should be line-wrapped
@@ -171,8 +240,49 @@ impl ProcessingThread {
     }
 }
 
+/// Activity descriptions being streamed back by the processing thread
+///
+/// Iterating over this yields `(id, description)` pairs in request order,
+/// blocking until the next description is available, and ends once all
+/// requested descriptions have been received or the stream has been canceled.
+///
+pub struct DescriptionStream {
+    /// Channel to receive activity descriptions from the processing thread
+    receiver: Receiver<(ActivityTraceId, Arc<str>)>,
+
+    /// Cancelation flag
+    canceled: Arc<AtomicBool>,
+}
+//
+impl DescriptionStream {
+    /// Stop rendering descriptions
+    ///
+    /// Descriptions which were already sent by the processing thread can still
+    /// be received, after which the stream ends.
+    ///
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::Relaxed);
+    }
+}
+//
+impl Drop for DescriptionStream {
+    /// Dropping the stream cancels it, so that the processing thread stops
+    /// before rendering the next description instead of after it
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+//
+impl Iterator for DescriptionStream {
+    type Item = (ActivityTraceId, Arc<str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
 /// Result of the ClangTrace loading process
-pub type ClangTraceLoadResult = Result<(), ClangTraceLoadError>;
+pub type ClangTraceLoadResult = Result<TraceSummary, ClangTraceLoadError>;
 
 /// Basic activity data as emitted by the processing thread
 ///
@@ -191,8 +301,19 @@ pub struct ActivityInfo {
     /// Time spent specificially processing this activity
     pub self_duration: Duration,
 
-    /// Truth that this activity has children
-    pub has_children: bool,
+    /// Truth that this activity has no children
+    pub is_leaf: bool,
+}
+
+/// Profiling information that can be appended to activity descriptions
+///
+/// Durations are displayed both in absolute terms and as a percentage of the
+/// total duration of the trace's root activities.
+///
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ProfileSuffix {
+    /// Time spent processing the activity or one of its callees
+    Duration,
 }
 
 /// Instructions that can be sent to the processing thread
@@ -203,22 +324,40 @@ enum Instruction {
     /// Render trace-wide metadata for a certain terminal width
     DescribeTrace { max_cols: u16 },
 
-    /// Get the list of root nodes (reply via activities channel)
-    GetRootActivities,
+    /// Get the list of root nodes, optionally restricted to some activity
+    /// kinds (reply via activities channel)
+    GetRootActivities(Option<ActivityKinds>),
 
     /// Get the list of all activities (reply via activities channel)
     GetAllActivities,
 
-    /// Get the list of a node's direct children (reply via activities channel)
-    GetDirectChildren(ActivityTraceId),
+    /// Get the list of a node's direct children, optionally restricted to
+    /// some activity kinds (reply via activities channel)
+    GetDirectChildren(ActivityTraceId, Option<ActivityKinds>),
+
+    /// Get the list of a node's direct children whose duration is above a
+    /// threshold, folding the others into an aggregate (reply via folded
+    /// activities channel)
+    GetFoldedChildren {
+        id: ActivityTraceId,
+        threshold: Duration,
+    },
 
     /// Get the list of all a node's children (reply via activities channel)
     GetAllChildren(ActivityTraceId),
 
-    /// Display a set of activity descriptions in one-line format
-    DescribeActivities {
+    /// Get the list of activities whose file path argument contains a
+    /// certain substring (reply via activities channel)
+    FindByFilePath { substring: Box<str> },
+
+    /// Display a set of activity descriptions in one-line format, streaming
+    /// them back through a dedicated channel until canceled
+    StreamActivityDescriptions {
         activities: Box<[ActivityTraceId]>,
         max_cols: u16,
+        suffix: Option<ProfileSuffix>,
+        sender: Sender<(ActivityTraceId, Arc<str>)>,
+        canceled: Arc<AtomicBool>,
     },
 
     /// Display a single activity in multi-line format, tell if the result
@@ -231,10 +370,11 @@ enum Instruction {
 
 /// Processing thread worker
 fn worker(
+    display_options: ActivityDisplayOptions,
     instructions: Receiver<Instruction>,
     string: Sender<(String, bool)>,
     activities: Sender<ActivityInfoList>,
-    strings: Sender<ActivityDescList>,
+    folded: Sender<FoldedActivityList>,
 ) {
     // Set up caches for activity parsing and rendering, which are costly
     let mut trace = None;
@@ -244,8 +384,10 @@ fn worker(
             .expect("Client requested trace analysis before loading a trace")
     }
     let mut parsed_arg_cache = HashMap::new();
+    let mut activity_desc_cache = HashMap::new();
     let mut description_cache = HashMap::new();
     let mut last_max_cols = 0;
+    let mut last_suffix = None;
 
     // Process instructions until the main thread hangs up
     for instruction in instructions.iter() {
@@ -253,10 +395,11 @@ fn worker(
             // Load a trace
             Instruction::LoadTrace(path, callback) => {
                 parsed_arg_cache.clear();
+                activity_desc_cache.clear();
                 description_cache.clear();
                 trace = match ClangTrace::from_file(path) {
                     Ok(trace) => {
-                        callback(Ok(()));
+                        callback(Ok(TraceSummary::new(&trace)));
                         Some(trace)
                     }
                     Err(e) => {
@@ -273,23 +416,35 @@ fn worker(
             }
 
             // Get the list of root nodes
-            Instruction::GetRootActivities => {
+            Instruction::GetRootActivities(kinds) => {
                 let trace = expect(&mut trace);
-                reply(&activities, activity_list(trace.root_activities()))
+                reply(
+                    &activities,
+                    activity_list(trace.root_activities(), kinds.as_deref()),
+                )
             }
 
             // Get the list of all activities
             Instruction::GetAllActivities => {
                 let trace = expect(&mut trace);
-                reply(&activities, activity_list(trace.all_activities()))
+                reply(&activities, activity_list(trace.all_activities(), None))
             }
 
             // Get the list of a node's direct children
-            Instruction::GetDirectChildren(id) => {
+            Instruction::GetDirectChildren(id, kinds) => {
                 let trace = expect(&mut trace);
                 reply(
                     &activities,
-                    activity_list(trace.activity_trace(id).direct_children()),
+                    activity_list(trace.activity_trace(id).direct_children(), kinds.as_deref()),
+                )
+            }
+
+            // Get the list of a node's direct children, folding small ones
+            Instruction::GetFoldedChildren { id, threshold } => {
+                let trace = expect(&mut trace);
+                reply(
+                    &folded,
+                    folded_activity_list(&trace.activity_trace(id), threshold),
                 )
             }
 
@@ -298,32 +453,47 @@ fn worker(
                 let trace = expect(&mut trace);
                 reply(
                     &activities,
-                    activity_list(trace.activity_trace(id).all_children()),
+                    activity_list(trace.activity_trace(id).all_children(), None),
+                )
+            }
+
+            // Get the list of activities operating on matching file paths
+            Instruction::FindByFilePath { substring } => {
+                let trace = expect(&mut trace);
+                reply(
+                    &activities,
+                    find_by_file_path(trace, &mut parsed_arg_cache, &substring),
                 )
             }
 
-            // Describe a set of activities
-            Instruction::DescribeActivities {
+            // Stream descriptions of a set of activities
+            Instruction::StreamActivityDescriptions {
                 activities,
                 max_cols,
+                suffix,
+                sender,
+                canceled,
             } => {
-                // A screen width change invalidates the description cache
-                if max_cols != last_max_cols {
-                    description_cache.clear();
-                    last_max_cols = max_cols;
-                }
+                // A screen width or suffix change invalidates the description
+                // cache, but not the width-independent activity descriptions
+                update_description_format(
+                    &mut description_cache,
+                    (&mut last_max_cols, &mut last_suffix),
+                    (max_cols, suffix),
+                );
 
-                // Describe the requested activities
+                // A dropped stream is treated like a canceled one
                 let trace = expect(&mut trace);
-                reply(
-                    &strings,
-                    describe_activities(
-                        trace,
-                        &mut parsed_arg_cache,
-                        &mut description_cache,
-                        activities,
-                        max_cols,
-                    ),
+                stream_activity_descriptions(
+                    trace,
+                    &mut parsed_arg_cache,
+                    &mut activity_desc_cache,
+                    &mut description_cache,
+                    &activities,
+                    (max_cols, suffix),
+                    &display_options,
+                    &canceled,
+                    |id, desc| sender.send((id, desc)).is_ok(),
                 )
             }
 
@@ -342,6 +512,7 @@ fn worker(
                             header_cols: 0,
                             trailer_cols: 0,
                         },
+                        &display_options,
                     ),
                 )
             }
@@ -355,60 +526,182 @@ fn reply<T: Send + 'static>(sender: &Sender<T>, data: T) {
     sender.send(data).expect("Main thread has likely crashed")
 }
 
-/// Build a list of activities
-fn activity_list<'a>(iterator: impl Iterator<Item = ActivityTrace<'a>>) -> ActivityInfoList {
+/// Build a list of activities, optionally restricted to some activity kinds
+fn activity_list<'a>(
+    iterator: impl Iterator<Item = ActivityTrace<'a>>,
+    kinds: Option<&[Box<str>]>,
+) -> ActivityInfoList {
     iterator
+        .filter(|activity_trace| {
+            kinds.map_or(true, |kinds| {
+                let name = activity_trace.activity().name();
+                kinds.iter().any(|kind| &**kind == name)
+            })
+        })
         .map(|activity_trace| ActivityInfo {
             id: activity_trace.id(),
             duration: activity_trace.duration(),
             self_duration: activity_trace.self_duration(),
-            has_children: activity_trace.direct_children().next().is_some(),
+            is_leaf: activity_trace.is_leaf(),
         })
         .collect()
 }
 
-/// Describe a list of activities
-#[cfg_attr(
-    not(feature = "unstable_interner_stats"),
-    allow(clippy::let_and_return)
-)]
-fn describe_activities(
+/// List the direct children of an activity whose duration is above a
+/// threshold, folding the others into an aggregate
+fn folded_activity_list(parent: &ActivityTrace, threshold: Duration) -> FoldedActivityList {
+    let (hottest, folded) =
+        trace::fold_activities(parent.direct_children(), |a| a.duration(), threshold);
+    (activity_list(hottest.into_vec().into_iter(), None), folded)
+}
+
+/// List activities whose argument is a file path containing some substring
+fn find_by_file_path(
     trace: &mut ClangTrace,
     parsed_arg_cache: &mut HashMap<ActivityTraceId, ParsedActivityArgument>,
-    description_cache: &mut HashMap<ActivityTraceId, Arc<str>>,
-    activities: Box<[ActivityTraceId]>,
-    max_cols: u16,
-) -> ActivityDescList {
-    // Describe activities
-    let result = activities
-        .into_vec()
+    substring: &str,
+) -> ActivityInfoList {
+    // Only activities with a file path argument need to be considered
+    let candidates = trace
+        .all_activities()
+        .filter(|activity_trace| {
+            activity_trace.activity().raw_argument().arg_type()
+                == ActivityArgumentType::FilePathOrModule
+        })
+        .map(|activity_trace| activity_trace.id())
+        .collect::<Vec<_>>();
+
+    // Resolve their file paths and check them
+    let matches = candidates
         .into_iter()
-        .map(|activity| {
-            description_cache
+        .filter(|&activity| {
+            let parsed_arg = parsed_arg_cache
                 .entry(activity)
-                .or_insert_with(|| {
-                    let (desc, wrap) = describe_activity(
-                        trace,
-                        parsed_arg_cache,
-                        activity,
-                        DisplayConfig::SingleLine { max_cols },
-                    );
-                    assert!(
-                        !wrap,
-                        "Single-line activity descriptions should not be line-wrapped"
-                    );
-                    desc
-                })
-                .clone()
+                .or_insert_with(|| crate::ui::force_parse_arg(trace, activity));
+            match parsed_arg.resolve(trace) {
+                ActivityArgument::FilePath(path) => path.to_string().contains(substring),
+                _ => false,
+            }
         })
-        .collect();
+        .collect::<Vec<_>>();
+    activity_list(matches.into_iter().map(|id| trace.activity_trace(id)), None)
+}
+
+/// Clear the description cache if the description format changed
+fn update_description_format(
+    description_cache: &mut HashMap<ActivityTraceId, Arc<str>>,
+    (last_max_cols, last_suffix): (&mut u16, &mut Option<ProfileSuffix>),
+    (max_cols, suffix): (u16, Option<ProfileSuffix>),
+) {
+    if max_cols != *last_max_cols || suffix != *last_suffix {
+        description_cache.clear();
+        *last_max_cols = max_cols;
+        *last_suffix = suffix;
+    }
+}
+
+/// Describe a list of activities, emitting each description as soon as it is
+/// computed, until the emitter returns false or the cancelation flag is set
+#[allow(clippy::too_many_arguments)]
+fn stream_activity_descriptions(
+    trace: &mut ClangTrace,
+    parsed_arg_cache: &mut HashMap<ActivityTraceId, ParsedActivityArgument>,
+    activity_desc_cache: &mut HashMap<ActivityTraceId, Rc<ActivityDesc>>,
+    description_cache: &mut HashMap<ActivityTraceId, Arc<str>>,
+    activities: &[ActivityTraceId],
+    (max_cols, suffix): (u16, Option<ProfileSuffix>),
+    display_options: &ActivityDisplayOptions,
+    canceled: &AtomicBool,
+    mut emit: impl FnMut(ActivityTraceId, Arc<str>) -> bool,
+) {
+    // Use total clang execution time as a duration norm
+    let duration_norm = trace::duration_norm(trace.root_activities());
+
+    // Describe activities until done or canceled
+    for &activity in activities.iter() {
+        if canceled.load(Ordering::Relaxed) {
+            break;
+        }
+        let desc = describe_activity_line(
+            trace,
+            parsed_arg_cache,
+            activity_desc_cache,
+            description_cache,
+            activity,
+            (max_cols, suffix),
+            display_options,
+            duration_norm,
+        );
+        if !emit(activity, desc) {
+            break;
+        }
+    }
 
     // Conclude on new parser/interner usage after this transaction
     #[cfg(feature = "unstable_interner_stats")]
     trace.log_interner_usage();
+}
 
-    // Emit results
-    result
+/// Describe a single activity in one-line format, reusing cached descriptions
+#[allow(clippy::too_many_arguments)]
+fn describe_activity_line(
+    trace: &mut ClangTrace,
+    parsed_arg_cache: &mut HashMap<ActivityTraceId, ParsedActivityArgument>,
+    activity_desc_cache: &mut HashMap<ActivityTraceId, Rc<ActivityDesc>>,
+    description_cache: &mut HashMap<ActivityTraceId, Arc<str>>,
+    activity: ActivityTraceId,
+    (max_cols, suffix): (u16, Option<ProfileSuffix>),
+    display_options: &ActivityDisplayOptions,
+    duration_norm: Duration,
+) -> Arc<str> {
+    description_cache
+        .entry(activity)
+        .or_insert_with(|| {
+            // Reuse the width-independent description if available
+            let desc = activity_desc_cache
+                .entry(activity)
+                .or_insert_with(|| {
+                    Rc::new(describe_activity_width_independent(
+                        trace,
+                        parsed_arg_cache,
+                        activity,
+                    ))
+                })
+                .clone();
+
+            // Truncate it to the requested width
+            let mut output = Vec::new();
+            if let Some(suffix) = suffix {
+                let activity_trace = trace.activity_trace(activity);
+                let duration = match suffix {
+                    ProfileSuffix::Duration => activity_trace.duration(),
+                };
+                display_activity(
+                    &mut output,
+                    &desc,
+                    max_cols,
+                    duration,
+                    duration_norm,
+                    DurationFormat::Scaled,
+                    display_options.clone(),
+                )
+                .expect("IO to a buffer shouldn't fail");
+            } else {
+                match desc.display(&mut output, max_cols, display_options.clone()) {
+                    Ok(()) => {}
+                    Err(ActivityDescError::NotEnoughCols(_)) => {
+                        write!(output, "…").expect("IO to a buffer shouldn't fail")
+                    }
+                    Err(ActivityDescError::IoError(e)) => {
+                        unreachable!("IO to a buffer shouldn't fail, but failed with error {e}")
+                    }
+                }
+            }
+            std::str::from_utf8(&output[..])
+                .expect("Activity descriptions should be UTF-8")
+                .into()
+        })
+        .clone()
 }
 
 /// Describe a single activity, return the description string along with the
@@ -419,6 +712,7 @@ fn describe_activity<OwnedStr: for<'a> From<&'a str> + 'static>(
     parsed_arg_cache: &mut HashMap<ActivityTraceId, ParsedActivityArgument>,
     activity: ActivityTraceId,
     config: DisplayConfig,
+    display_options: &ActivityDisplayOptions,
 ) -> (OwnedStr, bool) {
     // Have we parsed that activity's argument previously ?
     let parsed_arg = parsed_arg_cache
@@ -433,6 +727,7 @@ fn describe_activity<OwnedStr: for<'a> From<&'a str> + 'static>(
         activity_trace.activity().id(),
         &parsed_arg.resolve(trace),
         config,
+        display_options.clone(),
     ) {
         Ok(wrap) => wrap,
         Err(ActivityDescError::NotEnoughCols(_)) => {
@@ -447,4 +742,331 @@ fn describe_activity<OwnedStr: for<'a> From<&'a str> + 'static>(
     (OwnedStr::from(output), wrap)
 }
 
-// FIXME: Add tests
+/// Build a width-independent description of a single activity
+fn describe_activity_width_independent(
+    trace: &mut ClangTrace,
+    parsed_arg_cache: &mut HashMap<ActivityTraceId, ParsedActivityArgument>,
+    activity: ActivityTraceId,
+) -> ActivityDesc {
+    // Have we parsed that activity's argument previously ?
+    let parsed_arg = parsed_arg_cache
+        .entry(activity)
+        .or_insert_with(|| crate::ui::force_parse_arg(trace, activity));
+
+    // Resolve the argument and render it
+    let activity_trace = trace.activity_trace(activity);
+    ActivityDesc::new(trace, activity_trace.activity().id(), parsed_arg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::with_test_trace;
+    use unicode_width::UnicodeWidthStr;
+
+    /// Start a processing thread and load the test trace into it
+    fn start_with_test_trace() -> ProcessingThread {
+        let mut processing_thread = ProcessingThread::start(ActivityDisplayOptions::default());
+        let (sender, receiver) = mpsc::channel();
+        processing_thread.start_load_trace(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/7-GMSTests_main.json"),
+            move |result| sender.send(result.is_ok()).unwrap(),
+        );
+        assert!(receiver.recv().unwrap(), "Failed to load the test trace");
+        processing_thread
+    }
+
+    /// Describe a list of activities, as the processing thread would
+    fn describe_activities(
+        trace: &mut ClangTrace,
+        parsed_arg_cache: &mut HashMap<ActivityTraceId, ParsedActivityArgument>,
+        activity_desc_cache: &mut HashMap<ActivityTraceId, Rc<ActivityDesc>>,
+        description_cache: &mut HashMap<ActivityTraceId, Arc<str>>,
+        activities: Box<[ActivityTraceId]>,
+        max_cols: u16,
+        suffix: Option<ProfileSuffix>,
+    ) -> ActivityDescList {
+        let mut descs = Vec::with_capacity(activities.len());
+        super::stream_activity_descriptions(
+            trace,
+            parsed_arg_cache,
+            activity_desc_cache,
+            description_cache,
+            &activities,
+            (max_cols, suffix),
+            &ActivityDisplayOptions::default(),
+            &AtomicBool::new(false),
+            |_id, desc| {
+                descs.push(desc);
+                true
+            },
+        );
+        descs.into()
+    }
+
+    #[test]
+    fn describe_activities_with_profile() {
+        with_test_trace(|trace| {
+            let activities = trace
+                .all_activities()
+                .map(|activity_trace| activity_trace.id())
+                .collect::<Box<[_]>>();
+            for max_cols in [1, 10, 30, 80, 200] {
+                for suffix in [None, Some(ProfileSuffix::Duration)] {
+                    let descs = describe_activities(
+                        trace,
+                        &mut HashMap::new(),
+                        &mut HashMap::new(),
+                        &mut HashMap::new(),
+                        activities.clone(),
+                        max_cols,
+                        suffix,
+                    );
+                    assert_eq!(descs.len(), activities.len());
+                    for desc in descs.iter() {
+                        assert!(
+                            desc.width() <= usize::from(max_cols),
+                            "Description {desc:?} does not fit in {max_cols} columns"
+                        );
+                        if max_cols == 200 && suffix.is_some() {
+                            assert!(
+                                desc.ends_with("%]"),
+                                "Description {desc:?} lacks a profiling suffix"
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn stream_activity_descriptions() {
+        let processing_thread = start_with_test_trace();
+        let activities = processing_thread
+            .get_all_activities()
+            .iter()
+            .map(|info| info.id)
+            .collect::<Box<[_]>>();
+        let suffix = Some(ProfileSuffix::Duration);
+        let expected = with_test_trace(|trace| {
+            describe_activities(
+                trace,
+                &mut HashMap::new(),
+                &mut HashMap::new(),
+                &mut HashMap::new(),
+                activities.clone(),
+                80,
+                suffix,
+            )
+        });
+
+        // Descriptions are received one by one, in request order
+        let mut stream =
+            processing_thread.stream_activity_descriptions(activities.clone(), 80, suffix);
+        for (&id, expected) in activities.iter().zip(expected.iter()) {
+            assert_eq!(stream.next(), Some((id, expected.clone())));
+        }
+        assert_eq!(stream.next(), None);
+
+        // The processing thread keeps handling requests afterwards
+        assert_eq!(
+            processing_thread.describe_activities(activities, 80, suffix),
+            expected
+        );
+    }
+
+    #[test]
+    fn cancel_activity_descriptions() {
+        with_test_trace(|trace| {
+            let activities = trace
+                .all_activities()
+                .map(|activity| activity.id())
+                .collect::<Box<[_]>>();
+
+            // Describe activities, stopping after a few of them either by
+            // setting the cancelation flag or by refusing further descriptions
+            const NUM_EMITTED: usize = 3;
+            for use_flag in [true, false] {
+                let canceled = AtomicBool::new(false);
+                let mut emitted = Vec::new();
+                super::stream_activity_descriptions(
+                    trace,
+                    &mut HashMap::new(),
+                    &mut HashMap::new(),
+                    &mut HashMap::new(),
+                    &activities,
+                    (80, None),
+                    &ActivityDisplayOptions::default(),
+                    &canceled,
+                    |id, _desc| {
+                        emitted.push(id);
+                        if emitted.len() < NUM_EMITTED {
+                            true
+                        } else if use_flag {
+                            canceled.store(true, Ordering::Relaxed);
+                            true
+                        } else {
+                            false
+                        }
+                    },
+                );
+
+                // No description is rendered after cancelation
+                assert_eq!(emitted, activities[..NUM_EMITTED]);
+            }
+        });
+    }
+
+    #[test]
+    fn cancel_activity_description_stream() {
+        let processing_thread = start_with_test_trace();
+        let activities = processing_thread
+            .get_all_activities()
+            .iter()
+            .map(|info| info.id)
+            .collect::<Box<[_]>>();
+        let mut stream =
+            processing_thread.stream_activity_descriptions(activities.clone(), 80, None);
+
+        // Receive a few descriptions, then cancel the stream
+        const NUM_RECEIVED: usize = 3;
+        for &id in activities.iter().take(NUM_RECEIVED) {
+            assert_eq!(stream.next().map(|(id, _)| id), Some(id));
+        }
+        stream.cancel();
+
+        // Descriptions that were already sent can still be received, in order,
+        // after which the stream ends. How many of them there are depends on
+        // how far the processing thread got, which cancel_activity_descriptions
+        // checks deterministically.
+        for ((id, _), &expected_id) in stream.zip(activities.iter().skip(NUM_RECEIVED)) {
+            assert_eq!(id, expected_id);
+        }
+
+        // The processing thread keeps handling requests afterwards
+        assert_eq!(
+            processing_thread
+                .describe_activities(activities[..NUM_RECEIVED].into(), 80, None)
+                .len(),
+            NUM_RECEIVED
+        );
+    }
+
+    #[test]
+    fn activity_list_kinds() {
+        with_test_trace(|trace| {
+            let root = trace
+                .root_activities()
+                .next()
+                .expect("Test trace should have a root activity");
+            let all_children = activity_list(root.direct_children(), None);
+            assert_eq!(all_children.len(), root.direct_children().count());
+
+            let kinds: ActivityKinds = vec!["Frontend".into()].into();
+            let frontend_children = activity_list(root.direct_children(), Some(&kinds));
+            assert!(!frontend_children.is_empty());
+            assert!(frontend_children.len() < all_children.len());
+            for info in frontend_children.iter() {
+                assert_eq!(trace.activity_trace(info.id).activity().name(), "Frontend");
+            }
+
+            let no_kinds: ActivityKinds = Box::new([]);
+            assert!(activity_list(root.direct_children(), Some(&no_kinds)).is_empty());
+        });
+    }
+
+    #[test]
+    fn activity_list_leaves() {
+        with_test_trace(|trace| {
+            let infos = activity_list(trace.all_activities(), None);
+            assert!(infos.iter().any(|info| info.is_leaf));
+            assert!(infos.iter().any(|info| !info.is_leaf));
+            for info in infos.iter() {
+                let activity_trace = trace.activity_trace(info.id);
+                assert_eq!(info.is_leaf, activity_trace.direct_children().count() == 0);
+            }
+        });
+    }
+
+    #[test]
+    fn folded_activity_list() {
+        with_test_trace(|trace| {
+            let frontend = trace
+                .find_first("Frontend")
+                .expect("Test trace should have a frontend activity");
+            let threshold = 0.01 * frontend.duration();
+            let (children, folded) = super::folded_activity_list(&frontend, threshold);
+            let folded = folded.expect("Frontend should have small children");
+            assert_eq!(
+                children.len() + folded.count,
+                frontend.direct_children().count()
+            );
+            assert!(children.iter().all(|info| info.duration >= threshold));
+            let children_duration = children.iter().map(|info| info.duration).sum::<Duration>();
+            assert!(
+                (children_duration + folded.duration
+                    - (frontend.duration() - frontend.self_duration()))
+                .abs()
+                    < 1e-6 * frontend.duration()
+            );
+        });
+    }
+
+    #[test]
+    fn find_by_file_path() {
+        with_test_trace(|trace| {
+            let matches = super::find_by_file_path(trace, &mut HashMap::new(), "features.h");
+            assert_eq!(matches.len(), 1);
+            let activity_trace = trace.activity_trace(matches[0].id);
+            assert_eq!(activity_trace.activity().name(), "Source");
+            assert_eq!(
+                activity_trace.activity().raw_argument().detail().as_deref(),
+                Some("/usr/include/features.h")
+            );
+            assert_eq!(matches[0].duration, activity_trace.duration());
+
+            let no_match = super::find_by_file_path(trace, &mut HashMap::new(), "no_such_file");
+            assert!(no_match.is_empty());
+        });
+    }
+
+    #[test]
+    fn describe_activities_resolves_once() {
+        with_test_trace(|trace| {
+            let activities = trace
+                .all_activities()
+                .map(|activity_trace| activity_trace.id())
+                .collect::<Box<[_]>>();
+            let mut parsed_arg_cache = HashMap::new();
+            let mut activity_desc_cache = HashMap::new();
+            let mut describe = |trace: &mut ClangTrace, max_cols, suffix| {
+                describe_activities(
+                    trace,
+                    &mut parsed_arg_cache,
+                    &mut activity_desc_cache,
+                    &mut HashMap::new(),
+                    activities.clone(),
+                    max_cols,
+                    suffix,
+                );
+                activity_desc_cache.clone()
+            };
+
+            // Width-independent descriptions are built on first request...
+            let initial_descs = describe(trace, 80, None);
+            assert_eq!(initial_descs.len(), activities.len());
+
+            // ...and reused for all subsequent widths
+            for max_cols in [1, 30, 120, 200] {
+                for suffix in [None, Some(ProfileSuffix::Duration)] {
+                    let descs = describe(trace, max_cols, suffix);
+                    assert_eq!(descs.len(), initial_descs.len());
+                    for (id, desc) in descs.iter() {
+                        assert!(Rc::ptr_eq(desc, &initial_descs[id]));
+                    }
+                }
+            }
+        });
+    }
+}
@@ -1,11 +1,14 @@
 //! Interactive UI for loading and displaying trace profiles
 
-use crate::ui::{
-    display::duration::display_duration,
-    tui::{
-        names::ViewName::{ActivityDescPrefix, ActivityTablePrefix},
-        processing::{ActivityDescList, ActivityInfo, ActivityInfoList},
-        with_state, State,
+use crate::{
+    trace::FoldedActivities,
+    ui::{
+        display::duration::TraceDuration,
+        tui::{
+            names::ViewName::{ActivityDescPrefix, ActivityTablePrefix},
+            processing::{ActivityDescList, ActivityInfoList, FoldedActivityList},
+            with_state, State,
+        },
     },
 };
 use clang_time_trace::{ActivityTraceId, Duration};
@@ -97,6 +100,9 @@ pub struct ProfileLayer {
     /// Name of the entity being profiled, for display use
     parent_name: Rc<str>,
 
+    /// Activity being profiled, if the profile is about a single activity
+    parent_id: Option<ActivityTraceId>,
+
     /// Identifier of the table widget displaying the profile
     table_name: Rc<str>,
 
@@ -112,6 +118,11 @@ impl ProfileLayer {
     pub fn parent_name(&self) -> &str {
         &self.parent_name
     }
+
+    /// Activity being profiled, if the profile is about a single activity
+    pub fn parent_id(&self) -> Option<ActivityTraceId> {
+        self.parent_id
+    }
 }
 //
 /// Kind of profile that this app can display
@@ -152,28 +163,32 @@ impl ProfileDisplay {
 /// Display a hierarchical profile (see show_profile for parameters docs)
 pub(super) fn show_hierarchical_profile(
     cursive: &mut Cursive,
-    parent_name: Rc<str>,
+    (parent_name, parent_id): (Rc<str>, Option<ActivityTraceId>),
     parent_percent_norm: Finite<Duration>,
-    activity_infos: ActivityInfoList,
+    activities: FoldedActivityList,
     get_flat_activities: impl 'static + FnOnce(&mut State) -> ActivityInfoList,
 ) {
     show_profile(
         cursive,
-        parent_name,
+        (parent_name, parent_id),
         parent_percent_norm,
-        activity_infos,
-        Box::new(get_flat_activities),
+        activities,
+        Box::new(move |state| (get_flat_activities(state), None)),
         ProfileKind::Hierarchical,
     );
 }
 
 /// Display a trace profile
+///
+/// Activities which were folded into an aggregate, if any, are displayed as a
+/// single "(other)" row of the profile.
+///
 fn show_profile(
     cursive: &mut Cursive,
-    parent_name: Rc<str>,
+    parent: (Rc<str>, Option<ActivityTraceId>),
     parent_percent_norm: Finite<Duration>,
-    activity_infos: ActivityInfoList,
-    get_other_activities: Box<dyn 'static + FnOnce(&mut State) -> ActivityInfoList>,
+    activities: FoldedActivityList,
+    get_other_activities: Box<dyn 'static + FnOnce(&mut State) -> FoldedActivityList>,
     kind: ProfileKind,
 ) {
     // Check terminal dimensions
@@ -185,10 +200,10 @@ fn show_profile(
     let (display_config, activity_data, footer_str, profile_id, table_name) = register_profile(
         cursive,
         terminal_width,
-        parent_name,
+        parent,
         parent_percent_norm,
         kind,
-        activity_infos,
+        activities,
         desc_col_width,
     );
 
@@ -238,10 +253,10 @@ fn show_profile(
 fn register_profile(
     cursive: &mut Cursive,
     terminal_width: u16,
-    parent_name: Rc<str>,
+    (parent_name, parent_id): (Rc<str>, Option<ActivityTraceId>),
     parent_percent_norm: Finite<Duration>,
     kind: ProfileKind,
-    activity_infos: ActivityInfoList,
+    activities: FoldedActivityList,
     description_width: u16,
 ) -> (ProfileDisplay, ActivityData, String, usize, Rc<str>) {
     with_state(cursive, |state| {
@@ -270,8 +285,9 @@ fn register_profile(
 
         // Generate activity descriptions of the right width
         let activity_descs = state.processing_thread.describe_activities(
-            activity_infos.iter().map(|info| info.id).collect(),
+            activities.0.iter().map(|info| info.id).collect(),
             description_width,
+            None,
         );
 
         // Register this new layer in the profile stack
@@ -279,6 +295,7 @@ fn register_profile(
         let table_name: Rc<str> = format!("{}{profile_id}", ActivityTablePrefix.as_ref()).into();
         state.profile_stack.push(ProfileLayer {
             parent_name,
+            parent_id,
             table_name: table_name.clone(),
             parent_percent_norm,
             kind,
@@ -290,7 +307,7 @@ fn register_profile(
         // Bubble up useful data for following steps
         (
             state.display_config,
-            (activity_infos, activity_descs),
+            (activities, activity_descs),
             footer,
             profile_id,
             table_name,
@@ -299,20 +316,20 @@ fn register_profile(
 }
 
 /// Alias used to simplify passing arround activity infos + descriptions
-type ActivityData = (ActivityInfoList, ActivityDescList);
+type ActivityData = (FoldedActivityList, ActivityDescList);
 
 /// Set up the tabular view that is the heart of a profile
 fn make_profile_view(
     table_name: Rc<str>,
     kind: ProfileKind,
-    (activity_infos, activity_descs): ActivityData,
-    get_other_activities: Box<dyn 'static + FnOnce(&mut State) -> ActivityInfoList>,
+    (activities, activity_descs): ActivityData,
+    get_other_activities: Box<dyn 'static + FnOnce(&mut State) -> FoldedActivityList>,
     description_width: u16,
     display_config: ProfileDisplay,
-    update_description: impl Fn(&mut Cursive, ActivityTraceId) + 'static,
-) -> (impl View, ActivityTraceId) {
+    update_description: impl Fn(&mut Cursive, Option<ActivityTraceId>) + 'static,
+) -> (impl View, Option<ActivityTraceId>) {
     // Set up the children activity table
-    let items = make_profile_data(kind, &activity_infos, activity_descs);
+    let items = make_profile_data(kind, &activities, activity_descs);
     let update_desc = select(table_name.clone(), update_description);
     let mut table = ProfileView::new()
         .items(items)
@@ -341,23 +358,23 @@ fn make_profile_view(
     let table = table.with_name(table_name.to_string());
 
     // Let F shortcut toggle between hierarchical and flat profile
-    let once_state = RefCell::new(Some((activity_infos, get_other_activities)));
+    let once_state = RefCell::new(Some((activities, get_other_activities)));
     let view = OnEventView::new(table).on_event('f', move |cursive| {
         let kind = match kind {
             ProfileKind::Hierarchical => ProfileKind::Flat,
             ProfileKind::Flat => ProfileKind::Hierarchical,
         };
-        let (old_activity_infos, get_other_activities) = once_state
+        let (old_activities, get_other_activities) = once_state
             .borrow_mut()
             .take()
             .expect("This callback may only be called once, after that the view is destroyed");
-        let (parent_name, parent_percent_norm, new_activity_infos) = with_state(cursive, |state| {
+        let (parent, parent_percent_norm, new_activities) = with_state(cursive, |state| {
             let layer = state
                 .profile_stack
                 .pop()
                 .expect("There should be a profile if this shortcut works");
             (
-                layer.parent_name,
+                (layer.parent_name, layer.parent_id),
                 layer.parent_percent_norm,
                 get_other_activities(state),
             )
@@ -365,10 +382,10 @@ fn make_profile_view(
         cursive.pop_layer();
         show_profile(
             cursive,
-            parent_name,
+            parent,
             parent_percent_norm,
-            new_activity_infos,
-            Box::new(|_state| old_activity_infos),
+            new_activities,
+            Box::new(|_state| old_activities),
             kind,
         )
     });
@@ -380,7 +397,7 @@ fn make_profile_view(
 /// on_select callback for profiles that updates the description pane
 fn select(
     table_name: Rc<str>,
-    update_desc: impl Fn(&mut Cursive, ActivityTraceId) + 'static,
+    update_desc: impl Fn(&mut Cursive, Option<ActivityTraceId>) + 'static,
 ) -> impl Fn(&mut Cursive, usize, usize) + 'static {
     move |cursive, _row, index| {
         // Access the hierarchical profile's table to check the selected
@@ -413,7 +430,7 @@ fn zoom(table_name: Rc<str>) -> impl Fn(&mut Cursive, usize, usize) + 'static {
                 // will want to strip. If it does not start with a '+', then
                 // this activity has no children and cannot be zoomed on.
                 let stripped_description = activity.description.strip_prefix('+')?;
-                Some((activity.id, stripped_description.into(), activity.duration))
+                Some((activity.id?, stripped_description.into(), activity.duration))
             })
             .expect("Failed to access trace profile view");
         let (activity_trace_id, stripped_description, activity_duration) = match activity {
@@ -421,21 +438,34 @@ fn zoom(table_name: Rc<str>) -> impl Fn(&mut Cursive, usize, usize) + 'static {
             None => return,
         };
 
-        // Query the activity's direct children
-        let activity_children = with_state(cursive, |state| {
-            state
-                .processing_thread
-                .get_direct_children(activity_trace_id)
+        // Query the activity's direct children, folding the small ones into an
+        // aggregate if the user asked for it
+        let activity_children = with_state(cursive, |state| match state.fold_threshold {
+            Some(threshold) => {
+                let global_percent_norm = state
+                    .global_percent_norm
+                    .expect("Global percent norm should be set at this point");
+                state.processing_thread.get_folded_children(
+                    activity_trace_id,
+                    threshold / global_percent_norm.into_inner(),
+                )
+            }
+            None => (
+                state
+                    .processing_thread
+                    .get_direct_children(activity_trace_id, None),
+                None,
+            ),
         });
         assert!(
-            !activity_children.is_empty(),
+            !activity_children.0.is_empty() || activity_children.1.is_some(),
             "If the activity had no children, early exit should have occured"
         );
 
         // Show a hierarchical profile of this activity
         show_hierarchical_profile(
             cursive,
-            stripped_description,
+            (stripped_description, Some(activity_trace_id)),
             super::percent_norm(activity_duration),
             activity_children,
             Box::new(move |state: &mut State| {
@@ -660,8 +690,8 @@ impl HierarchicalColumnName {
 /// Row of hierarchical profile data
 #[derive(Clone, Debug)]
 struct HierarchicalData {
-    /// Activity identifier
-    id: ActivityTraceId,
+    /// Activity identifier, or None for the aggregate of folded activities
+    id: Option<ActivityTraceId>,
 
     /// Time spent processing this activity or one of its callees
     duration: Duration,
@@ -675,11 +705,6 @@ struct HierarchicalData {
 //
 impl TableViewItem<HierarchicalColumn> for HierarchicalData {
     fn to_column(&self, column: HierarchicalColumn) -> String {
-        let format_duration = |duration| {
-            let mut buffer = Vec::<u8>::new();
-            display_duration(&mut buffer, duration).expect("Writing to a buffer shouldn't fail");
-            String::from_utf8(buffer).expect("display_duration should produce UTF-8 data")
-        };
         match column {
             HierarchicalColumn::Duration(kind, display) => {
                 let data = match kind {
@@ -687,10 +712,10 @@ impl TableViewItem<HierarchicalColumn> for HierarchicalData {
                     DurationKind::Myself => self.self_duration,
                 };
                 match display {
-                    DurationDisplay::Time => format_duration(data),
-                    DurationDisplay::Percentage(norm, _reference) => {
-                        format!("{:.2}%", data * norm.into_inner())
-                    }
+                    DurationDisplay::Time => TraceDuration(data).to_string(),
+                    DurationDisplay::Percentage(norm, _reference) => TraceDuration(data)
+                        .percentage(norm.into_inner())
+                        .to_string(),
                 }
             }
             HierarchicalColumn::Description => self.description.clone().into(),
@@ -789,7 +814,7 @@ fn make_description_view(
     profile_id: usize,
 ) -> (
     impl View + 'static,
-    impl Fn(&mut Cursive, ActivityTraceId) + Clone + 'static,
+    impl Fn(&mut Cursive, Option<ActivityTraceId>) + Clone + 'static,
 ) {
     // Give the activity description panel a name that's unique within the
     // current overall cursive display
@@ -826,12 +851,15 @@ fn make_description_view(
     );
 
     // Set up the callback to update the description view
-    let update_desc = move |cursive: &mut Cursive, activity: ActivityTraceId| {
-        let (desc, wrap) = with_state(cursive, |state| {
-            state
-                .processing_thread
-                .describe_activity(activity, terminal_width)
-        });
+    let update_desc = move |cursive: &mut Cursive, activity: Option<ActivityTraceId>| {
+        let (desc, wrap) = match activity {
+            Some(activity) => with_state(cursive, |state| {
+                state
+                    .processing_thread
+                    .describe_activity(activity, terminal_width)
+            }),
+            None => (FOLDED_DESCRIPTION.to_owned(), true),
+        };
         cursive
             .call_on_name(&name, |view: &mut TextView| {
                 view.set_content(desc);
@@ -860,42 +888,50 @@ fn make_footer_view(terminal_width: u16, mut footer_str: String) -> impl View +
     TextView::new(footer_str).center().no_wrap()
 }
 
+/// Description of the "(other)" row, which aggregates folded activities
+const FOLDED_DESCRIPTION: &str = "Activities whose duration is below the \
+                                  folding threshold, merged into a single row";
+
 /// Construct the tabular data used by the profile view
 fn make_profile_data(
     profile_kind: ProfileKind,
-    activity_infos: &[ActivityInfo],
+    (activity_infos, folded): &(ActivityInfoList, Option<FoldedActivities>),
     activity_descs: ActivityDescList,
 ) -> Vec<HierarchicalData> {
+    // Hierarchical profiles have little "+" indicators that tell which
+    // activities have children and can be zoomed, flat profiles just display
+    // the raw activity description
+    let describe = |is_leaf: bool, description: &str| -> Box<str> {
+        match profile_kind {
+            ProfileKind::Hierarchical => {
+                let mut buf = String::new();
+                if !is_leaf {
+                    buf.push('+');
+                } else {
+                    buf.push(' ');
+                }
+                buf.push_str(description);
+                buf.into_boxed_str()
+            }
+            ProfileKind::Flat => description.into(),
+        }
+    };
+
+    // Activities are displayed first, followed by the folded aggregate if any
     activity_infos
         .iter()
         .zip(activity_descs.into_vec())
-        .map(|(activity_info, description)| {
-            let description = match profile_kind {
-                // Hierarchical profiles have little "+" indicators that tell
-                // which activities have children and can be zoomed
-                ProfileKind::Hierarchical => {
-                    let mut buf = String::new();
-                    if activity_info.has_children {
-                        buf.push('+');
-                    } else {
-                        buf.push(' ');
-                    }
-                    buf.push_str(&description);
-                    buf.into_boxed_str()
-                }
-
-                // Flat profiles just display the raw activity description
-                ProfileKind::Flat => {
-                    let description_str: &str = &description;
-                    description_str.into()
-                }
-            };
-            HierarchicalData {
-                id: activity_info.id,
-                duration: activity_info.duration,
-                self_duration: activity_info.self_duration,
-                description,
-            }
+        .map(|(activity_info, description)| HierarchicalData {
+            id: Some(activity_info.id),
+            duration: activity_info.duration,
+            self_duration: activity_info.self_duration,
+            description: describe(activity_info.is_leaf, &description),
         })
+        .chain(folded.iter().map(|folded| HierarchicalData {
+            id: None,
+            duration: folded.duration,
+            self_duration: folded.self_duration,
+            description: describe(true, &format!("(other ×{})", folded.count)),
+        }))
         .collect()
 }
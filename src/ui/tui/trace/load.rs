@@ -1,7 +1,6 @@
 //! Interactive UI for loading trace profiles
 
 use crate::ui::tui::{processing::ProcessingThread, trace, with_state};
-use clang_time_trace::Duration;
 use cursive::{views::Dialog, Cursive};
 use std::{
     num::NonZeroU32,
@@ -33,22 +32,20 @@ pub fn show_loader(cursive: &mut Cursive, trace_path: impl AsRef<Path>) {
                         end_wait_for_input(cursive, wait_state);
 
                         // Handle trace loading errors
-                        if let Err(error) = result {
-                            cursive.add_layer(Dialog::info(format!(
-                                "Failed to process input: {error}"
-                            )));
-                            return;
-                        }
+                        let summary = match result {
+                            Ok(summary) => summary,
+                            Err(error) => {
+                                cursive.add_layer(Dialog::info(format!(
+                                    "Failed to process input: {error}"
+                                )));
+                                return;
+                            }
+                        };
 
                         // Query the list of root activities and deduce the global percentage norm
                         let (root_activities, global_percent_norm) = with_state(cursive, |state| {
-                            let root_activities = state.processing_thread.get_root_activities();
-                            let global_percent_norm = super::percent_norm(
-                                root_activities
-                                    .iter()
-                                    .map(|activity| activity.duration)
-                                    .sum::<Duration>(),
-                            );
+                            let root_activities = state.processing_thread.get_root_activities(None);
+                            let global_percent_norm = super::percent_norm(summary.root_duration);
                             state.global_percent_norm = Some(global_percent_norm);
                             state.display_config.reset_duration_display();
                             (root_activities, global_percent_norm)
@@ -57,9 +54,9 @@ pub fn show_loader(cursive: &mut Cursive, trace_path: impl AsRef<Path>) {
                         // Display the hierarchical profile
                         trace::display::show_hierarchical_profile(
                             cursive,
-                            "<profile root>".into(),
+                            ("<profile root>".into(), None),
                             global_percent_norm,
-                            root_activities,
+                            (root_activities, None),
                             |state| state.processing_thread.get_all_activities(),
                         );
                     }))
@@ -116,7 +113,8 @@ fn start_wait_for_input(cursive: &mut Cursive) -> (WaitForInputState, Arc<Atomic
                 cursive.quit();
             } else {
                 with_state(cursive, |state| {
-                    state.processing_thread = ProcessingThread::start();
+                    state.processing_thread =
+                        ProcessingThread::start(state.display_options.clone());
                 });
             }
         }
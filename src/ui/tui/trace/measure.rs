@@ -53,10 +53,10 @@ pub fn show_wizard(
 
     // Determine clang time-trace output file name
     let mut output = match entry.output() {
-        Some(output) => output,
-        None => {
+        Ok(output) => output,
+        Err(e) => {
             cursive.add_layer(Dialog::info(format!(
-                "Failed to extract output file from compilation command {:?}",
+                "Failed to extract output file from compilation command {:?}: {e}",
                 entry.raw_command()
             )));
             return;
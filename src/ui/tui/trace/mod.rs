@@ -3,11 +3,15 @@
 pub mod display;
 pub mod load;
 pub mod measure;
+pub mod search;
 
 use clang_time_trace::Duration;
 use decorum::Finite;
 
 /// Compute the percentage norm associated with a set of activities
+///
+/// This is the inverse of their total duration, as expected by
+/// `TraceDuration::percentage()`.
 fn percent_norm(total_duration: Duration) -> Finite<Duration> {
-    Finite::<Duration>::from_inner(100.0 / total_duration)
+    Finite::<Duration>::from_inner(1.0 / total_duration)
 }
@@ -0,0 +1,64 @@
+//! Interactive UI for searching activities in trace profiles
+
+use crate::ui::tui::{names::ViewName::GlobalDialog, trace::display, with_state};
+use cursive::{
+    view::{Nameable, Resizable},
+    views::{Dialog, EditView},
+    Cursive,
+};
+
+/// Width of the file path search box
+const SEARCH_WIDTH: usize = 40;
+
+/// Dialog that asks for a file path substring, then displays a profile of the
+/// activities operating on files whose path contains that substring
+pub fn search_dialog(cursive: &mut Cursive) -> Option<Dialog> {
+    // This shortcut is specific to profile views
+    if !display::is_profiling(cursive) {
+        return None;
+    }
+    Some(
+        Dialog::around(
+            EditView::new()
+                .on_submit(show_matches)
+                .fixed_width(SEARCH_WIDTH),
+        )
+        .title("Search file path")
+        .dismiss_button("Cancel"),
+    )
+}
+
+/// on_submit callback of the search dialog
+fn show_matches(cursive: &mut Cursive, substring: &str) {
+    // Close the search dialog
+    cursive.pop_layer();
+
+    // Search activities operating on matching files
+    let (matches, global_percent_norm) = with_state(cursive, |state| {
+        (
+            state.processing_thread.find_by_file_path(substring),
+            state
+                .global_percent_norm
+                .expect("Global percent norm should be set at this point"),
+        )
+    });
+    if matches.is_empty() {
+        cursive.add_layer(
+            Dialog::info(format!(
+                "No activity operates on a file whose path contains {substring:?}"
+            ))
+            .with_name(GlobalDialog.as_ref()),
+        );
+        return;
+    }
+
+    // Display a hierarchical profile of the matching activities
+    let substring: Box<str> = substring.into();
+    display::show_hierarchical_profile(
+        cursive,
+        (format!("<files matching {substring:?}>").into(), None),
+        global_percent_norm,
+        (matches, None),
+        move |state| state.processing_thread.find_by_file_path(substring),
+    );
+}
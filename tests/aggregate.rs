@@ -0,0 +1,69 @@
+//! Integration test of whole-build-directory profiling
+
+use std::{fs, path::Path, process::Command};
+
+/// Write a tiny time-trace where a compilation unit spends `source_us`
+/// microseconds out of 1000 processing a common header
+fn write_trace(path: &Path, source_us: u32) {
+    fs::write(
+        path,
+        format!(
+            r#"{{
+    "traceEvents": [
+        {{ "ph": "X", "pid": 1, "tid": 0, "ts": 110, "dur": {source_us}, "name": "Source", "args": {{ "detail": "/src/common.h" }} }},
+        {{ "ph": "X", "pid": 1, "tid": 0, "ts": 10, "dur": 1000, "name": "ExecuteCompiler" }},
+        {{ "ph": "M", "pid": 1, "tid": 0, "ts": 0, "cat": "", "name": "process_name", "args": {{ "name": "clang-14.0.0" }} }}
+    ]
+}}"#
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn aggregate() {
+    // Set up a build directory with two compilation units and their traces
+    let build_dir = tempfile::tempdir().unwrap();
+    let build_dir = build_dir.path();
+    let mut database = Vec::new();
+    for name in ["a", "b"] {
+        let input = build_dir.join(format!("{name}.cpp"));
+        fs::write(&input, "int main() {}\n").unwrap();
+        database.push(format!(
+            r#"{{"directory": "{}", "command": "not-a-compiler -c {name}.cpp -o {name}.o", "file": "{}"}}"#,
+            build_dir.display(),
+            input.display()
+        ));
+    }
+    fs::write(
+        build_dir.join("compile_commands.json"),
+        format!("[{}]", database.join(",")),
+    )
+    .unwrap();
+    write_trace(&build_dir.join("a.json"), 600);
+    write_trace(&build_dir.join("b.json"), 200);
+
+    // Profile the build directory
+    let output = Command::new(env!("CARGO_BIN_EXE_crofiler"))
+        .arg("aggregate")
+        .arg(build_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Check the aggregate profile
+    assert!(stdout.contains("Aggregated 2 time-trace(s)"), "{stdout}");
+    assert!(
+        stdout.contains("- ExecuteCompiler [1.20ms, 60.00%] in 2 trace(s)"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("- Source [800µs, 40.00%] in 2 trace(s)"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("- Source(/src/common.h) [800µs, 40.00%] in 2 trace(s)"),
+        "{stdout}"
+    );
+}